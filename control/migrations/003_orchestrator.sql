@@ -106,6 +106,3 @@ CREATE TABLE IF NOT EXISTS orchestrator_action (
 
 CREATE INDEX IF NOT EXISTS idx_orch_action_status_retry
     ON orchestrator_action(status, next_retry_at);
-
-CREATE INDEX IF NOT EXISTS idx_orch_action_runtime_op
-    ON orchestrator_action(runtime_operation_id);
@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS nodes (
+    node_id TEXT PRIMARY KEY,
+    hostname TEXT NOT NULL,
+    host_ip TEXT NOT NULL,
+    subnet TEXT NOT NULL UNIQUE,
+    cpu_cores INTEGER,
+    ram_mb INTEGER,
+    status TEXT NOT NULL,
+    registered_at INTEGER NOT NULL,
+    last_heartbeat INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_nodes_status
+    ON nodes(status);
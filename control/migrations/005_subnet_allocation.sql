@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS subnet_allocation (
+    subnet TEXT PRIMARY KEY,
+    node_id TEXT NOT NULL,
+    allocated_at INTEGER NOT NULL,
+    released_at INTEGER
+);
+
+CREATE INDEX IF NOT EXISTS idx_subnet_allocation_node
+    ON subnet_allocation(node_id);
+
+CREATE INDEX IF NOT EXISTS idx_nodes_identity
+    ON nodes(hostname, host_ip);
+
+-- Adopt subnets handed out before the allocator table existed.
+INSERT OR IGNORE INTO subnet_allocation (subnet, node_id, allocated_at, released_at)
+SELECT subnet, node_id, registered_at, CASE WHEN status = 'down' THEN last_heartbeat END
+FROM nodes;
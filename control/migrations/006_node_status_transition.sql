@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS node_status_transition (
+    seq INTEGER PRIMARY KEY AUTOINCREMENT,
+    node_id TEXT NOT NULL,
+    from_status TEXT,
+    to_status TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    transitioned_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_node_status_transition_node
+    ON node_status_transition(node_id, seq);
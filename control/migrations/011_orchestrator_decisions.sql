@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS orchestrator_decision (
+    decision_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    tenant_id TEXT NOT NULL,
+    workload_id TEXT NOT NULL,
+    reason_code TEXT NOT NULL,
+    applied INTEGER NOT NULL,
+    suppressed_by TEXT,
+    trace_json TEXT NOT NULL,
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_orch_decision_workload
+    ON orchestrator_decision(tenant_id, workload_id, decision_id);
+
+CREATE INDEX IF NOT EXISTS idx_orch_decision_created
+    ON orchestrator_decision(created_at);
@@ -0,0 +1,41 @@
+CREATE TABLE IF NOT EXISTS orchestrator_workload_observation_sample (
+    sample_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    tenant_id TEXT NOT NULL,
+    workload_id TEXT NOT NULL,
+    node_group TEXT NOT NULL,
+    queue_depth INTEGER NOT NULL,
+    cpu_pressure REAL NOT NULL,
+    mem_pressure REAL NOT NULL,
+    io_pressure REAL NOT NULL,
+    cold_start_pct REAL NOT NULL,
+    invoke_p95_ms INTEGER NOT NULL,
+    reject_pct REAL NOT NULL,
+    active_compute_units INTEGER NOT NULL,
+    cost_per_compute_unit REAL NOT NULL,
+    observed_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_orch_workload_sample_workload
+    ON orchestrator_workload_observation_sample(tenant_id, workload_id, sample_id);
+
+CREATE INDEX IF NOT EXISTS idx_orch_workload_sample_observed
+    ON orchestrator_workload_observation_sample(observed_at);
+
+CREATE TABLE IF NOT EXISTS orchestrator_node_group_observation_sample (
+    sample_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    node_group TEXT NOT NULL,
+    cpu_pressure REAL NOT NULL,
+    mem_pressure REAL NOT NULL,
+    io_pressure REAL NOT NULL,
+    warm_ready INTEGER NOT NULL,
+    warm_hit_rate REAL NOT NULL,
+    capacity_units INTEGER NOT NULL,
+    used_units INTEGER NOT NULL,
+    observed_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_orch_node_group_sample_group
+    ON orchestrator_node_group_observation_sample(node_group, sample_id);
+
+CREATE INDEX IF NOT EXISTS idx_orch_node_group_sample_observed
+    ON orchestrator_node_group_observation_sample(observed_at);
@@ -0,0 +1,10 @@
+ALTER TABLE orchestrator_workload_policy ADD COLUMN scaling_strategy_json TEXT NOT NULL DEFAULT '{"kind":"heuristic"}';
+
+CREATE TABLE IF NOT EXISTS orchestrator_scaling_state (
+    tenant_id TEXT NOT NULL,
+    workload_id TEXT NOT NULL,
+    strategy TEXT NOT NULL,
+    state_json TEXT NOT NULL,
+    updated_at INTEGER NOT NULL,
+    PRIMARY KEY (tenant_id, workload_id)
+);
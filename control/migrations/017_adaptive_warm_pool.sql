@@ -0,0 +1,5 @@
+ALTER TABLE orchestrator_workload_policy ADD COLUMN warm_pool_json TEXT;
+
+ALTER TABLE orchestrator_intent ADD COLUMN warm_pool_adjustment REAL NOT NULL DEFAULT 0;
+
+ALTER TABLE orchestrator_intent ADD COLUMN warm_pool_idle_ticks INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,108 @@
+CREATE TABLE IF NOT EXISTS orchestrator_workload_policy (
+    tenant_id TEXT NOT NULL,
+    workload_id TEXT NOT NULL,
+    runtime_function_id TEXT NOT NULL,
+    max_concurrency BIGINT NOT NULL,
+    hard_quota BIGINT NOT NULL,
+    soft_burst BIGINT NOT NULL,
+    absolute_limit BIGINT NOT NULL,
+    priority BIGINT NOT NULL,
+    cooldown_seconds BIGINT NOT NULL,
+    hysteresis_pct DOUBLE PRECISION NOT NULL,
+    burst_cpu_cap DOUBLE PRECISION NOT NULL,
+    burst_mem_mb BIGINT NOT NULL,
+    burst_ttl_seconds BIGINT NOT NULL,
+    target_container_ids_json TEXT NOT NULL,
+    updated_at BIGINT NOT NULL,
+    PRIMARY KEY (tenant_id, workload_id)
+);
+
+CREATE TABLE IF NOT EXISTS orchestrator_workload_slo (
+    tenant_id TEXT NOT NULL,
+    workload_id TEXT NOT NULL,
+    p95_latency_ms BIGINT NOT NULL,
+    max_cold_start_pct DOUBLE PRECISION NOT NULL,
+    max_reject_pct DOUBLE PRECISION NOT NULL,
+    rto_seconds BIGINT NOT NULL,
+    max_cost_per_compute_unit DOUBLE PRECISION NOT NULL,
+    updated_at BIGINT NOT NULL,
+    PRIMARY KEY (tenant_id, workload_id)
+);
+
+CREATE TABLE IF NOT EXISTS orchestrator_workload_observation (
+    tenant_id TEXT NOT NULL,
+    workload_id TEXT NOT NULL,
+    node_group TEXT NOT NULL,
+    queue_depth BIGINT NOT NULL,
+    cpu_pressure DOUBLE PRECISION NOT NULL,
+    mem_pressure DOUBLE PRECISION NOT NULL,
+    io_pressure DOUBLE PRECISION NOT NULL,
+    cold_start_pct DOUBLE PRECISION NOT NULL,
+    invoke_p95_ms BIGINT NOT NULL,
+    reject_pct DOUBLE PRECISION NOT NULL,
+    active_compute_units BIGINT NOT NULL,
+    cost_per_compute_unit DOUBLE PRECISION NOT NULL,
+    updated_at BIGINT NOT NULL,
+    PRIMARY KEY (tenant_id, workload_id)
+);
+
+CREATE TABLE IF NOT EXISTS orchestrator_node_group_observation (
+    node_group TEXT PRIMARY KEY,
+    cpu_pressure DOUBLE PRECISION NOT NULL,
+    mem_pressure DOUBLE PRECISION NOT NULL,
+    io_pressure DOUBLE PRECISION NOT NULL,
+    warm_ready BIGINT NOT NULL,
+    warm_hit_rate DOUBLE PRECISION NOT NULL,
+    capacity_units BIGINT NOT NULL,
+    used_units BIGINT NOT NULL,
+    updated_at BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS orchestrator_intent (
+    tenant_id TEXT NOT NULL,
+    workload_id TEXT NOT NULL,
+    target_concurrency BIGINT NOT NULL,
+    burst_cpu_cap DOUBLE PRECISION NOT NULL,
+    burst_mem_mb BIGINT NOT NULL,
+    burst_ttl_seconds BIGINT NOT NULL,
+    pool_min_ready BIGINT NOT NULL,
+    pool_target_ready BIGINT NOT NULL,
+    pool_max_ready BIGINT NOT NULL,
+    preferred_node_group TEXT NOT NULL,
+    anti_affinity BIGINT NOT NULL,
+    reason_code TEXT NOT NULL,
+    effective_at BIGINT NOT NULL,
+    ttl_seconds BIGINT NOT NULL,
+    updated_at BIGINT NOT NULL,
+    PRIMARY KEY (tenant_id, workload_id)
+);
+
+CREATE TABLE IF NOT EXISTS orchestrator_action (
+    action_id TEXT PRIMARY KEY,
+    tenant_id TEXT NOT NULL,
+    workload_id TEXT NOT NULL,
+    action_type TEXT NOT NULL,
+    payload_json TEXT NOT NULL,
+    ttl_seconds BIGINT NOT NULL,
+    rollback_action_json TEXT,
+    parent_action_id TEXT,
+    idempotency_key TEXT NOT NULL UNIQUE,
+    decision_window_start BIGINT NOT NULL,
+    status TEXT NOT NULL,
+    reason_code TEXT,
+    reason_message TEXT,
+    effective_at BIGINT NOT NULL,
+    outbound_requested_at BIGINT,
+    runtime_operation_id TEXT,
+    runtime_operation_type TEXT,
+    terminal_status TEXT,
+    terminal_at BIGINT,
+    total_latency_ms BIGINT,
+    attempt_count BIGINT NOT NULL,
+    next_retry_at BIGINT NOT NULL,
+    created_at BIGINT NOT NULL,
+    updated_at BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_orch_action_status_retry
+    ON orchestrator_action(status, next_retry_at);
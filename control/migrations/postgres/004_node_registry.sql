@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS nodes (
+    node_id TEXT PRIMARY KEY,
+    hostname TEXT NOT NULL,
+    host_ip TEXT NOT NULL,
+    subnet TEXT NOT NULL UNIQUE,
+    cpu_cores BIGINT,
+    ram_mb BIGINT,
+    status TEXT NOT NULL,
+    registered_at BIGINT NOT NULL,
+    last_heartbeat BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_nodes_status
+    ON nodes(status);
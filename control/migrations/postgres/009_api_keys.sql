@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS api_key (
+    key_id TEXT PRIMARY KEY,
+    key_hash TEXT NOT NULL UNIQUE,
+    role TEXT NOT NULL,
+    tenant_id TEXT,
+    created_at BIGINT NOT NULL,
+    revoked_at BIGINT
+);
+
+CREATE INDEX IF NOT EXISTS idx_api_key_tenant
+    ON api_key(tenant_id);
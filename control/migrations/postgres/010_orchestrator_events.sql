@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS orchestrator_event (
+    seq BIGSERIAL PRIMARY KEY,
+    event_type TEXT NOT NULL,
+    tenant_id TEXT NOT NULL,
+    workload_id TEXT NOT NULL,
+    action_id TEXT,
+    action_type TEXT,
+    status TEXT,
+    reason_code TEXT,
+    data_json TEXT NOT NULL,
+    created_at BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_orch_event_created
+    ON orchestrator_event(created_at);
@@ -0,0 +1,41 @@
+CREATE TABLE IF NOT EXISTS orchestrator_workload_observation_sample (
+    sample_id BIGSERIAL PRIMARY KEY,
+    tenant_id TEXT NOT NULL,
+    workload_id TEXT NOT NULL,
+    node_group TEXT NOT NULL,
+    queue_depth BIGINT NOT NULL,
+    cpu_pressure DOUBLE PRECISION NOT NULL,
+    mem_pressure DOUBLE PRECISION NOT NULL,
+    io_pressure DOUBLE PRECISION NOT NULL,
+    cold_start_pct DOUBLE PRECISION NOT NULL,
+    invoke_p95_ms BIGINT NOT NULL,
+    reject_pct DOUBLE PRECISION NOT NULL,
+    active_compute_units BIGINT NOT NULL,
+    cost_per_compute_unit DOUBLE PRECISION NOT NULL,
+    observed_at BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_orch_workload_sample_workload
+    ON orchestrator_workload_observation_sample(tenant_id, workload_id, sample_id);
+
+CREATE INDEX IF NOT EXISTS idx_orch_workload_sample_observed
+    ON orchestrator_workload_observation_sample(observed_at);
+
+CREATE TABLE IF NOT EXISTS orchestrator_node_group_observation_sample (
+    sample_id BIGSERIAL PRIMARY KEY,
+    node_group TEXT NOT NULL,
+    cpu_pressure DOUBLE PRECISION NOT NULL,
+    mem_pressure DOUBLE PRECISION NOT NULL,
+    io_pressure DOUBLE PRECISION NOT NULL,
+    warm_ready BIGINT NOT NULL,
+    warm_hit_rate DOUBLE PRECISION NOT NULL,
+    capacity_units BIGINT NOT NULL,
+    used_units BIGINT NOT NULL,
+    observed_at BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_orch_node_group_sample_group
+    ON orchestrator_node_group_observation_sample(node_group, sample_id);
+
+CREATE INDEX IF NOT EXISTS idx_orch_node_group_sample_observed
+    ON orchestrator_node_group_observation_sample(observed_at);
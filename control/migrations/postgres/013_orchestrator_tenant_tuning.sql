@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS orchestrator_tenant_tuning (
+    tenant_id TEXT PRIMARY KEY,
+    overrides_json TEXT NOT NULL,
+    updated_at BIGINT NOT NULL
+);
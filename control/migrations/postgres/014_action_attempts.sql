@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS orchestrator_action_attempt (
+    attempt_id BIGSERIAL PRIMARY KEY,
+    action_id TEXT NOT NULL,
+    attempt BIGINT NOT NULL,
+    status TEXT NOT NULL,
+    reason_code TEXT NOT NULL,
+    reason_message TEXT,
+    attempted_at BIGINT NOT NULL,
+    next_retry_at BIGINT
+);
+
+CREATE INDEX IF NOT EXISTS idx_orch_action_attempt_action
+    ON orchestrator_action_attempt(action_id, attempt_id);
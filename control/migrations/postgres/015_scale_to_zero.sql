@@ -0,0 +1,3 @@
+ALTER TABLE orchestrator_workload_policy ADD COLUMN scale_to_zero_idle_seconds BIGINT;
+
+ALTER TABLE orchestrator_intent ADD COLUMN idle_since BIGINT;
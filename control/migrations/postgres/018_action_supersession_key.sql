@@ -0,0 +1,10 @@
+ALTER TABLE orchestrator_action ADD COLUMN supersession_key TEXT;
+
+UPDATE orchestrator_action SET supersession_key = '' WHERE action_type = 'SetPoolTarget';
+
+UPDATE orchestrator_action
+SET supersession_key = payload_json::jsonb ->> 'container_id'
+WHERE action_type = 'SetBurstPolicy';
+
+CREATE INDEX IF NOT EXISTS idx_orch_action_supersession
+    ON orchestrator_action(tenant_id, workload_id, action_type, supersession_key, status);
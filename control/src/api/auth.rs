@@ -0,0 +1,207 @@
+//! Authentication and tenant authorization for the `/v1/orchestrator` API.
+//!
+//! Callers present an API key as `X-Api-Key: <key>` or
+//! `Authorization: Bearer <key>`. The key resolves to a [`Principal`]: a
+//! tenant, which may only touch its own `tenant_id`, or an admin, which may
+//! touch every tenant and the platform-wide endpoints.
+
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap},
+    middleware::Next,
+    response::Response,
+};
+use std::sync::Arc;
+
+use crate::api::error::ApiError;
+use crate::api::AppState;
+use crate::services::api_keys::{self, ROLE_ADMIN};
+
+pub const RC_AUTH_MISSING_CREDENTIALS: &str = "AUTH_MISSING_CREDENTIALS";
+pub const RC_AUTH_INVALID_CREDENTIALS: &str = "AUTH_INVALID_CREDENTIALS";
+pub const RC_AUTH_TENANT_FORBIDDEN: &str = "AUTH_TENANT_FORBIDDEN";
+pub const RC_AUTH_ADMIN_REQUIRED: &str = "AUTH_ADMIN_REQUIRED";
+
+const BOOTSTRAP_ADMIN_KEY_ID: &str = "bootstrap-admin";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Principal {
+    Admin { key_id: String },
+    Tenant { key_id: String, tenant_id: String },
+}
+
+impl Principal {
+    pub fn require_admin(&self) -> Result<(), ApiError> {
+        match self {
+            Principal::Admin { .. } => Ok(()),
+            Principal::Tenant { .. } => Err(forbidden(
+                RC_AUTH_ADMIN_REQUIRED,
+                "this endpoint requires an admin key".to_string(),
+            )),
+        }
+    }
+
+    pub fn require_tenant(&self, tenant_id: &str) -> Result<(), ApiError> {
+        match self {
+            Principal::Admin { .. } => Ok(()),
+            Principal::Tenant { tenant_id: own, .. } if own == tenant_id => Ok(()),
+            Principal::Tenant { tenant_id: own, .. } => Err(forbidden(
+                RC_AUTH_TENANT_FORBIDDEN,
+                format!("key for tenant {own} cannot access tenant {tenant_id}"),
+            )),
+        }
+    }
+
+    /// Tenant filter to apply to a listing. Admins get what they asked for;
+    /// tenants are pinned to their own tenant and rejected if they asked for
+    /// another.
+    pub fn scope_tenant_filter(&self, requested: Option<&str>) -> Result<Option<String>, ApiError> {
+        match self {
+            Principal::Admin { .. } => Ok(requested.map(ToString::to_string)),
+            Principal::Tenant { tenant_id, .. } => {
+                if let Some(requested) = requested {
+                    self.require_tenant(requested)?;
+                }
+                Ok(Some(tenant_id.clone()))
+            }
+        }
+    }
+}
+
+fn unauthorized(error_code: &'static str, message: &str) -> ApiError {
+    ApiError::Unauthorized {
+        error_code,
+        message: message.to_string(),
+    }
+}
+
+fn forbidden(error_code: &'static str, message: String) -> ApiError {
+    ApiError::Forbidden {
+        error_code,
+        message,
+    }
+}
+
+/// Static credentials configured at startup.
+#[derive(Debug, Clone, Default)]
+pub struct AuthConfig {
+    admin_key_hash: Option<String>,
+}
+
+impl AuthConfig {
+    /// `admin_api_key` is a bootstrap admin key that works without a DB row,
+    /// so operators can mint the first keys through the API.
+    pub fn new(admin_api_key: Option<&str>) -> Self {
+        Self {
+            admin_key_hash: admin_api_key
+                .filter(|k| !k.is_empty())
+                .map(api_keys::hash_key),
+        }
+    }
+}
+
+fn presented_key(headers: &HeaderMap) -> Option<&str> {
+    if let Some(key) = headers.get("x-api-key").and_then(|v| v.to_str().ok()) {
+        return Some(key.trim());
+    }
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim)
+}
+
+/// Middleware that resolves the caller's [`Principal`] and stores it in the
+/// request extensions, or rejects the request with 401.
+pub async fn authenticate(
+    State(state): State<Arc<AppState>>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let Some(key) = presented_key(req.headers()).filter(|k| !k.is_empty()) else {
+        return Err(unauthorized(
+            RC_AUTH_MISSING_CREDENTIALS,
+            "missing API key (X-Api-Key or Authorization: Bearer)",
+        ));
+    };
+
+    let principal = if state.auth.admin_key_hash.as_deref() == Some(&api_keys::hash_key(key)) {
+        Principal::Admin {
+            key_id: BOOTSTRAP_ADMIN_KEY_ID.to_string(),
+        }
+    } else {
+        match api_keys::find_active_key(&state.db, key).await? {
+            Some(k) if k.role == ROLE_ADMIN => Principal::Admin { key_id: k.key_id },
+            Some(k) => match k.tenant_id {
+                Some(tenant_id) => Principal::Tenant {
+                    key_id: k.key_id,
+                    tenant_id,
+                },
+                None => {
+                    return Err(unauthorized(
+                        RC_AUTH_INVALID_CREDENTIALS,
+                        "API key has no tenant binding",
+                    ))
+                }
+            },
+            None => {
+                return Err(unauthorized(
+                    RC_AUTH_INVALID_CREDENTIALS,
+                    "API key is unknown or revoked",
+                ))
+            }
+        }
+    };
+
+    req.extensions_mut().insert(principal);
+    Ok(next.run(req).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tenant(tenant_id: &str) -> Principal {
+        Principal::Tenant {
+            key_id: "k1".to_string(),
+            tenant_id: tenant_id.to_string(),
+        }
+    }
+
+    fn admin() -> Principal {
+        Principal::Admin {
+            key_id: "k0".to_string(),
+        }
+    }
+
+    #[test]
+    fn tenant_principal_is_confined_to_its_tenant() {
+        let p = tenant("tenant-a");
+        assert!(p.require_tenant("tenant-a").is_ok());
+        let err = p.require_tenant("tenant-b").expect_err("cross-tenant");
+        assert_eq!(err.status(), axum::http::StatusCode::FORBIDDEN);
+        assert_eq!(err.error_code(), RC_AUTH_TENANT_FORBIDDEN);
+        assert_eq!(
+            p.require_admin().expect_err("admin only").error_code(),
+            RC_AUTH_ADMIN_REQUIRED
+        );
+
+        assert_eq!(
+            p.scope_tenant_filter(None).expect("scope").as_deref(),
+            Some("tenant-a")
+        );
+        assert!(p.scope_tenant_filter(Some("tenant-b")).is_err());
+        assert_eq!(admin().scope_tenant_filter(None).expect("scope"), None);
+        assert!(admin().require_tenant("tenant-b").is_ok());
+    }
+
+    #[test]
+    fn key_is_read_from_either_header() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(presented_key(&headers), None);
+        headers.insert(header::AUTHORIZATION, "Bearer qk_abc".parse().unwrap());
+        assert_eq!(presented_key(&headers), Some("qk_abc"));
+        headers.insert("x-api-key", "qk_def".parse().unwrap());
+        assert_eq!(presented_key(&headers), Some("qk_def"));
+    }
+}
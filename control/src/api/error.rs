@@ -0,0 +1,246 @@
+//! Error type for control API handlers.
+//!
+//! Every error is rendered as `{error_code, message, details, request_id}`,
+//! the shape `quiltc` parses, with the message repeated under `error` for
+//! clients that read that key. The request id is taken from `X-Request-Id`
+//! when the caller sends one and generated otherwise. Internal errors are
+//! logged with their cause and reach the client only as "internal error".
+
+use axum::{
+    async_trait,
+    extract::{rejection::JsonRejection, rejection::QueryRejection, FromRequest, FromRequestParts},
+    extract::{Query, Request},
+    http::{header, request::Parts, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::services::error::ServiceError;
+use crate::types::FieldError;
+
+pub const RC_API_BAD_REQUEST: &str = "API_BAD_REQUEST";
+pub const RC_API_NOT_FOUND: &str = "API_NOT_FOUND";
+pub const RC_API_CONFLICT: &str = "API_CONFLICT";
+pub const RC_API_VALIDATION_FAILED: &str = "API_VALIDATION_FAILED";
+pub const RC_API_INTERNAL: &str = "API_INTERNAL";
+
+const REQUEST_ID_HEADER: &str = "x-request-id";
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    /// Malformed body or query string.
+    #[error("{0}")]
+    BadRequest(String),
+    #[error("{message}")]
+    Unauthorized {
+        error_code: &'static str,
+        message: String,
+    },
+    #[error("{message}")]
+    Forbidden {
+        error_code: &'static str,
+        message: String,
+    },
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
+    Conflict(String),
+    /// Well-formed request that fails field-level checks.
+    #[error("request failed validation")]
+    Validation(Vec<FieldError>),
+    #[error("internal error")]
+    Internal(anyhow::Error),
+}
+
+impl ApiError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::Unauthorized { .. } => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden { .. } => StatusCode::FORBIDDEN,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            ApiError::BadRequest(_) => RC_API_BAD_REQUEST,
+            ApiError::Unauthorized { error_code, .. } | ApiError::Forbidden { error_code, .. } => {
+                error_code
+            }
+            ApiError::NotFound(_) => RC_API_NOT_FOUND,
+            ApiError::Conflict(_) => RC_API_CONFLICT,
+            ApiError::Validation(_) => RC_API_VALIDATION_FAILED,
+            ApiError::Internal(_) => RC_API_INTERNAL,
+        }
+    }
+
+    fn details(&self) -> Value {
+        match self {
+            ApiError::Validation(errors) => json!({ "fields": errors }),
+            _ => Value::Null,
+        }
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(err: anyhow::Error) -> Self {
+        match err.downcast::<ServiceError>() {
+            Ok(ServiceError::Validation(errors)) => ApiError::Validation(errors),
+            Ok(ServiceError::NotFound(message)) => ApiError::NotFound(message),
+            Ok(ServiceError::Conflict(message)) => ApiError::Conflict(message),
+            Err(err) => ApiError::Internal(err),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = self.status();
+        let request_id = current_request_id();
+        if let ApiError::Internal(err) = &self {
+            tracing::error!("request {} failed: {:#}", request_id, err);
+        }
+        let message = self.to_string();
+        let body = Json(json!({
+            "error_code": self.error_code(),
+            "error": message,
+            "message": message,
+            "details": self.details(),
+            "request_id": request_id,
+        }));
+        if status == StatusCode::UNAUTHORIZED {
+            (status, [(header::WWW_AUTHENTICATE, "Bearer")], body).into_response()
+        } else {
+            (status, body).into_response()
+        }
+    }
+}
+
+fn current_request_id() -> String {
+    REQUEST_ID
+        .try_with(Clone::clone)
+        .unwrap_or_else(|_| Uuid::new_v4().to_string())
+}
+
+/// Middleware that scopes the caller's `X-Request-Id` (or a fresh one) to
+/// the request and echoes it on the response.
+pub async fn request_id(req: Request, next: Next) -> Response {
+    let id = req
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|v| !v.is_empty() && v.len() <= 128)
+        .map(ToString::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    let mut response = REQUEST_ID.scope(id.clone(), next.run(req)).await;
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+/// `Json` extractor whose rejection is an [`ApiError`].
+pub struct ApiJson<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for ApiJson<T>
+where
+    Json<T>: FromRequest<S, Rejection = JsonRejection>,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        Json::<T>::from_request(req, state)
+            .await
+            .map(|Json(value)| ApiJson(value))
+            .map_err(|rejection| ApiError::BadRequest(rejection.body_text()))
+    }
+}
+
+/// `Query` extractor whose rejection is an [`ApiError`].
+pub struct ApiQuery<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for ApiQuery<T>
+where
+    Query<T>: FromRequestParts<S, Rejection = QueryRejection>,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        Query::<T>::from_request_parts(parts, state)
+            .await
+            .map(|Query(value)| ApiQuery(value))
+            .map_err(|rejection| ApiError::BadRequest(rejection.body_text()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn body_carries_code_details_and_request_id() {
+        let err = ApiError::Validation(vec![FieldError {
+            field: "hard_quota".to_string(),
+            message: "must be >= max_concurrency".to_string(),
+        }]);
+        let response = REQUEST_ID
+            .scope("req-1".to_string(), async { err.into_response() })
+            .await;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let body: Value = serde_json::from_slice(&bytes).expect("json");
+        assert_eq!(body["error_code"], RC_API_VALIDATION_FAILED);
+        assert_eq!(body["request_id"], "req-1");
+        assert_eq!(body["details"]["fields"][0]["field"], "hard_quota");
+        assert_eq!(body["error"], body["message"]);
+    }
+
+    #[tokio::test]
+    async fn internal_errors_hide_their_cause() {
+        let err = ApiError::from(anyhow::anyhow!(
+            "relation \"orchestrator_action\" does not exist"
+        ));
+        let bytes = axum::body::to_bytes(err.into_response().into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let body: Value = serde_json::from_slice(&bytes).expect("json");
+        assert_eq!(body["error_code"], RC_API_INTERNAL);
+        assert_eq!(body["message"], "internal error");
+        assert_eq!(body["error"], "internal error");
+    }
+
+    #[test]
+    fn service_errors_map_to_status_codes() {
+        let cases = [
+            (ServiceError::NotFound("x".into()), StatusCode::NOT_FOUND),
+            (ServiceError::Conflict("x".into()), StatusCode::CONFLICT),
+            (
+                ServiceError::Validation(Vec::new()),
+                StatusCode::UNPROCESSABLE_ENTITY,
+            ),
+        ];
+        for (err, status) in cases {
+            assert_eq!(ApiError::from(anyhow::Error::from(err)).status(), status);
+        }
+        let other = ApiError::from(anyhow::anyhow!("db down"));
+        assert_eq!(other.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
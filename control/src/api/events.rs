@@ -0,0 +1,103 @@
+use axum::{
+    extract::State,
+    http::HeaderMap,
+    response::sse::{Event, KeepAlive, Sse},
+    Extension,
+};
+use futures::stream::{self, Stream, StreamExt};
+use serde::Deserialize;
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::api::auth::Principal;
+use crate::api::error::{ApiError, ApiQuery};
+use crate::api::AppState;
+use crate::db::DbPool;
+use crate::services::events::{self, EventFilter};
+
+const LAST_EVENT_ID_HEADER: &str = "last-event-id";
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+const BATCH_SIZE: usize = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct EventStreamQuery {
+    pub tenant_id: Option<String>,
+    pub workload_id: Option<String>,
+    pub action_type: Option<String>,
+    /// Replay events after this sequence. Ignored when the client sends
+    /// `Last-Event-ID`; when neither is given the stream starts at the tail.
+    pub after_seq: Option<i64>,
+}
+
+/// Server-sent stream of orchestrator action and intent events.
+pub async fn stream_events(
+    State(state): State<Arc<AppState>>,
+    Extension(principal): Extension<Principal>,
+    headers: HeaderMap,
+    ApiQuery(query): ApiQuery<EventStreamQuery>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let filter = EventFilter {
+        tenant_id: principal.scope_tenant_filter(query.tenant_id.as_deref())?,
+        workload_id: query.workload_id,
+        action_type: query.action_type,
+    };
+    let last_event_id = match headers.get(LAST_EVENT_ID_HEADER) {
+        Some(value) => Some(
+            value
+                .to_str()
+                .ok()
+                .and_then(|v| v.trim().parse::<i64>().ok())
+                .ok_or_else(|| {
+                    ApiError::BadRequest("Last-Event-ID must be an event sequence".to_string())
+                })?,
+        ),
+        None => None,
+    };
+    let cursor = match last_event_id.or(query.after_seq) {
+        Some(seq) => seq,
+        None => events::latest_seq(&state.db).await?,
+    };
+
+    Ok(Sse::new(event_stream(state.db.clone(), filter, cursor)).keep_alive(KeepAlive::default()))
+}
+
+fn event_stream(
+    db: DbPool,
+    filter: EventFilter,
+    cursor: i64,
+) -> impl Stream<Item = Result<Event, Infallible>> {
+    stream::unfold(
+        (db, filter, cursor, VecDeque::new()),
+        |(db, filter, mut cursor, mut pending)| async move {
+            loop {
+                if let Some(event) = pending.pop_front() {
+                    return Some((Ok(event), (db, filter, cursor, pending)));
+                }
+                match events::list_events(&db, &filter, cursor, BATCH_SIZE).await {
+                    Ok(batch) if !batch.is_empty() => {
+                        for event in batch {
+                            cursor = event.seq;
+                            match Event::default()
+                                .id(event.seq.to_string())
+                                .event(event.event_type.clone())
+                                .json_data(&event)
+                            {
+                                Ok(sse) => pending.push_back(sse),
+                                Err(e) => {
+                                    tracing::warn!("Failed to encode event {}: {}", event.seq, e)
+                                }
+                            }
+                        }
+                        continue;
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Failed to read orchestrator events: {}", e),
+                }
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+        },
+    )
+    .boxed()
+}
@@ -0,0 +1,46 @@
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::api::AppState;
+use crate::metrics;
+use crate::services::orchestrator;
+
+/// Prometheus scrape endpoint.
+pub async fn metrics(State(state): State<Arc<AppState>>) -> Response {
+    match orchestrator::action_status_counts(&state.db).await {
+        Ok(counts) => metrics::set_action_status_counts(&counts),
+        Err(e) => tracing::warn!("Failed to count orchestrator actions: {}", e),
+    }
+    metrics::set_leader(state.leader.is_leader());
+
+    match metrics::render() {
+        Ok(body) => ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+/// Record per-route request counts and latency. Labels use the matched route
+/// template rather than the raw path so IDs don't explode cardinality.
+pub async fn track_http(req: Request, next: Next) -> Response {
+    let method = req.method().to_string();
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+    let started = Instant::now();
+    let response = next.run(req).await;
+    metrics::http_request(
+        &method,
+        &route,
+        response.status().as_u16(),
+        started.elapsed(),
+    );
+    response
+}
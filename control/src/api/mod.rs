@@ -1,30 +1,62 @@
+pub mod auth;
+pub mod error;
+pub mod events;
+pub mod metrics;
+pub mod nodes;
 pub mod orchestrator;
 
 use axum::{
+    extract::State,
     http::StatusCode,
-    routing::{get, post, put},
+    middleware,
+    routing::{delete, get, post, put},
     Json, Router,
 };
 use std::sync::Arc;
 
+use crate::api::auth::AuthConfig;
+use crate::db::ipam::SubnetAllocator;
 use crate::db::DbPool;
+use crate::services::circuit_breaker::CircuitBreaker;
+use crate::services::leader_election::LeaderElection;
+use crate::services::orchestrator_config::ConfigHandle;
 use crate::types::HealthResponse;
 
 #[derive(Clone)]
 pub struct AppState {
     pub db: DbPool,
+    pub ipam: SubnetAllocator,
+    pub leader: LeaderElection,
+    pub auth: AuthConfig,
+    pub config: ConfigHandle,
+    pub breaker: CircuitBreaker,
 }
 
 pub fn create_router(state: Arc<AppState>) -> Router {
-    Router::new()
-        .route("/v1/health", get(health))
+    let orchestrator_routes = Router::new()
         .route(
             "/v1/orchestrator/tenants/:tenant_id/workloads/:workload_id/policy",
-            put(orchestrator::upsert_workload_policy),
+            get(orchestrator::get_workload_policy)
+                .put(orchestrator::upsert_workload_policy)
+                .delete(orchestrator::delete_workload_policy),
         )
         .route(
             "/v1/orchestrator/tenants/:tenant_id/workloads/:workload_id/slo",
-            put(orchestrator::upsert_workload_slo),
+            get(orchestrator::get_workload_slo)
+                .put(orchestrator::upsert_workload_slo)
+                .delete(orchestrator::delete_workload_slo),
+        )
+        .route(
+            "/v1/orchestrator/tenants/:tenant_id/workloads/:workload_id/decisions",
+            get(orchestrator::list_decisions),
+        )
+        .route(
+            "/v1/orchestrator/policies",
+            get(orchestrator::list_workload_policies),
+        )
+        .route(
+            "/v1/orchestrator/slos",
+            get(orchestrator::list_workload_slos),
         )
         .route(
             "/v1/orchestrator/observations",
@@ -32,10 +64,38 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         )
         .route("/v1/orchestrator/intents", get(orchestrator::list_intents))
         .route("/v1/orchestrator/actions", get(orchestrator::list_actions))
+        .route(
+            "/v1/orchestrator/dead-letters",
+            get(orchestrator::list_dead_letters),
+        )
+        .route(
+            "/v1/orchestrator/actions/:action_id",
+            post(orchestrator::action_command),
+        )
         .route(
             "/v1/orchestrator/actions/:action_id/result",
             post(orchestrator::update_action_result),
         )
+        .route("/v1/orchestrator/events", get(events::stream_events))
+        .route("/v1/orchestrator/leader", get(orchestrator::leader_status))
+        .route(
+            "/v1/orchestrator/api-keys",
+            get(orchestrator::list_api_keys).post(orchestrator::create_api_key),
+        )
+        .route(
+            "/v1/orchestrator/api-keys/:key_id",
+            delete(orchestrator::revoke_api_key),
+        )
+        .route("/v1/orchestrator/simulate", post(orchestrator::simulate))
+        .route("/v1/orchestrator/config", get(orchestrator::get_config))
+        .route(
+            "/v1/orchestrator/config:reload",
+            post(orchestrator::reload_config),
+        )
+        .route(
+            "/v1/orchestrator/tenants/:tenant_id/tuning",
+            put(orchestrator::upsert_tenant_tuning).delete(orchestrator::delete_tenant_tuning),
+        )
         .route(
             "/v1/orchestrator/loops/fast:run",
             post(orchestrator::trigger_fast_loop),
@@ -44,14 +104,34 @@ pub fn create_router(state: Arc<AppState>) -> Router {
             "/v1/orchestrator/loops/slow:run",
             post(orchestrator::trigger_slow_loop),
         )
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth::authenticate,
+        ));
+
+    Router::new()
+        .route("/v1/health", get(health))
+        .route("/metrics", get(metrics::metrics))
+        .route("/api/nodes", get(nodes::list_nodes))
+        .route("/api/nodes/register", post(nodes::register_node))
+        .route("/api/nodes/transitions", get(nodes::list_transitions))
+        .route("/api/nodes/:node_id", get(nodes::get_node))
+        .route("/api/nodes/:node_id/heartbeat", post(nodes::heartbeat))
+        .route("/api/nodes/:node_id/deregister", post(nodes::deregister))
+        .merge(orchestrator_routes)
+        .layer(middleware::from_fn(metrics::track_http))
+        .layer(middleware::from_fn(error::request_id))
         .with_state(state)
 }
 
-async fn health() -> (StatusCode, Json<HealthResponse>) {
+/// The control plane stays healthy while elasticity control is down; the
+/// breaker state tells callers whether actions are going out.
+async fn health(State(state): State<Arc<AppState>>) -> (StatusCode, Json<HealthResponse>) {
     (
         StatusCode::OK,
         Json(HealthResponse {
             status: "ok".to_string(),
+            elasticity_control: state.breaker.status(),
         }),
     )
 }
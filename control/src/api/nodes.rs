@@ -0,0 +1,86 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::api::error::{ApiError, ApiJson, ApiQuery};
+use crate::api::AppState;
+use crate::services::node_registry;
+use crate::types::{
+    ListNodesResponse, Node, NodeTransitionListResponse, RegisterNodeRequest, RegisterNodeResponse,
+};
+
+pub async fn register_node(
+    State(state): State<Arc<AppState>>,
+    ApiJson(req): ApiJson<RegisterNodeRequest>,
+) -> Result<Json<RegisterNodeResponse>, ApiError> {
+    let resp = node_registry::register_node(&state.db, &state.ipam, req).await?;
+    Ok(Json(resp))
+}
+
+pub async fn heartbeat(
+    State(state): State<Arc<AppState>>,
+    Path(node_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    let found = node_registry::heartbeat(&state.db, &state.ipam, &node_id).await?;
+    if !found {
+        return Err(node_not_found(&node_id));
+    }
+    Ok(StatusCode::OK)
+}
+
+pub async fn deregister(
+    State(state): State<Arc<AppState>>,
+    Path(node_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    let found = node_registry::deregister(&state.db, &state.ipam, &node_id).await?;
+    if !found {
+        return Err(node_not_found(&node_id));
+    }
+    Ok(StatusCode::OK)
+}
+
+pub async fn get_node(
+    State(state): State<Arc<AppState>>,
+    Path(node_id): Path<String>,
+) -> Result<Json<Node>, ApiError> {
+    node_registry::get_node(&state.db, &node_id)
+        .await?
+        .map(Json)
+        .ok_or_else(|| node_not_found(&node_id))
+}
+
+pub async fn list_nodes(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<ListNodesResponse>, ApiError> {
+    let nodes = node_registry::list_nodes(&state.db).await?;
+    Ok(Json(ListNodesResponse { nodes }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TransitionListQuery {
+    pub node_id: Option<String>,
+    pub after_seq: Option<i64>,
+    pub limit: Option<usize>,
+}
+
+pub async fn list_transitions(
+    State(state): State<Arc<AppState>>,
+    ApiQuery(query): ApiQuery<TransitionListQuery>,
+) -> Result<Json<NodeTransitionListResponse>, ApiError> {
+    let transitions = node_registry::list_transitions(
+        &state.db,
+        query.node_id.as_deref(),
+        query.after_seq.unwrap_or(0),
+        query.limit.unwrap_or(100).min(1000),
+    )
+    .await?;
+    Ok(Json(NodeTransitionListResponse { transitions }))
+}
+
+fn node_not_found(node_id: &str) -> ApiError {
+    ApiError::NotFound(format!("Node not found: {node_id}"))
+}
@@ -1,59 +1,172 @@
 use axum::{
-    extract::{Path, Query, State},
+    extract::{Path, State},
     http::StatusCode,
-    Json,
+    Extension, Json,
 };
 use serde::Deserialize;
 use std::sync::Arc;
 
+use crate::api::auth::Principal;
+use crate::api::error::{ApiError, ApiJson, ApiQuery};
 use crate::api::AppState;
-use crate::services::orchestrator;
+use crate::services::error::ServiceError;
+use crate::services::orchestrator_config::{self, OrchestratorConfigResponse, TuningOverrides};
+use crate::services::{api_keys, decisions, orchestrator};
 use crate::types::{
-    ActionListResponse, ActionResultRequest, IntentListResponse, ObservationIngestRequest,
-    WorkloadPolicyRequest, WorkloadSloRequest,
+    ActionListResponse, ActionResultRequest, ApiKeyListResponse, CreateApiKeyRequest,
+    CreateApiKeyResponse, DeadLetterListResponse, DecisionListResponse, IntentListResponse,
+    LeaderStatusResponse, ObservationIngestRequest, OrchestratorAction, SimulationRequest,
+    SimulationResponse, WorkloadPolicy, WorkloadPolicyListResponse, WorkloadPolicyRequest,
+    WorkloadSlo, WorkloadSloListResponse, WorkloadSloRequest,
 };
 
 pub async fn upsert_workload_policy(
     State(state): State<Arc<AppState>>,
+    Extension(principal): Extension<Principal>,
     Path((tenant_id, workload_id)): Path<(String, String)>,
-    Json(req): Json<WorkloadPolicyRequest>,
-) -> Result<StatusCode, (StatusCode, String)> {
-    orchestrator::upsert_workload_policy(&state.db, &tenant_id, &workload_id, req)
-        .await
-        .map_err(internal_error)?;
+    ApiJson(req): ApiJson<WorkloadPolicyRequest>,
+) -> Result<StatusCode, ApiError> {
+    principal.require_tenant(&tenant_id)?;
+    orchestrator::upsert_workload_policy(&state.db, &tenant_id, &workload_id, req).await?;
     Ok(StatusCode::ACCEPTED)
 }
 
 pub async fn upsert_workload_slo(
     State(state): State<Arc<AppState>>,
+    Extension(principal): Extension<Principal>,
     Path((tenant_id, workload_id)): Path<(String, String)>,
-    Json(req): Json<WorkloadSloRequest>,
-) -> Result<StatusCode, (StatusCode, String)> {
-    orchestrator::upsert_workload_slo(&state.db, &tenant_id, &workload_id, req)
-        .await
-        .map_err(internal_error)?;
+    ApiJson(req): ApiJson<WorkloadSloRequest>,
+) -> Result<StatusCode, ApiError> {
+    principal.require_tenant(&tenant_id)?;
+    orchestrator::upsert_workload_slo(&state.db, &tenant_id, &workload_id, req).await?;
     Ok(StatusCode::ACCEPTED)
 }
 
+pub async fn get_workload_policy(
+    State(state): State<Arc<AppState>>,
+    Extension(principal): Extension<Principal>,
+    Path((tenant_id, workload_id)): Path<(String, String)>,
+) -> Result<Json<WorkloadPolicy>, ApiError> {
+    principal.require_tenant(&tenant_id)?;
+    orchestrator::get_workload_policy(&state.db, &tenant_id, &workload_id)
+        .await?
+        .map(Json)
+        .ok_or_else(|| {
+            ApiError::NotFound(format!(
+                "No policy for workload {}/{}",
+                tenant_id, workload_id
+            ))
+        })
+}
+
+pub async fn delete_workload_policy(
+    State(state): State<Arc<AppState>>,
+    Extension(principal): Extension<Principal>,
+    Path((tenant_id, workload_id)): Path<(String, String)>,
+) -> Result<StatusCode, ApiError> {
+    principal.require_tenant(&tenant_id)?;
+    orchestrator::delete_workload_policy(&state.db, &tenant_id, &workload_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TenantFilterQuery {
+    pub tenant_id: Option<String>,
+}
+
+pub async fn list_workload_policies(
+    State(state): State<Arc<AppState>>,
+    Extension(principal): Extension<Principal>,
+    ApiQuery(query): ApiQuery<TenantFilterQuery>,
+) -> Result<Json<WorkloadPolicyListResponse>, ApiError> {
+    let tenant_id = principal.scope_tenant_filter(query.tenant_id.as_deref())?;
+    let policies = orchestrator::list_workload_policies(&state.db, tenant_id.as_deref()).await?;
+    Ok(Json(WorkloadPolicyListResponse { policies }))
+}
+
+pub async fn get_workload_slo(
+    State(state): State<Arc<AppState>>,
+    Extension(principal): Extension<Principal>,
+    Path((tenant_id, workload_id)): Path<(String, String)>,
+) -> Result<Json<WorkloadSlo>, ApiError> {
+    principal.require_tenant(&tenant_id)?;
+    orchestrator::get_workload_slo(&state.db, &tenant_id, &workload_id)
+        .await?
+        .map(Json)
+        .ok_or_else(|| {
+            ApiError::NotFound(format!("No SLO for workload {}/{}", tenant_id, workload_id))
+        })
+}
+
+pub async fn delete_workload_slo(
+    State(state): State<Arc<AppState>>,
+    Extension(principal): Extension<Principal>,
+    Path((tenant_id, workload_id)): Path<(String, String)>,
+) -> Result<StatusCode, ApiError> {
+    principal.require_tenant(&tenant_id)?;
+    orchestrator::delete_workload_slo(&state.db, &tenant_id, &workload_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn list_workload_slos(
+    State(state): State<Arc<AppState>>,
+    Extension(principal): Extension<Principal>,
+    ApiQuery(query): ApiQuery<TenantFilterQuery>,
+) -> Result<Json<WorkloadSloListResponse>, ApiError> {
+    let tenant_id = principal.scope_tenant_filter(query.tenant_id.as_deref())?;
+    let slos = orchestrator::list_workload_slos(&state.db, tenant_id.as_deref()).await?;
+    Ok(Json(WorkloadSloListResponse { slos }))
+}
+
 pub async fn ingest_observations(
     State(state): State<Arc<AppState>>,
-    Json(req): Json<ObservationIngestRequest>,
-) -> Result<StatusCode, (StatusCode, String)> {
-    orchestrator::ingest_observations(&state.db, req)
-        .await
-        .map_err(internal_error)?;
+    Extension(principal): Extension<Principal>,
+    ApiJson(req): ApiJson<ObservationIngestRequest>,
+) -> Result<StatusCode, ApiError> {
+    for w in &req.workloads {
+        principal.require_tenant(&w.tenant_id)?;
+    }
+    // Node group pressure is shared by every tenant on the group.
+    if !req.node_groups.is_empty() {
+        principal.require_admin()?;
+    }
+    orchestrator::ingest_observations(&state.db, req).await?;
     Ok(StatusCode::ACCEPTED)
 }
 
 pub async fn list_intents(
     State(state): State<Arc<AppState>>,
-) -> Result<Json<IntentListResponse>, (StatusCode, String)> {
-    let intents = orchestrator::list_intents(&state.db)
-        .await
-        .map_err(internal_error)?;
+    Extension(principal): Extension<Principal>,
+) -> Result<Json<IntentListResponse>, ApiError> {
+    let tenant_id = principal.scope_tenant_filter(None)?;
+    let intents = orchestrator::list_intents(&state.db, tenant_id.as_deref()).await?;
     Ok(Json(IntentListResponse { intents }))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct DecisionListQuery {
+    pub before: Option<i64>,
+    pub limit: Option<usize>,
+}
+
+pub async fn list_decisions(
+    State(state): State<Arc<AppState>>,
+    Extension(principal): Extension<Principal>,
+    Path((tenant_id, workload_id)): Path<(String, String)>,
+    ApiQuery(query): ApiQuery<DecisionListQuery>,
+) -> Result<Json<DecisionListResponse>, ApiError> {
+    principal.require_tenant(&tenant_id)?;
+    let decisions = decisions::list_decisions(
+        &state.db,
+        &tenant_id,
+        &workload_id,
+        query.before,
+        query.limit.unwrap_or(50).min(500),
+    )
+    .await?;
+    Ok(Json(DecisionListResponse { decisions }))
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ActionListQuery {
     pub tenant_id: Option<String>,
@@ -63,24 +176,29 @@ pub struct ActionListQuery {
 
 pub async fn list_actions(
     State(state): State<Arc<AppState>>,
-    Query(query): Query<ActionListQuery>,
-) -> Result<Json<ActionListResponse>, (StatusCode, String)> {
+    Extension(principal): Extension<Principal>,
+    ApiQuery(query): ApiQuery<ActionListQuery>,
+) -> Result<Json<ActionListResponse>, ApiError> {
+    let tenant_id = principal.scope_tenant_filter(query.tenant_id.as_deref())?;
     let actions = orchestrator::list_actions(
         &state.db,
-        query.tenant_id.as_deref(),
+        tenant_id.as_deref(),
         query.status.as_deref(),
         query.limit.unwrap_or(100).min(1000),
     )
-    .await
-    .map_err(internal_error)?;
+    .await?;
     Ok(Json(ActionListResponse { actions }))
 }
 
+/// The executor's callback. Admin only: a tenant reporting results for its
+/// own actions could mark them done without anything having run.
 pub async fn update_action_result(
     State(state): State<Arc<AppState>>,
+    Extension(principal): Extension<Principal>,
     Path(action_id): Path<String>,
-    Json(req): Json<ActionResultRequest>,
-) -> Result<StatusCode, (StatusCode, String)> {
+    ApiJson(req): ApiJson<ActionResultRequest>,
+) -> Result<StatusCode, ApiError> {
+    principal.require_admin()?;
     orchestrator::update_action_result(
         &state.db,
         &action_id,
@@ -88,29 +206,220 @@ pub async fn update_action_result(
         req.reason_code.as_deref(),
         req.reason_message.as_deref(),
     )
-    .await
-    .map_err(internal_error)?;
+    .await?;
     Ok(StatusCode::OK)
 }
 
+/// `POST /v1/orchestrator/actions/{id}:cancel`, `{id}:requeue` and
+/// `{id}:replay`. axum can't route on a suffix within a segment, so the verb
+/// is split off here.
+pub async fn action_command(
+    State(state): State<Arc<AppState>>,
+    Extension(principal): Extension<Principal>,
+    Path(target): Path<String>,
+) -> Result<Json<OrchestratorAction>, ApiError> {
+    let not_found = || ApiError::NotFound(format!("No route for action command {}", target));
+    let (action_id, command) = target.rsplit_once(':').ok_or_else(not_found)?;
+    if !["cancel", "requeue", "replay"].contains(&command) {
+        return Err(not_found());
+    }
+    let tenant_id = orchestrator::action_tenant(&state.db, action_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Action {} not found", action_id)))?;
+    principal.require_tenant(&tenant_id)?;
+    let action = match command {
+        "cancel" => orchestrator::cancel_action(&state.db, action_id).await?,
+        "requeue" => orchestrator::requeue_action(&state.db, action_id).await?,
+        _ => orchestrator::replay_action(&state.db, action_id).await?,
+    };
+    Ok(Json(action))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeadLetterListQuery {
+    pub tenant_id: Option<String>,
+    pub limit: Option<usize>,
+}
+
+pub async fn list_dead_letters(
+    State(state): State<Arc<AppState>>,
+    Extension(principal): Extension<Principal>,
+    ApiQuery(query): ApiQuery<DeadLetterListQuery>,
+) -> Result<Json<DeadLetterListResponse>, ApiError> {
+    let tenant_id = principal.scope_tenant_filter(query.tenant_id.as_deref())?;
+    let dead_letters = orchestrator::list_dead_letters(
+        &state.db,
+        tenant_id.as_deref(),
+        query.limit.unwrap_or(100).min(1000),
+    )
+    .await?;
+    Ok(Json(DeadLetterListResponse { dead_letters }))
+}
+
+pub async fn simulate(
+    State(state): State<Arc<AppState>>,
+    Extension(principal): Extension<Principal>,
+    ApiJson(req): ApiJson<SimulationRequest>,
+) -> Result<Json<SimulationResponse>, ApiError> {
+    for tenant_id in req
+        .policies
+        .iter()
+        .map(|p| &p.tenant_id)
+        .chain(req.slos.iter().map(|s| &s.tenant_id))
+        .chain(req.workloads.iter().map(|w| &w.tenant_id))
+    {
+        principal.require_tenant(tenant_id)?;
+    }
+    if !req.node_groups.is_empty() {
+        principal.require_admin()?;
+    }
+    let tenant_id = principal.scope_tenant_filter(None)?;
+    let response = orchestrator::simulate(
+        &state.db,
+        &state.config.current(),
+        req,
+        tenant_id.as_deref(),
+    )
+    .await?;
+    Ok(Json(response))
+}
+
+/// Manual loop runs go through the leader like the scheduled ones, so a
+/// follower never runs a loop alongside it.
+fn require_leader(state: &AppState) -> Result<(), ApiError> {
+    if state.leader.is_leader() {
+        return Ok(());
+    }
+    Err(ApiError::Conflict(format!(
+        "instance {} is not the orchestrator leader; send this to the leader",
+        state.leader.instance_id()
+    )))
+}
+
 pub async fn trigger_fast_loop(
     State(state): State<Arc<AppState>>,
-) -> Result<StatusCode, (StatusCode, String)> {
-    orchestrator::run_fast_loop(&state.db)
-        .await
-        .map_err(internal_error)?;
+    Extension(principal): Extension<Principal>,
+) -> Result<StatusCode, ApiError> {
+    principal.require_admin()?;
+    require_leader(&state)?;
+    orchestrator::run_fast_loop(&state.db, &state.config.current()).await?;
     Ok(StatusCode::ACCEPTED)
 }
 
 pub async fn trigger_slow_loop(
     State(state): State<Arc<AppState>>,
-) -> Result<StatusCode, (StatusCode, String)> {
-    orchestrator::run_slow_loop(&state.db)
-        .await
-        .map_err(internal_error)?;
+    Extension(principal): Extension<Principal>,
+) -> Result<StatusCode, ApiError> {
+    principal.require_admin()?;
+    require_leader(&state)?;
+    orchestrator::run_slow_loop(&state.db, &state.config.current()).await?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+async fn config_response(
+    state: &AppState,
+    tenant_id: Option<&str>,
+) -> Result<OrchestratorConfigResponse, ApiError> {
+    Ok(OrchestratorConfigResponse {
+        config: (*state.config.current()).clone(),
+        source: state.config.source().map(|p| p.display().to_string()),
+        loaded_at: state.config.loaded_at(),
+        tenant_overrides: orchestrator_config::list_tenant_overrides(&state.db, tenant_id).await?,
+    })
+}
+
+pub async fn get_config(
+    State(state): State<Arc<AppState>>,
+    Extension(principal): Extension<Principal>,
+) -> Result<Json<OrchestratorConfigResponse>, ApiError> {
+    let tenant_id = principal.scope_tenant_filter(None)?;
+    Ok(Json(config_response(&state, tenant_id.as_deref()).await?))
+}
+
+/// Re-read the config file. A file that fails to parse or validate is
+/// rejected and the running config is kept.
+pub async fn reload_config(
+    State(state): State<Arc<AppState>>,
+    Extension(principal): Extension<Principal>,
+) -> Result<Json<OrchestratorConfigResponse>, ApiError> {
+    principal.require_admin()?;
+    if let Err(err) = state.config.reload() {
+        if err.downcast_ref::<ServiceError>().is_some() {
+            return Err(err.into());
+        }
+        return Err(ApiError::BadRequest(format!("{:#}", err)));
+    }
+    Ok(Json(config_response(&state, None).await?))
+}
+
+pub async fn upsert_tenant_tuning(
+    State(state): State<Arc<AppState>>,
+    Extension(principal): Extension<Principal>,
+    Path(tenant_id): Path<String>,
+    ApiJson(req): ApiJson<TuningOverrides>,
+) -> Result<StatusCode, ApiError> {
+    principal.require_admin()?;
+    orchestrator_config::upsert_tenant_overrides(
+        &state.db,
+        &state.config.current().tuning,
+        &tenant_id,
+        req,
+    )
+    .await?;
     Ok(StatusCode::ACCEPTED)
 }
 
-fn internal_error(err: anyhow::Error) -> (StatusCode, String) {
-    (StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+pub async fn delete_tenant_tuning(
+    State(state): State<Arc<AppState>>,
+    Extension(principal): Extension<Principal>,
+    Path(tenant_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    principal.require_admin()?;
+    orchestrator_config::delete_tenant_overrides(&state.db, &tenant_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn leader_status(
+    State(state): State<Arc<AppState>>,
+    Extension(principal): Extension<Principal>,
+) -> Result<Json<LeaderStatusResponse>, ApiError> {
+    principal.require_admin()?;
+    let status = state.leader.status().await?;
+    Ok(Json(status))
+}
+
+pub async fn create_api_key(
+    State(state): State<Arc<AppState>>,
+    Extension(principal): Extension<Principal>,
+    ApiJson(req): ApiJson<CreateApiKeyRequest>,
+) -> Result<(StatusCode, Json<CreateApiKeyResponse>), ApiError> {
+    principal.require_admin()?;
+    let created = api_keys::create_key(&state.db, req).await?;
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+pub async fn list_api_keys(
+    State(state): State<Arc<AppState>>,
+    Extension(principal): Extension<Principal>,
+    ApiQuery(query): ApiQuery<TenantFilterQuery>,
+) -> Result<Json<ApiKeyListResponse>, ApiError> {
+    principal.require_admin()?;
+    let api_keys = api_keys::list_keys(&state.db, query.tenant_id.as_deref()).await?;
+    Ok(Json(ApiKeyListResponse { api_keys }))
+}
+
+pub async fn revoke_api_key(
+    State(state): State<Arc<AppState>>,
+    Extension(principal): Extension<Principal>,
+    Path(key_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    principal.require_admin()?;
+    let revoked = api_keys::revoke_key(&state.db, &key_id).await?;
+    if !revoked {
+        return Err(ApiError::NotFound(format!(
+            "API key {} not found or already revoked",
+            key_id
+        )));
+    }
+    Ok(StatusCode::NO_CONTENT)
 }
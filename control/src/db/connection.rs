@@ -0,0 +1,743 @@
+//! Backend-neutral connection wrapper.
+//!
+//! Service code is written once against [`Connection`] using SQLite-style
+//! numbered placeholders (`?1`, `?2`, ...). On PostgreSQL the placeholders are
+//! rewritten to `$1`, `$2`, ... and values are encoded to whatever column type
+//! the server inferred, so the same SQL runs on both backends as long as it
+//! sticks to the common dialect (`ON CONFLICT ... DO UPDATE`, `RETURNING`,
+//! `COALESCE`, no `PRAGMA`/`AUTOINCREMENT`).
+
+use anyhow::{Context, Result};
+use postgres::types::{FromSql as PgFromSql, IsNull, ToSql as PgToSql, Type};
+use postgres::NoTls;
+use r2d2::PooledConnection;
+use r2d2_sqlite::SqliteConnectionManager;
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
+use std::ops::{Deref, DerefMut};
+
+/// A bound parameter or a column value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+}
+
+pub trait ToValue {
+    fn to_value(&self) -> Value;
+}
+
+pub trait FromValue: Sized {
+    fn from_value(value: &Value) -> Result<Self>;
+}
+
+macro_rules! integer_value {
+    ($($t:ty),*) => {$(
+        impl ToValue for $t {
+            fn to_value(&self) -> Value {
+                Value::Integer(*self as i64)
+            }
+        }
+        impl FromValue for $t {
+            fn from_value(value: &Value) -> Result<Self> {
+                match value {
+                    Value::Integer(v) => <$t>::try_from(*v)
+                        .with_context(|| format!("integer {} out of range", v)),
+                    other => anyhow::bail!("expected integer, got {:?}", other),
+                }
+            }
+        }
+    )*};
+}
+
+integer_value!(i32, i64, u8, u32, u64, usize);
+
+impl ToValue for bool {
+    fn to_value(&self) -> Value {
+        Value::Integer(i64::from(*self))
+    }
+}
+
+impl FromValue for bool {
+    fn from_value(value: &Value) -> Result<Self> {
+        Ok(i64::from_value(value)? != 0)
+    }
+}
+
+impl ToValue for f64 {
+    fn to_value(&self) -> Value {
+        Value::Real(*self)
+    }
+}
+
+impl FromValue for f64 {
+    fn from_value(value: &Value) -> Result<Self> {
+        match value {
+            Value::Real(v) => Ok(*v),
+            Value::Integer(v) => Ok(*v as f64),
+            other => anyhow::bail!("expected real, got {:?}", other),
+        }
+    }
+}
+
+impl ToValue for str {
+    fn to_value(&self) -> Value {
+        Value::Text(self.to_string())
+    }
+}
+
+impl ToValue for String {
+    fn to_value(&self) -> Value {
+        Value::Text(self.clone())
+    }
+}
+
+impl FromValue for String {
+    fn from_value(value: &Value) -> Result<Self> {
+        match value {
+            Value::Text(v) => Ok(v.clone()),
+            other => anyhow::bail!("expected text, got {:?}", other),
+        }
+    }
+}
+
+impl<T: ToValue + ?Sized> ToValue for &T {
+    fn to_value(&self) -> Value {
+        (**self).to_value()
+    }
+}
+
+impl<T: ToValue> ToValue for Option<T> {
+    fn to_value(&self) -> Value {
+        match self {
+            Some(v) => v.to_value(),
+            None => Value::Null,
+        }
+    }
+}
+
+impl<T: FromValue> FromValue for Option<T> {
+    fn from_value(value: &Value) -> Result<Self> {
+        match value {
+            Value::Null => Ok(None),
+            other => T::from_value(other).map(Some),
+        }
+    }
+}
+
+impl ToValue for Value {
+    fn to_value(&self) -> Value {
+        self.clone()
+    }
+}
+
+/// Build a parameter slice, mirroring `rusqlite::params!`.
+macro_rules! params {
+    () => {
+        &[] as &[$crate::db::Value]
+    };
+    ($($param:expr),+ $(,)?) => {
+        &[$($crate::db::ToValue::to_value(&$param)),+] as &[$crate::db::Value]
+    };
+}
+pub(crate) use params;
+
+/// A materialized result row.
+pub struct Row {
+    values: Vec<Value>,
+}
+
+impl Row {
+    pub fn get<T: FromValue>(&self, idx: usize) -> Result<T> {
+        let value = self
+            .values
+            .get(idx)
+            .with_context(|| format!("column {} out of range", idx))?;
+        T::from_value(value).with_context(|| format!("invalid value in column {}", idx))
+    }
+}
+
+/// Returned by [`Connection::query_row`] when the query produced no rows.
+#[derive(Debug, thiserror::Error)]
+#[error("query returned no rows")]
+pub struct QueryReturnedNoRows;
+
+/// Turns a "no rows" error into `Ok(None)`, like rusqlite's extension trait.
+pub trait OptionalExtension<T> {
+    fn optional(self) -> Result<Option<T>>;
+}
+
+impl<T> OptionalExtension<T> for Result<T> {
+    fn optional(self) -> Result<Option<T>> {
+        match self {
+            Ok(v) => Ok(Some(v)),
+            Err(e) if e.is::<QueryReturnedNoRows>() => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    Sqlite,
+    Postgres,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionBehavior {
+    Deferred,
+    /// Lock the named resource when the transaction starts so read-then-write
+    /// sequences on it serialize across writers: `BEGIN IMMEDIATE` on SQLite,
+    /// where that is the database write lock, and a lock on just that
+    /// resource on PostgreSQL (see [`Connection::lock_until_commit`]).
+    Immediate(&'static str),
+}
+
+enum SqliteHandle {
+    Pooled(PooledConnection<SqliteConnectionManager>),
+    #[cfg(test)]
+    Owned(rusqlite::Connection),
+}
+
+impl Deref for SqliteHandle {
+    type Target = rusqlite::Connection;
+
+    fn deref(&self) -> &rusqlite::Connection {
+        match self {
+            SqliteHandle::Pooled(c) => c,
+            #[cfg(test)]
+            SqliteHandle::Owned(c) => c,
+        }
+    }
+}
+
+/// r2d2 manager for synchronous PostgreSQL clients.
+#[derive(Debug)]
+pub struct PostgresManager {
+    config: postgres::Config,
+}
+
+impl PostgresManager {
+    pub fn new(config: postgres::Config) -> Self {
+        Self { config }
+    }
+}
+
+impl r2d2::ManageConnection for PostgresManager {
+    type Connection = PgClient;
+    type Error = postgres::Error;
+
+    fn connect(&self) -> std::result::Result<PgClient, postgres::Error> {
+        self.config.connect(NoTls).map(|c| PgClient(Some(c)))
+    }
+
+    fn is_valid(&self, conn: &mut PgClient) -> std::result::Result<(), postgres::Error> {
+        conn.simple_query("").map(|_| ())
+    }
+
+    fn has_broken(&self, conn: &mut PgClient) -> bool {
+        conn.is_closed()
+    }
+}
+
+/// `postgres::Client` blocks on an internal runtime when dropped, which
+/// panics on a tokio worker thread. Pools can be dropped from async code, so
+/// hand the close-out to a plain thread whenever a runtime is active.
+pub struct PgClient(Option<postgres::Client>);
+
+impl Deref for PgClient {
+    type Target = postgres::Client;
+
+    fn deref(&self) -> &postgres::Client {
+        self.0.as_ref().expect("client present until drop")
+    }
+}
+
+impl DerefMut for PgClient {
+    fn deref_mut(&mut self) -> &mut postgres::Client {
+        self.0.as_mut().expect("client present until drop")
+    }
+}
+
+impl Drop for PgClient {
+    fn drop(&mut self) {
+        if let Some(client) = self.0.take() {
+            if tokio::runtime::Handle::try_current().is_ok() {
+                std::thread::spawn(move || drop(client));
+            }
+        }
+    }
+}
+
+pub type PgPooledConnection = PooledConnection<PostgresManager>;
+
+pub struct Connection {
+    inner: Inner,
+}
+
+enum Inner {
+    Sqlite(SqliteHandle),
+    Postgres(Box<RefCell<PgPooledConnection>>),
+}
+
+impl Connection {
+    pub(crate) fn from_sqlite(conn: PooledConnection<SqliteConnectionManager>) -> Self {
+        Self {
+            inner: Inner::Sqlite(SqliteHandle::Pooled(conn)),
+        }
+    }
+
+    pub(crate) fn from_postgres(conn: PgPooledConnection) -> Self {
+        Self {
+            inner: Inner::Postgres(Box::new(RefCell::new(conn))),
+        }
+    }
+
+    /// Private in-memory SQLite database, used by unit tests.
+    #[cfg(test)]
+    pub fn open_in_memory() -> Result<Self> {
+        Ok(Self {
+            inner: Inner::Sqlite(SqliteHandle::Owned(rusqlite::Connection::open_in_memory()?)),
+        })
+    }
+
+    pub fn backend(&self) -> Backend {
+        match self.inner {
+            Inner::Sqlite(_) => Backend::Sqlite,
+            Inner::Postgres(_) => Backend::Postgres,
+        }
+    }
+
+    pub fn execute_batch(&self, sql: &str) -> Result<()> {
+        match &self.inner {
+            Inner::Sqlite(c) => c.execute_batch(sql)?,
+            Inner::Postgres(c) => c.borrow_mut().batch_execute(sql)?,
+        }
+        Ok(())
+    }
+
+    pub fn execute(&self, sql: &str, params: &[Value]) -> Result<usize> {
+        match &self.inner {
+            Inner::Sqlite(c) => {
+                let mut stmt = c.prepare_cached(sql)?;
+                Ok(stmt.execute(rusqlite::params_from_iter(params.iter().map(sqlite_value)))?)
+            }
+            Inner::Postgres(c) => {
+                let sql = rewrite_placeholders(sql);
+                let args = pg_args(params);
+                Ok(c.borrow_mut().execute(sql.as_str(), &args)? as usize)
+            }
+        }
+    }
+
+    pub fn query_map<T, F>(&self, sql: &str, params: &[Value], mut f: F) -> Result<Vec<T>>
+    where
+        F: FnMut(&Row) -> Result<T>,
+    {
+        self.query_rows(sql, params)?.iter().map(&mut f).collect()
+    }
+
+    pub fn query_row<T, F>(&self, sql: &str, params: &[Value], f: F) -> Result<T>
+    where
+        F: FnOnce(&Row) -> Result<T>,
+    {
+        let rows = self.query_rows(sql, params)?;
+        let row = rows.first().ok_or(QueryReturnedNoRows)?;
+        f(row)
+    }
+
+    fn query_rows(&self, sql: &str, params: &[Value]) -> Result<Vec<Row>> {
+        match &self.inner {
+            Inner::Sqlite(c) => {
+                let mut stmt = c.prepare_cached(sql)?;
+                let width = stmt.column_count();
+                let mut rows =
+                    stmt.query(rusqlite::params_from_iter(params.iter().map(sqlite_value)))?;
+                let mut out = Vec::new();
+                while let Some(row) = rows.next()? {
+                    let values = (0..width)
+                        .map(|i| Ok(value_from_sqlite(row.get_ref(i)?)))
+                        .collect::<Result<Vec<_>>>()?;
+                    out.push(Row { values });
+                }
+                Ok(out)
+            }
+            Inner::Postgres(c) => {
+                let sql = rewrite_placeholders(sql);
+                let args = pg_args(params);
+                let rows = c.borrow_mut().query(sql.as_str(), &args)?;
+                rows.iter()
+                    .map(|row| {
+                        let values = (0..row.len())
+                            .map(|i| value_from_pg(row, i))
+                            .collect::<Result<Vec<_>>>()?;
+                        Ok(Row { values })
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    pub fn transaction(&self) -> Result<Transaction<'_>> {
+        self.transaction_with_behavior(TransactionBehavior::Deferred)
+    }
+
+    pub fn transaction_with_behavior(
+        &self,
+        behavior: TransactionBehavior,
+    ) -> Result<Transaction<'_>> {
+        match (self.backend(), behavior) {
+            (Backend::Sqlite, TransactionBehavior::Deferred) => self.execute_batch("BEGIN")?,
+            (Backend::Sqlite, TransactionBehavior::Immediate(_)) => {
+                self.execute_batch("BEGIN IMMEDIATE")?
+            }
+            (Backend::Postgres, TransactionBehavior::Deferred) => self.execute_batch("BEGIN")?,
+            (Backend::Postgres, TransactionBehavior::Immediate(resource)) => {
+                self.execute_batch("BEGIN")?;
+                self.lock_until_commit(resource)?;
+            }
+        }
+        Ok(Transaction {
+            conn: self,
+            finished: Cell::new(false),
+        })
+    }
+
+    /// Hold a lock named `key` until the current transaction ends, so
+    /// writers of the same resource serialize. A no-op on SQLite, where a
+    /// writing transaction already excludes every other writer. Outside a
+    /// transaction the lock is released as soon as it is taken.
+    pub fn lock_until_commit(&self, key: &str) -> Result<()> {
+        if self.backend() == Backend::Postgres {
+            self.query_row(
+                "SELECT pg_advisory_xact_lock(hashtext(?1))::TEXT",
+                params![key],
+                |_| Ok(()),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Column names of `table`, used for schema validation.
+    pub fn table_columns(&self, table: &str) -> Result<HashSet<String>> {
+        let cols = match self.backend() {
+            Backend::Sqlite => {
+                self.query_map(&format!("PRAGMA table_info({})", table), params![], |row| {
+                    row.get::<String>(1)
+                })?
+            }
+            Backend::Postgres => self.query_map(
+                "SELECT column_name::TEXT FROM information_schema.columns
+                 WHERE table_schema = current_schema() AND table_name = ?1",
+                params![table],
+                |row| row.get::<String>(0),
+            )?,
+        };
+        Ok(cols.into_iter().collect())
+    }
+}
+
+/// Transaction guard that rolls back on drop unless committed. Like
+/// rusqlite's `unchecked_transaction`, nesting is not supported.
+pub struct Transaction<'a> {
+    conn: &'a Connection,
+    finished: Cell<bool>,
+}
+
+impl Transaction<'_> {
+    pub fn commit(self) -> Result<()> {
+        self.finished.set(true);
+        self.conn.execute_batch("COMMIT")
+    }
+}
+
+impl Deref for Transaction<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn
+    }
+}
+
+impl Drop for Transaction<'_> {
+    fn drop(&mut self) {
+        if !self.finished.get() {
+            let _ = self.conn.execute_batch("ROLLBACK");
+        }
+    }
+}
+
+fn sqlite_value(value: &Value) -> rusqlite::types::Value {
+    match value {
+        Value::Null => rusqlite::types::Value::Null,
+        Value::Integer(v) => rusqlite::types::Value::Integer(*v),
+        Value::Real(v) => rusqlite::types::Value::Real(*v),
+        Value::Text(v) => rusqlite::types::Value::Text(v.clone()),
+    }
+}
+
+fn value_from_sqlite(value: rusqlite::types::ValueRef<'_>) -> Value {
+    match value {
+        rusqlite::types::ValueRef::Null => Value::Null,
+        rusqlite::types::ValueRef::Integer(v) => Value::Integer(v),
+        rusqlite::types::ValueRef::Real(v) => Value::Real(v),
+        rusqlite::types::ValueRef::Text(v) | rusqlite::types::ValueRef::Blob(v) => {
+            Value::Text(String::from_utf8_lossy(v).into_owned())
+        }
+    }
+}
+
+fn value_from_pg(row: &postgres::Row, idx: usize) -> Result<Value> {
+    let ty = row.columns()[idx].type_();
+    let value = match *ty {
+        Type::INT2 => row
+            .try_get::<_, Option<i16>>(idx)?
+            .map(|v| Value::Integer(v.into())),
+        Type::INT4 => row
+            .try_get::<_, Option<i32>>(idx)?
+            .map(|v| Value::Integer(v.into())),
+        Type::INT8 => row.try_get::<_, Option<i64>>(idx)?.map(Value::Integer),
+        Type::FLOAT4 => row
+            .try_get::<_, Option<f32>>(idx)?
+            .map(|v| Value::Real(v.into())),
+        Type::FLOAT8 => row.try_get::<_, Option<f64>>(idx)?.map(Value::Real),
+        Type::NUMERIC => row.try_get::<_, Option<PgNumeric>>(idx)?.map(|v| v.0),
+        Type::BOOL => row
+            .try_get::<_, Option<bool>>(idx)?
+            .map(|v| Value::Integer(v.into())),
+        Type::TEXT | Type::VARCHAR | Type::BPCHAR | Type::NAME => {
+            row.try_get::<_, Option<String>>(idx)?.map(Value::Text)
+        }
+        ref other => anyhow::bail!("unsupported PostgreSQL column type {} at {}", other, idx),
+    };
+    Ok(value.unwrap_or(Value::Null))
+}
+
+/// A NUMERIC column, which aggregates such as `SUM(bigint)` return. Decoded
+/// the way SQLite would hand the same value back: an integer when it is whole
+/// and fits, a real otherwise.
+struct PgNumeric(Value);
+
+impl<'a> PgFromSql<'a> for PgNumeric {
+    fn from_sql(
+        _ty: &Type,
+        raw: &'a [u8],
+    ) -> std::result::Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        // Binary layout: ndigits, weight, sign, dscale, then `ndigits`
+        // base-10000 digits, most significant first, the first of which is
+        // worth 10000^weight.
+        let word = |i: usize| {
+            raw.get(2 * i..2 * i + 2)
+                .map(|b| i16::from_be_bytes([b[0], b[1]]))
+                .ok_or("truncated NUMERIC value")
+        };
+        let (ndigits, weight, sign, dscale) = (word(0)?, word(1)?, word(2)? as u16, word(3)?);
+        let negative = match sign {
+            0x0000 => false,
+            0x4000 => true,
+            0xC000 => return Ok(PgNumeric(Value::Real(f64::NAN))),
+            0xD000 => return Ok(PgNumeric(Value::Real(f64::INFINITY))),
+            0xF000 => return Ok(PgNumeric(Value::Real(f64::NEG_INFINITY))),
+            other => return Err(format!("invalid NUMERIC sign {:#x}", other).into()),
+        };
+        let digits = (0..ndigits.max(0) as usize)
+            .map(|i| word(4 + i).map(i128::from))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        if dscale == 0 {
+            let whole = (0..=weight.max(-1) as isize).try_fold(0_i128, |acc, i| {
+                acc.checked_mul(10_000)?
+                    .checked_add(digits.get(i as usize).copied().unwrap_or(0))
+            });
+            if let Some(v) = whole.and_then(|v| i64::try_from(if negative { -v } else { v }).ok()) {
+                return Ok(PgNumeric(Value::Integer(v)));
+            }
+        }
+        let magnitude: f64 = digits
+            .iter()
+            .enumerate()
+            .map(|(i, d)| *d as f64 * 10_000_f64.powi(weight as i32 - i as i32))
+            .sum();
+        Ok(PgNumeric(Value::Real(if negative {
+            -magnitude
+        } else {
+            magnitude
+        })))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::NUMERIC
+    }
+}
+
+/// Rewrite `?N` placeholders to `$N`, leaving string literals untouched.
+fn rewrite_placeholders(sql: &str) -> String {
+    let mut out = String::with_capacity(sql.len());
+    let mut in_literal = false;
+    let mut chars = sql.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_literal = !in_literal;
+                out.push(c);
+            }
+            '?' if !in_literal && chars.peek().is_some_and(char::is_ascii_digit) => out.push('$'),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn pg_args(params: &[Value]) -> Vec<&(dyn PgToSql + Sync)> {
+    params.iter().map(|v| v as &(dyn PgToSql + Sync)).collect()
+}
+
+impl PgToSql for Value {
+    fn to_sql(
+        &self,
+        ty: &Type,
+        out: &mut bytes::BytesMut,
+    ) -> std::result::Result<IsNull, Box<dyn std::error::Error + Sync + Send>> {
+        match (self, ty) {
+            (Value::Null, _) => Ok(IsNull::Yes),
+            (Value::Integer(v), &Type::INT2) => i16::try_from(*v)?.to_sql(ty, out),
+            (Value::Integer(v), &Type::INT4) => i32::try_from(*v)?.to_sql(ty, out),
+            (Value::Integer(v), &Type::INT8) => v.to_sql(ty, out),
+            (Value::Integer(v), &Type::FLOAT4) => (*v as f32).to_sql(ty, out),
+            (Value::Integer(v), &Type::FLOAT8) => (*v as f64).to_sql(ty, out),
+            (Value::Integer(v), &Type::BOOL) => (*v != 0).to_sql(ty, out),
+            (Value::Integer(v), _) => v.to_string().to_sql(ty, out),
+            (Value::Real(v), &Type::FLOAT4) => (*v as f32).to_sql(ty, out),
+            (Value::Real(v), &Type::FLOAT8) => v.to_sql(ty, out),
+            (Value::Real(v), _) => v.to_string().to_sql(ty, out),
+            (Value::Text(v), _) => v.to_sql(ty, out),
+        }
+    }
+
+    fn accepts(_ty: &Type) -> bool {
+        true
+    }
+
+    postgres::types::to_sql_checked!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholders_are_rewritten_outside_literals() {
+        assert_eq!(
+            rewrite_placeholders("SELECT '?1' FROM t WHERE a = ?1 AND b = ?12"),
+            "SELECT '?1' FROM t WHERE a = $1 AND b = $12"
+        );
+    }
+
+    #[test]
+    fn sqlite_round_trip_and_optional() {
+        let conn = Connection::open_in_memory().expect("sqlite");
+        conn.execute_batch("CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT, score REAL)")
+            .expect("create");
+        let tx = conn.transaction().expect("begin");
+        tx.execute(
+            "INSERT INTO t (id, name, score) VALUES (?1, ?2, ?3)",
+            params![1_u32, "a", 0.5],
+        )
+        .expect("insert");
+        drop(tx);
+        let missing = conn
+            .query_row("SELECT name FROM t WHERE id = ?1", params![1], |row| {
+                row.get::<String>(0)
+            })
+            .optional()
+            .expect("query");
+        assert_eq!(missing, None, "dropped transaction must roll back");
+
+        conn.execute(
+            "INSERT INTO t (id, name, score) VALUES (?1, ?2, ?3)",
+            params![2, None::<String>, 3],
+        )
+        .expect("insert");
+        let (name, score) = conn
+            .query_row(
+                "SELECT name, score FROM t WHERE id = ?1",
+                params![2],
+                |row| Ok((row.get::<Option<String>>(0)?, row.get::<f64>(1)?)),
+            )
+            .expect("query");
+        assert_eq!(name, None);
+        assert_eq!(score, 3.0);
+    }
+
+    fn numeric(ndigits: i16, weight: i16, sign: u16, dscale: i16, digits: &[i16]) -> Value {
+        let mut raw = Vec::new();
+        for word in [ndigits, weight, sign as i16, dscale].iter().chain(digits) {
+            raw.extend_from_slice(&word.to_be_bytes());
+        }
+        PgNumeric::from_sql(&Type::NUMERIC, &raw).expect("decode").0
+    }
+
+    #[test]
+    fn numeric_decodes_like_sqlite_would_store_it() {
+        assert_eq!(numeric(0, 0, 0, 0, &[]), Value::Integer(0));
+        // 123456789 = 1|2345|6789
+        assert_eq!(
+            numeric(3, 2, 0, 0, &[1, 2345, 6789]),
+            Value::Integer(123_456_789)
+        );
+        // -20000 = 2|0000, trailing zero group stripped
+        assert_eq!(numeric(1, 1, 0x4000, 0, &[2]), Value::Integer(-20_000));
+        // 12.5 = 12.5000
+        assert_eq!(numeric(2, 0, 0, 1, &[12, 5000]), Value::Real(12.5));
+        // -0.0025
+        assert_eq!(numeric(1, -1, 0x4000, 4, &[25]), Value::Real(-0.0025));
+        // Whole but wider than i64.
+        assert_eq!(
+            numeric(6, 5, 0, 0, &[1, 0, 0, 0, 0, 1]),
+            Value::Real(1e20 + 1.0)
+        );
+        assert!(matches!(numeric(0, 0, 0xC000, 0, &[]), Value::Real(v) if v.is_nan()));
+    }
+
+    #[test]
+    #[ignore = "needs PostgreSQL: set QUILT_TEST_POSTGRES_URL and run with --ignored"]
+    fn postgres_column_types_round_trip() {
+        let pool = crate::db::postgres_test_pool("column_types");
+        let conn = pool.get().expect("connection");
+        conn.execute_batch(
+            "CREATE TABLE column_types (id BIGINT PRIMARY KEY, small SMALLINT, single REAL, exact NUMERIC)",
+        )
+        .expect("create");
+        conn.execute(
+            "INSERT INTO column_types (id, small, single, exact) VALUES (?1, ?2, ?3, 12.5)",
+            params![1, 7, 0.25],
+        )
+        .expect("insert");
+        conn.execute(
+            "INSERT INTO column_types (id, small, single, exact) VALUES (?1, ?2, ?3, NULL)",
+            params![2, -3, None::<f64>],
+        )
+        .expect("insert");
+
+        let rows: Vec<(i64, Option<f64>, Option<f64>)> = conn
+            .query_map(
+                "SELECT small, single, exact FROM column_types ORDER BY id",
+                params![],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .expect("select");
+        assert_eq!(rows, [(7, Some(0.25), Some(12.5)), (-3, None, None)]);
+
+        // SUM and AVG over BIGINT come back as NUMERIC.
+        let (sum, avg): (i64, f64) = conn
+            .query_row(
+                "SELECT SUM(id), AVG(id) FROM column_types",
+                params![],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .expect("aggregate");
+        assert_eq!((sum, avg), (3, 1.5));
+    }
+}
@@ -0,0 +1,186 @@
+use anyhow::{Context, Result};
+use ipnet::Ipv4Net;
+use std::collections::HashMap;
+use tracing::info;
+
+use super::{params, Connection};
+
+/// Per-node subnet allocator backed by the `subnet_allocation` table.
+///
+/// Subnets are carved out of `cluster_cidr` at `subnet_prefix`. The first
+/// subnet of the cluster CIDR is reserved and never handed out. A subnet that
+/// was released (node deregistered or declared dead) is only reclaimed once
+/// `quarantine_seconds` have passed, so peers have time to drop routes and FDB
+/// entries pointing at the old owner.
+#[derive(Debug, Clone)]
+pub struct SubnetAllocator {
+    cluster_cidr: Ipv4Net,
+    subnet_prefix: u8,
+    quarantine_seconds: i64,
+}
+
+/// Result of an allocation. `reclaimed_from` carries the previous owner when
+/// a quarantined subnet was reused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubnetLease {
+    pub subnet: String,
+    pub reclaimed_from: Option<String>,
+}
+
+impl SubnetAllocator {
+    pub fn new(cluster_cidr: &str, subnet_prefix: u8, quarantine_seconds: u64) -> Result<Self> {
+        let cluster_cidr: Ipv4Net = cluster_cidr
+            .parse()
+            .with_context(|| format!("Invalid cluster CIDR: {cluster_cidr}"))?;
+        let cluster_cidr = cluster_cidr.trunc();
+        if subnet_prefix <= cluster_cidr.prefix_len() || subnet_prefix > 30 {
+            anyhow::bail!(
+                "Node subnet prefix /{} must be longer than cluster CIDR {} and at most /30",
+                subnet_prefix,
+                cluster_cidr
+            );
+        }
+        Ok(Self {
+            cluster_cidr,
+            subnet_prefix,
+            quarantine_seconds: quarantine_seconds as i64,
+        })
+    }
+
+    fn candidates(&self) -> impl Iterator<Item = Ipv4Net> {
+        self.cluster_cidr
+            .subnets(self.subnet_prefix)
+            .expect("prefix validated in SubnetAllocator::new")
+            .skip(1)
+    }
+
+    /// Allocate the lowest free subnet for `node_id`. Must run inside a write
+    /// transaction so concurrent registrations serialize.
+    pub fn allocate(&self, conn: &Connection, node_id: &str, now: i64) -> Result<SubnetLease> {
+        let existing: HashMap<_, _> = conn
+            .query_map(
+                "SELECT subnet, node_id, released_at FROM subnet_allocation",
+                params![],
+                |row| {
+                    Ok((
+                        row.get::<String>(0)?,
+                        (row.get::<String>(1)?, row.get::<Option<i64>>(2)?),
+                    ))
+                },
+            )?
+            .into_iter()
+            .collect();
+
+        let reclaim_before = now - self.quarantine_seconds;
+        for candidate in self.candidates() {
+            let subnet = candidate.to_string();
+            match existing.get(&subnet) {
+                None => {
+                    conn.execute(
+                        "INSERT INTO subnet_allocation (subnet, node_id, allocated_at, released_at)
+                         VALUES (?1, ?2, ?3, NULL)",
+                        params![subnet, node_id, now],
+                    )?;
+                    return Ok(SubnetLease {
+                        subnet,
+                        reclaimed_from: None,
+                    });
+                }
+                Some((previous, Some(released_at))) if *released_at <= reclaim_before => {
+                    conn.execute(
+                        "UPDATE subnet_allocation
+                         SET node_id = ?1, allocated_at = ?2, released_at = NULL
+                         WHERE subnet = ?3",
+                        params![node_id, now, subnet],
+                    )?;
+                    info!(
+                        "Reclaimed subnet {} from node {} after quarantine",
+                        subnet, previous
+                    );
+                    return Ok(SubnetLease {
+                        subnet,
+                        reclaimed_from: Some(previous.clone()),
+                    });
+                }
+                Some(_) => continue,
+            }
+        }
+        anyhow::bail!(
+            "Cluster CIDR {} has no free /{} subnets",
+            self.cluster_cidr,
+            self.subnet_prefix
+        )
+    }
+
+    /// Re-activate a subnet still held by `node_id`. Returns `false` if the
+    /// subnet was reclaimed by another node in the meantime.
+    pub fn renew(&self, conn: &Connection, node_id: &str, subnet: &str) -> Result<bool> {
+        let rows = conn.execute(
+            "UPDATE subnet_allocation SET released_at = NULL WHERE subnet = ?1 AND node_id = ?2",
+            params![subnet, node_id],
+        )?;
+        Ok(rows > 0)
+    }
+
+    /// Start the quarantine clock on every subnet held by `node_id`.
+    pub fn release(&self, conn: &Connection, node_id: &str, now: i64) -> Result<()> {
+        conn.execute(
+            "UPDATE subnet_allocation SET released_at = ?1
+             WHERE node_id = ?2 AND released_at IS NULL",
+            params![now, node_id],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allocates_lowest_free_subnet_skipping_reserved_first() {
+        let conn = crate::db::test_conn();
+        let ipam = SubnetAllocator::new("10.42.0.0/16", 24, 60).expect("allocator");
+        let a = ipam.allocate(&conn, "node-a", 100).expect("allocate a");
+        let b = ipam.allocate(&conn, "node-b", 100).expect("allocate b");
+        assert_eq!(a.subnet, "10.42.1.0/24");
+        assert_eq!(b.subnet, "10.42.2.0/24");
+
+        let small = SubnetAllocator::new("10.50.0.0/24", 26, 60).expect("allocator");
+        assert_eq!(
+            small
+                .allocate(&conn, "node-c", 100)
+                .expect("allocate c")
+                .subnet,
+            "10.50.0.64/26"
+        );
+    }
+
+    #[test]
+    fn released_subnet_is_reclaimed_only_after_quarantine() {
+        let conn = crate::db::test_conn();
+        let ipam = SubnetAllocator::new("10.42.0.0/30", 30, 60);
+        assert!(ipam.is_err(), "prefix must be longer than the cluster CIDR");
+
+        let ipam = SubnetAllocator::new("10.42.0.0/29", 30, 60).expect("allocator");
+        let a = ipam.allocate(&conn, "node-a", 100).expect("allocate a");
+        ipam.release(&conn, "node-a", 200).expect("release");
+
+        assert!(
+            ipam.allocate(&conn, "node-b", 259).is_err(),
+            "quarantined subnet must not be handed out"
+        );
+        let b = ipam.allocate(&conn, "node-b", 260).expect("allocate b");
+        assert_eq!(b.subnet, a.subnet);
+        assert_eq!(b.reclaimed_from.as_deref(), Some("node-a"));
+        let owner: String = conn
+            .query_row(
+                "SELECT node_id FROM subnet_allocation WHERE subnet = ?1",
+                params![a.subnet],
+                |row| row.get(0),
+            )
+            .expect("owner");
+        assert_eq!(owner, "node-b");
+        assert!(!ipam.renew(&conn, "node-a", &a.subnet).expect("renew"));
+    }
+}
@@ -0,0 +1,358 @@
+//! Versioned, forward-only schema migrations.
+//!
+//! Every migration runs exactly once, inside its own write-locked transaction
+//! that also records it in `schema_migrations`, so concurrent control plane
+//! instances starting against the same database apply each version once.
+//! Migrations are never edited or removed once released; schema changes go
+//! into a new, higher version.
+
+use anyhow::{Context, Result};
+use tracing::info;
+
+use super::{params, Backend, Connection, OptionalExtension, TransactionBehavior};
+use crate::services::orchestrator::now_unix_seconds;
+
+enum Source {
+    Sql {
+        sqlite: &'static str,
+        postgres: &'static str,
+    },
+    /// For changes plain SQL cannot express idempotently on both backends,
+    /// such as adding a column only when it is missing.
+    Code(fn(&Connection) -> Result<()>),
+}
+
+struct Migration {
+    version: i64,
+    name: &'static str,
+    source: Source,
+}
+
+macro_rules! sql_migration {
+    ($version:expr, $name:literal) => {
+        Migration {
+            version: $version,
+            name: $name,
+            source: Source::Sql {
+                sqlite: include_str!(concat!("../../migrations/", $name, ".sql")),
+                postgres: include_str!(concat!("../../migrations/postgres/", $name, ".sql")),
+            },
+        }
+    };
+}
+
+const MIGRATIONS: &[Migration] = &[
+    sql_migration!(3, "003_orchestrator"),
+    sql_migration!(4, "004_node_registry"),
+    sql_migration!(5, "005_subnet_allocation"),
+    sql_migration!(6, "006_node_status_transition"),
+    sql_migration!(7, "007_leader_lease"),
+    Migration {
+        version: 8,
+        name: "008_orchestrator_upgrade_columns",
+        source: Source::Code(upgrade_orchestrator_columns),
+    },
+    sql_migration!(9, "009_api_keys"),
+    sql_migration!(10, "010_orchestrator_events"),
+    sql_migration!(11, "011_orchestrator_decisions"),
+    sql_migration!(12, "012_observation_history"),
+    sql_migration!(13, "013_orchestrator_tenant_tuning"),
+    sql_migration!(14, "014_action_attempts"),
+    sql_migration!(15, "015_scale_to_zero"),
+    sql_migration!(16, "016_scaling_strategies"),
+    sql_migration!(17, "017_adaptive_warm_pool"),
+    sql_migration!(18, "018_action_supersession_key"),
+];
+
+/// Lock held while checking and applying migrations.
+const MIGRATION_LOCK: &str = "schema_migrations";
+
+/// Columns added to the orchestrator tables after their first release, with
+/// the backfill used for existing rows.
+const ORCHESTRATOR_UPGRADE_COLUMNS: &[(&str, &str, &str)] = &[
+    (
+        "orchestrator_workload_policy",
+        "runtime_function_id",
+        "TEXT NOT NULL DEFAULT ''",
+    ),
+    (
+        "orchestrator_action",
+        "idempotency_key",
+        "TEXT NOT NULL DEFAULT ''",
+    ),
+    (
+        "orchestrator_action",
+        "decision_window_start",
+        "INTEGER NOT NULL DEFAULT 0",
+    ),
+    ("orchestrator_action", "outbound_requested_at", "INTEGER"),
+    ("orchestrator_action", "runtime_operation_id", "TEXT"),
+    ("orchestrator_action", "runtime_operation_type", "TEXT"),
+    ("orchestrator_action", "terminal_status", "TEXT"),
+    ("orchestrator_action", "terminal_at", "INTEGER"),
+    ("orchestrator_action", "total_latency_ms", "INTEGER"),
+];
+
+/// Bring orchestrator tables created by builds that predate versioned
+/// migrations up to the current shape. A no-op on databases created by 003.
+fn upgrade_orchestrator_columns(conn: &Connection) -> Result<()> {
+    for (table, column, definition) in ORCHESTRATOR_UPGRADE_COLUMNS {
+        if conn.table_columns(table)?.contains(*column) {
+            continue;
+        }
+        let definition = match conn.backend() {
+            Backend::Sqlite => definition.to_string(),
+            Backend::Postgres => definition.replace("INTEGER", "BIGINT"),
+        };
+        info!("Adding column {}.{}", table, column);
+        conn.execute_batch(&format!(
+            "ALTER TABLE {table} ADD COLUMN {column} {definition}"
+        ))?;
+        match *column {
+            // Pre-existing actions keep a unique key derived from their id.
+            "idempotency_key" => {
+                conn.execute(
+                    "UPDATE orchestrator_action SET idempotency_key = action_id",
+                    params![],
+                )?;
+                conn.execute_batch(
+                    "CREATE UNIQUE INDEX IF NOT EXISTS idx_orch_action_idempotency
+                     ON orchestrator_action(idempotency_key)",
+                )?;
+            }
+            "decision_window_start" => {
+                conn.execute(
+                    "UPDATE orchestrator_action SET decision_window_start = created_at",
+                    params![],
+                )?;
+            }
+            _ => {}
+        }
+    }
+    conn.execute_batch(
+        "CREATE INDEX IF NOT EXISTS idx_orch_action_runtime_op
+         ON orchestrator_action(runtime_operation_id)",
+    )?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub name: String,
+    /// `None` while the migration is pending.
+    pub applied_at: Option<i64>,
+}
+
+fn ensure_migrations_table(conn: &Connection) -> Result<()> {
+    // Under the migration lock: PostgreSQL's IF NOT EXISTS does not stop two
+    // instances starting together from both trying to create it.
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate(MIGRATION_LOCK))?;
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS schema_migrations (
+            version BIGINT PRIMARY KEY,
+            name TEXT NOT NULL,
+            applied_at BIGINT NOT NULL
+        )",
+    )
+    .context("Failed to create schema_migrations")?;
+    tx.commit()
+}
+
+fn applied_at(conn: &Connection, version: i64) -> Result<Option<i64>> {
+    conn.query_row(
+        "SELECT applied_at FROM schema_migrations WHERE version = ?1",
+        params![version],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+fn check_not_newer(conn: &Connection) -> Result<()> {
+    let latest = MIGRATIONS.last().map(|m| m.version).unwrap_or_default();
+    let current: Option<i64> = conn.query_row(
+        "SELECT MAX(version) FROM schema_migrations",
+        params![],
+        |row| row.get(0),
+    )?;
+    if let Some(current) = current.filter(|v| *v > latest) {
+        anyhow::bail!(
+            "Database schema is at version {} but this build only knows up to {}; \
+             migrations are forward-only, run a newer build",
+            current,
+            latest
+        );
+    }
+    Ok(())
+}
+
+/// Applied and pending migrations, in version order.
+pub fn status(conn: &Connection) -> Result<Vec<MigrationStatus>> {
+    ensure_migrations_table(conn)?;
+    MIGRATIONS
+        .iter()
+        .map(|m| {
+            Ok(MigrationStatus {
+                version: m.version,
+                name: m.name.to_string(),
+                applied_at: applied_at(conn, m.version)?,
+            })
+        })
+        .collect()
+}
+
+/// Apply every pending migration. Returns the versions applied by this call.
+pub fn migrate_up(conn: &Connection) -> Result<Vec<i64>> {
+    ensure_migrations_table(conn)?;
+    check_not_newer(conn)?;
+
+    let mut applied = Vec::new();
+    for migration in MIGRATIONS {
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate(MIGRATION_LOCK))?;
+        // Re-check under the write lock: another instance may have applied
+        // it since we looked.
+        if applied_at(&tx, migration.version)?.is_some() {
+            continue;
+        }
+        info!(
+            "Applying migration {} ({})",
+            migration.version, migration.name
+        );
+        match &migration.source {
+            Source::Sql { sqlite, postgres } => {
+                let sql = match tx.backend() {
+                    Backend::Sqlite => sqlite,
+                    Backend::Postgres => postgres,
+                };
+                tx.execute_batch(sql)
+            }
+            Source::Code(run) => run(&tx),
+        }
+        .with_context(|| {
+            format!(
+                "Failed to apply migration {} ({})",
+                migration.version, migration.name
+            )
+        })?;
+        tx.execute(
+            "INSERT INTO schema_migrations (version, name, applied_at) VALUES (?1, ?2, ?3)",
+            params![migration.version, migration.name, now_unix_seconds()],
+        )?;
+        tx.commit()?;
+        applied.push(migration.version);
+    }
+    Ok(applied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrations_apply_once_in_order() {
+        let conn = Connection::open_in_memory().expect("sqlite");
+        assert!(status(&conn)
+            .expect("status")
+            .iter()
+            .all(|m| m.applied_at.is_none()));
+
+        let applied = migrate_up(&conn).expect("migrate");
+        assert_eq!(
+            applied,
+            MIGRATIONS.iter().map(|m| m.version).collect::<Vec<_>>()
+        );
+        assert!(migrate_up(&conn).expect("migrate again").is_empty());
+        assert!(status(&conn)
+            .expect("status")
+            .iter()
+            .all(|m| m.applied_at.is_some()));
+
+        conn.execute(
+            "INSERT INTO schema_migrations (version, name, applied_at) VALUES (999, 'future', 0)",
+            params![],
+        )
+        .expect("insert");
+        let err = migrate_up(&conn).expect_err("newer schema must be rejected");
+        assert!(err.to_string().contains("version 999"));
+    }
+
+    #[test]
+    #[ignore = "needs PostgreSQL: set QUILT_TEST_POSTGRES_URL and run with --ignored"]
+    fn postgres_concurrent_starts_apply_each_migration_once() {
+        let pool = super::super::postgres_test_pool("migrations");
+        // Start over on the empty schema the pool points at.
+        pool.get()
+            .expect("connection")
+            .execute_batch(
+                "DO $$ DECLARE t TEXT; BEGIN
+                   FOR t IN SELECT tablename FROM pg_tables WHERE schemaname = current_schema() LOOP
+                     EXECUTE 'DROP TABLE ' || quote_ident(t) || ' CASCADE';
+                   END LOOP;
+                 END $$",
+            )
+            .expect("drop tables");
+
+        let mut applied: Vec<i64> = std::thread::scope(|s| {
+            let starts: Vec<_> = (0..4)
+                .map(|_| s.spawn(|| migrate_up(&pool.get().expect("connection"))))
+                .collect();
+            starts
+                .into_iter()
+                .flat_map(|h| h.join().expect("join").expect("migrate"))
+                .collect()
+        });
+        applied.sort();
+        assert_eq!(
+            applied,
+            MIGRATIONS.iter().map(|m| m.version).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn legacy_orchestrator_tables_are_upgraded_in_place() {
+        let conn = Connection::open_in_memory().expect("sqlite");
+        conn.execute_batch(
+            "CREATE TABLE orchestrator_workload_policy (
+                tenant_id TEXT NOT NULL, workload_id TEXT NOT NULL,
+                max_concurrency INTEGER NOT NULL, hard_quota INTEGER NOT NULL,
+                soft_burst INTEGER NOT NULL, absolute_limit INTEGER NOT NULL,
+                priority INTEGER NOT NULL, cooldown_seconds INTEGER NOT NULL,
+                hysteresis_pct REAL NOT NULL, burst_cpu_cap REAL NOT NULL,
+                burst_mem_mb INTEGER NOT NULL, burst_ttl_seconds INTEGER NOT NULL,
+                target_container_ids_json TEXT NOT NULL, updated_at INTEGER NOT NULL,
+                PRIMARY KEY (tenant_id, workload_id));
+             CREATE TABLE orchestrator_action (
+                action_id TEXT PRIMARY KEY, tenant_id TEXT NOT NULL, workload_id TEXT NOT NULL,
+                action_type TEXT NOT NULL, payload_json TEXT NOT NULL,
+                ttl_seconds INTEGER NOT NULL, rollback_action_json TEXT, parent_action_id TEXT,
+                status TEXT NOT NULL, reason_code TEXT, reason_message TEXT,
+                effective_at INTEGER NOT NULL, attempt_count INTEGER NOT NULL,
+                next_retry_at INTEGER NOT NULL, created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL);
+             INSERT INTO orchestrator_action
+                (action_id, tenant_id, workload_id, action_type, payload_json, ttl_seconds,
+                 status, effective_at, attempt_count, next_retry_at, created_at, updated_at)
+             VALUES ('a1', 't', 'w', 'SetPoolTarget', '{}', 30, 'succeeded', 10, 1, 10, 10, 10),
+                    ('a2', 't', 'w', 'SetBurstPolicy', '{\"container_id\":\"c1\"}', 30, 'pending', 10, 0, 10, 10, 10);",
+        )
+        .expect("legacy schema");
+
+        migrate_up(&conn).expect("migrate");
+        super::super::validate_schema(&conn).expect("schema valid after upgrade");
+        let rows: Vec<(String, i64, Option<String>)> = conn
+            .query_map(
+                "SELECT idempotency_key, decision_window_start, supersession_key
+                 FROM orchestrator_action ORDER BY action_id",
+                params![],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .expect("rows");
+        assert_eq!(
+            rows,
+            [
+                ("a1".to_string(), 10, Some(String::new())),
+                ("a2".to_string(), 10, Some("c1".to_string()))
+            ]
+        );
+    }
+}
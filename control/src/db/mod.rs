@@ -1,22 +1,86 @@
+mod connection;
+pub mod ipam;
+pub mod migrations;
+
 use anyhow::{Context, Result};
 use r2d2::Pool;
 use r2d2_sqlite::SqliteConnectionManager;
-use rusqlite::Connection;
 use std::path::PathBuf;
 use tracing::info;
 
-pub type DbPool = Pool<SqliteConnectionManager>;
+pub(crate) use connection::params;
+pub use connection::{
+    Backend, Connection, OptionalExtension, Row, ToValue, Transaction, TransactionBehavior, Value,
+};
+
+/// Connection pool for the configured storage backend.
+#[derive(Clone)]
+pub enum DbPool {
+    Sqlite(Pool<SqliteConnectionManager>),
+    Postgres(Pool<connection::PostgresManager>),
+}
+
+impl DbPool {
+    /// Check out a connection. Blocks; call from a blocking context.
+    pub fn get(&self) -> Result<Connection> {
+        match self {
+            DbPool::Sqlite(pool) => pool
+                .get()
+                .map(Connection::from_sqlite)
+                .context("Failed to get database connection"),
+            DbPool::Postgres(pool) => pool
+                .get()
+                .map(Connection::from_postgres)
+                .context("Failed to get database connection"),
+        }
+    }
+}
+
+/// Open a pool on the database selected by `db_url` (or the SQLite file at
+/// `db_path` when no URL is given) without touching the schema.
+///
+/// Accepted URLs are `sqlite://<path>` and `postgres://...` /
+/// `postgresql://...`. The PostgreSQL client blocks on its own runtime, so
+/// this must not be called directly from an async task.
+pub fn connect(db_url: Option<String>, db_path: Option<PathBuf>) -> Result<DbPool> {
+    match db_url {
+        Some(url) if url.starts_with("postgres://") || url.starts_with("postgresql://") => {
+            let config: postgres::Config = url.parse().context("Invalid PostgreSQL URL")?;
+            info!(
+                "Initializing PostgreSQL database at {:?}",
+                config.get_hosts()
+            );
+            postgres_pool(config)
+        }
+        Some(url) => {
+            let path = url
+                .strip_prefix("sqlite://")
+                .with_context(|| format!("Unsupported database URL: {}", url))?;
+            sqlite_pool(PathBuf::from(path))
+        }
+        None => sqlite_pool(db_path.unwrap_or_else(|| {
+            let mut path = dirs::data_local_dir().expect("Cannot determine data directory");
+            path.push("quilt-mesh");
+            std::fs::create_dir_all(&path).expect("Cannot create data directory");
+            path.push("control.db");
+            path
+        })),
+    }
+}
+
+/// Connect and apply any pending migrations. Blocks, like [`connect`].
+pub fn init_db(db_url: Option<String>, db_path: Option<PathBuf>) -> Result<DbPool> {
+    let pool = connect(db_url, db_path)?;
 
-/// Initialize database with connection pool and run migrations
-pub fn init_db(db_path: Option<PathBuf>) -> Result<DbPool> {
-    let path = db_path.unwrap_or_else(|| {
-        let mut path = dirs::data_local_dir().expect("Cannot determine data directory");
-        path.push("quilt-mesh");
-        std::fs::create_dir_all(&path).expect("Cannot create data directory");
-        path.push("control.db");
-        path
-    });
+    // Run migrations
+    let conn = pool.get()?;
+    run_migrations(&conn)?;
+
+    info!("Database initialized successfully");
+    Ok(pool)
+}
 
+fn sqlite_pool(path: PathBuf) -> Result<DbPool> {
     info!("Initializing database at: {:?}", path);
 
     let manager = SqliteConnectionManager::file(&path);
@@ -24,27 +88,28 @@ pub fn init_db(db_path: Option<PathBuf>) -> Result<DbPool> {
         .max_size(10)
         .build(manager)
         .context("Failed to create connection pool")?;
+    Ok(DbPool::Sqlite(pool))
+}
 
-    // Run migrations
-    let conn = pool.get().context("Failed to get database connection")?;
-    run_migrations(&conn)?;
-
-    info!("Database initialized successfully");
-    Ok(pool)
+fn postgres_pool(config: postgres::Config) -> Result<DbPool> {
+    let manager = connection::PostgresManager::new(config);
+    let pool = Pool::builder()
+        .max_size(10)
+        .build(manager)
+        .context("Failed to create connection pool")?;
+    Ok(DbPool::Postgres(pool))
 }
 
 fn run_migrations(conn: &Connection) -> Result<()> {
-    // Enable foreign keys
-    conn.execute_batch("PRAGMA foreign_keys = ON;")
-        .context("Failed to enable foreign keys")?;
-
-    // Run migration files in order
-    let migrations = [include_str!("../../migrations/003_orchestrator.sql")];
+    if conn.backend() == Backend::Sqlite {
+        // Enable foreign keys
+        conn.execute_batch("PRAGMA foreign_keys = ON;")
+            .context("Failed to enable foreign keys")?;
+    }
 
-    for (i, migration) in migrations.iter().enumerate() {
-        info!("Running migration {}", i + 1);
-        conn.execute_batch(migration)
-            .with_context(|| format!("Failed to run migration {}", i + 1))?;
+    let applied = migrations::migrate_up(conn)?;
+    if !applied.is_empty() {
+        info!("Applied migrations {:?}", applied);
     }
 
     validate_schema(conn)?;
@@ -54,21 +119,15 @@ fn run_migrations(conn: &Connection) -> Result<()> {
 
 fn validate_schema(conn: &Connection) -> Result<()> {
     fn columns_for(conn: &Connection, table: &str) -> Result<std::collections::HashSet<String>> {
-        let mut stmt = conn
-            .prepare(&format!("PRAGMA table_info({})", table))
-            .with_context(|| format!("Failed to inspect schema for table {}", table))?;
-        let cols = stmt
-            .query_map([], |row| row.get::<_, String>(1))?
-            .collect::<Result<std::collections::HashSet<_>, _>>()
-            .with_context(|| format!("Failed to read columns for table {}", table))?;
-        Ok(cols)
+        conn.table_columns(table)
+            .with_context(|| format!("Failed to read columns for table {}", table))
     }
 
     let policy = columns_for(conn, "orchestrator_workload_policy")?;
     if !policy.contains("runtime_function_id") {
         anyhow::bail!(
             "Schema mismatch: orchestrator_workload_policy.runtime_function_id missing. \
-             This build is not backward compatible; use a fresh control DB."
+             Migrations should have added it; check `migrate --status`."
         );
     }
 
@@ -83,7 +142,7 @@ fn validate_schema(conn: &Connection) -> Result<()> {
         if !action.contains(required) {
             anyhow::bail!(
                 "Schema mismatch: orchestrator_action.{} missing. \
-                 This build is not backward compatible; use a fresh control DB.",
+                 Migrations should have added it; check `migrate --status`.",
                 required
             );
         }
@@ -100,9 +159,42 @@ where
 {
     let pool = pool.clone();
     tokio::task::spawn_blocking(move || {
-        let conn = pool.get().context("Failed to get database connection")?;
+        let conn = pool.get()?;
         f(&conn)
     })
     .await
     .context("Task join error")?
 }
+
+/// In-memory SQLite connection on the full schema, migrated the way
+/// [`init_db`] migrates a real database.
+#[cfg(test)]
+pub(crate) fn test_conn() -> Connection {
+    let conn = Connection::open_in_memory().expect("in-memory sqlite");
+    run_migrations(&conn).expect("migrations");
+    conn
+}
+
+/// Pool on a fresh schema of the PostgreSQL server named by
+/// `QUILT_TEST_POSTGRES_URL`. Tests using it are `#[ignore]`d and run with
+/// `cargo test -- --ignored`; they fail rather than pass vacuously when the
+/// variable is unset. Blocks; call via `spawn_blocking` from async tests.
+#[cfg(test)]
+pub(crate) fn postgres_test_pool(name: &str) -> DbPool {
+    let url = std::env::var("QUILT_TEST_POSTGRES_URL")
+        .expect("QUILT_TEST_POSTGRES_URL must name a PostgreSQL server for --ignored tests");
+    let schema = format!("quilt_test_{}", name);
+    let mut client =
+        postgres::Client::connect(&url, postgres::NoTls).expect("connect to test postgres");
+    client
+        .batch_execute(&format!(
+            "DROP SCHEMA IF EXISTS {schema} CASCADE; CREATE SCHEMA {schema};"
+        ))
+        .expect("create test schema");
+
+    let mut config: postgres::Config = url.parse().expect("postgres url");
+    config.options(&format!("-c search_path={}", schema));
+    let pool = postgres_pool(config).expect("postgres pool");
+    run_migrations(&pool.get().expect("connection")).expect("migrations");
+    pool
+}
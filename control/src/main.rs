@@ -1,32 +1,48 @@
 mod api;
 mod db;
+mod metrics;
 mod services;
 mod tls;
 mod types;
 
 use anyhow::Result;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::Arc;
-use tracing::{info, Level};
+use std::time::Duration;
+use tracing::{info, warn, Level};
 use tracing_subscriber::FmtSubscriber;
 
+use api::auth::AuthConfig;
 use api::AppState;
-use services::orchestrator::{self, ExecutionConfig};
+use db::ipam::SubnetAllocator;
+use services::circuit_breaker::CircuitBreaker;
+use services::executor::{ExecutionConfig, ExecutorRegistry, HttpExecutor, LoggingExecutor};
+use services::heartbeat_monitor::{self, HeartbeatConfig};
+use services::leader_election::{LeaderConfig, LeaderElection};
+use services::orchestrator;
+use services::orchestrator_config::ConfigHandle;
 
 #[derive(Parser, Debug)]
 #[command(name = "quilt-mesh-control")]
 #[command(about = "Quilt Mesh control plane", long_about = None)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Bind address for HTTP server
     #[arg(long, default_value = "0.0.0.0:8080")]
     bind: String,
 
-    /// Database file path
-    #[arg(long)]
+    /// Database file path (SQLite; ignored when --db-url is set)
+    #[arg(long, global = true)]
     db_path: Option<PathBuf>,
 
+    /// Database URL: sqlite://<path> or postgres://user@host/db
+    #[arg(long, env = "QUILT_DB_URL", global = true)]
+    db_url: Option<String>,
+
     /// Log level
     #[arg(long, default_value = "info")]
     log_level: String,
@@ -43,6 +59,46 @@ struct Args {
     #[arg(long)]
     tls_ca: Option<PathBuf>,
 
+    /// Cluster CIDR that per-node subnets are allocated from
+    #[arg(long, default_value = "10.42.0.0/16")]
+    cluster_cidr: String,
+
+    /// Prefix length of the subnet assigned to each node
+    #[arg(long, default_value_t = 24)]
+    node_subnet_prefix: u8,
+
+    /// Seconds a released node subnet stays quarantined before it can be reused
+    #[arg(long, default_value_t = 600)]
+    subnet_quarantine_seconds: u64,
+
+    /// Seconds without a heartbeat before a node is marked suspect
+    #[arg(long, default_value_t = 30)]
+    heartbeat_suspect_seconds: u64,
+
+    /// Seconds without a heartbeat before a node is marked down
+    #[arg(long, default_value_t = 60)]
+    heartbeat_down_seconds: u64,
+
+    /// Interval between heartbeat monitor sweeps
+    #[arg(long, default_value_t = 5)]
+    heartbeat_check_seconds: u64,
+
+    /// Identity used for leader election (defaults to a random UUID)
+    #[arg(long, env = "QUILT_INSTANCE_ID")]
+    instance_id: Option<String>,
+
+    /// Seconds the orchestrator leader lease stays valid without renewal
+    #[arg(long, default_value_t = 15)]
+    leader_lease_seconds: u64,
+
+    /// Interval between leader lease renewals
+    #[arg(long, default_value_t = 5)]
+    leader_renew_seconds: u64,
+
+    /// Bootstrap admin API key for the /v1/orchestrator API
+    #[arg(long, env = "QUILT_ADMIN_API_KEY", hide_env_values = true)]
+    admin_api_key: Option<String>,
+
     /// Elasticity control base URL
     #[arg(long, env = "CONTROL_BASE_URL")]
     control_base_url: Option<String>,
@@ -50,6 +106,42 @@ struct Args {
     /// Elasticity control API key (sent as X-Api-Key)
     #[arg(long, env = "CONTROL_API_KEY")]
     control_api_key: Option<String>,
+
+    /// Milliseconds to wait for a connection to the elasticity control API
+    #[arg(long, default_value_t = 2000)]
+    control_connect_timeout_ms: u64,
+
+    /// Milliseconds before an elasticity control request is abandoned
+    #[arg(long, default_value_t = 10000)]
+    control_request_timeout_ms: u64,
+
+    /// How orchestrator actions are applied: `http` calls the elasticity
+    /// control API, `log` only logs them and marks them succeeded
+    #[arg(long, default_value = "http", value_parser = ["http", "log"])]
+    action_executor: String,
+
+    /// Orchestrator tuning file (TOML). Built-in defaults are used when
+    /// unset; reloaded on SIGHUP or `POST /v1/orchestrator/config:reload`
+    #[arg(long, env = "QUILT_ORCHESTRATOR_CONFIG")]
+    orchestrator_config: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Inspect or apply schema migrations, then exit
+    Migrate(MigrateArgs),
+}
+
+#[derive(clap::Args, Debug)]
+#[group(required = true, multiple = false)]
+struct MigrateArgs {
+    /// List applied and pending migrations
+    #[arg(long)]
+    status: bool,
+
+    /// Apply all pending migrations
+    #[arg(long)]
+    up: bool,
 }
 
 #[tokio::main]
@@ -70,20 +162,85 @@ async fn main() -> Result<()> {
 
     tracing::subscriber::set_global_default(subscriber)?;
 
+    if let Some(Command::Migrate(migrate)) = args.command {
+        return run_migrate(args.db_url, args.db_path, migrate).await;
+    }
+
     info!("Starting Quilt Mesh Control Plane");
 
-    // Initialize database
-    let db = db::init_db(args.db_path)?;
+    // Initialize database. The PostgreSQL client blocks, so connect off the
+    // async runtime.
+    let (db_url, db_path) = (args.db_url.clone(), args.db_path.clone());
+    let db = tokio::task::spawn_blocking(move || db::init_db(db_url, db_path)).await??;
+
+    let ipam = SubnetAllocator::new(
+        &args.cluster_cidr,
+        args.node_subnet_prefix,
+        args.subnet_quarantine_seconds,
+    )?;
+
+    let leader = LeaderElection::new(
+        db.clone(),
+        LeaderConfig {
+            instance_id: args
+                .instance_id
+                .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+            lease_seconds: args.leader_lease_seconds,
+            renew_interval_seconds: args.leader_renew_seconds,
+        },
+    )?;
+    leader.start().await?;
+
+    let config = ConfigHandle::load(args.orchestrator_config.clone())?;
+    #[cfg(unix)]
+    {
+        let config = config.clone();
+        let mut hangup = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())?;
+        tokio::spawn(async move {
+            while hangup.recv().await.is_some() {
+                match config.reload() {
+                    Ok(_) => info!("Reloaded orchestrator config on SIGHUP"),
+                    Err(e) => warn!("Keeping previous orchestrator config: {:#}", e),
+                }
+            }
+        });
+    }
+
+    let breaker = CircuitBreaker::new();
 
     // Create application state
-    let state = Arc::new(AppState { db: db.clone() });
-
-    let execution_config = ExecutionConfig {
-        control_base_url: args.control_base_url,
-        control_api_key: args.control_api_key,
+    let state = Arc::new(AppState {
+        db: db.clone(),
+        ipam: ipam.clone(),
+        leader: leader.clone(),
+        auth: AuthConfig::new(args.admin_api_key.as_deref()),
+        config: config.clone(),
+        breaker: breaker.clone(),
+    });
+
+    let executors = match args.action_executor.as_str() {
+        "log" => ExecutorRegistry::uniform(Arc::new(LoggingExecutor)),
+        _ => ExecutorRegistry::uniform(Arc::new(HttpExecutor::new(ExecutionConfig {
+            control_base_url: args.control_base_url,
+            control_api_key: args.control_api_key,
+            connect_timeout: Duration::from_millis(args.control_connect_timeout_ms),
+            request_timeout: Duration::from_millis(args.control_request_timeout_ms),
+        })?)),
     };
 
-    orchestrator::start_loops(db, execution_config).await?;
+    heartbeat_monitor::start(
+        db.clone(),
+        ipam,
+        HeartbeatConfig {
+            suspect_after_seconds: args.heartbeat_suspect_seconds,
+            down_after_seconds: args.heartbeat_down_seconds,
+            check_interval_seconds: args.heartbeat_check_seconds,
+        },
+        leader.clone(),
+    )
+    .await?;
+
+    orchestrator::start_loops(db, executors, leader.clone(), breaker, config).await?;
 
     // Create router
     let app = api::create_router(state);
@@ -100,7 +257,15 @@ async fn main() -> Result<()> {
         let rustls_config =
             axum_server::tls_rustls::RustlsConfig::from_config(Arc::new(tls_config));
 
+        let handle = axum_server::Handle::new();
+        let shutdown_handle = handle.clone();
+        tokio::spawn(async move {
+            shutdown_signal().await;
+            shutdown_handle.graceful_shutdown(Some(Duration::from_secs(10)));
+        });
+
         axum_server::bind_rustls(addr, rustls_config)
+            .handle(handle)
             .serve(app.into_make_service())
             .await?;
     } else {
@@ -112,11 +277,48 @@ async fn main() -> Result<()> {
             .await?;
     }
 
+    // Hand the orchestrator lease to a peer instead of making it wait for
+    // the lease to expire.
+    if let Err(e) = leader.step_down().await {
+        tracing::error!("Failed to release leader lease: {}", e);
+    }
+
     info!("Control plane shutdown complete");
 
     Ok(())
 }
 
+async fn run_migrate(
+    db_url: Option<String>,
+    db_path: Option<PathBuf>,
+    migrate: MigrateArgs,
+) -> Result<()> {
+    tokio::task::spawn_blocking(move || {
+        let pool = db::connect(db_url, db_path)?;
+        let conn = pool.get()?;
+        if migrate.up {
+            let applied = db::migrations::migrate_up(&conn)?;
+            if applied.is_empty() {
+                println!("Schema is up to date");
+            }
+            for version in applied {
+                println!("Applied migration {}", version);
+            }
+            return Ok(());
+        }
+        println!("{:<8} {:<40} APPLIED_AT", "VERSION", "NAME");
+        for m in db::migrations::status(&conn)? {
+            let applied_at = m
+                .applied_at
+                .map(|t| t.to_string())
+                .unwrap_or_else(|| "pending".to_string());
+            println!("{:<8} {:<40} {}", m.version, m.name, applied_at);
+        }
+        Ok(())
+    })
+    .await?
+}
+
 async fn shutdown_signal() {
     let ctrl_c = async {
         tokio::signal::ctrl_c()
@@ -0,0 +1,370 @@
+//! Prometheus metrics for the control plane, served at `GET /metrics`.
+//!
+//! Recorders are free functions over a process-wide registry so services can
+//! record without threading a handle through every call. Gauges that mirror
+//! DB state (action status distribution, leadership) are refreshed at scrape
+//! time by the `/metrics` handler.
+
+use anyhow::Result;
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry,
+    TextEncoder,
+};
+use std::cell::Cell;
+use std::future::Future;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+use crate::types::BreakerState;
+
+pub const LOOP_FAST: &str = "fast";
+pub const LOOP_SLOW: &str = "slow";
+pub const LOOP_DISPATCH: &str = "dispatch";
+
+struct Metrics {
+    registry: Registry,
+    loop_duration: HistogramVec,
+    loop_runs: IntCounterVec,
+    actions_enqueued: IntCounterVec,
+    action_retries: IntCounterVec,
+    actions_terminal: IntCounterVec,
+    action_latency: HistogramVec,
+    actions_by_status: IntGaugeVec,
+    observations_ingested: IntCounterVec,
+    is_leader: IntGauge,
+    dispatch_in_flight: IntGauge,
+    breaker_state: IntGaugeVec,
+    breaker_transitions: IntCounterVec,
+    http_requests: IntCounterVec,
+    http_duration: HistogramVec,
+}
+
+fn counter(registry: &Registry, name: &str, help: &str, labels: &[&str]) -> IntCounterVec {
+    let metric = IntCounterVec::new(Opts::new(name, help), labels).expect("valid counter");
+    registry
+        .register(Box::new(metric.clone()))
+        .expect("unique counter");
+    metric
+}
+
+fn histogram(
+    registry: &Registry,
+    name: &str,
+    help: &str,
+    labels: &[&str],
+    buckets: Vec<f64>,
+) -> HistogramVec {
+    let metric = HistogramVec::new(HistogramOpts::new(name, help).buckets(buckets), labels)
+        .expect("valid histogram");
+    registry
+        .register(Box::new(metric.clone()))
+        .expect("unique histogram");
+    metric
+}
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(|| {
+    let registry = Registry::new();
+    let actions_by_status = IntGaugeVec::new(
+        Opts::new(
+            "quilt_orchestrator_actions",
+            "Orchestrator actions by current status",
+        ),
+        &["status"],
+    )
+    .expect("valid gauge");
+    registry
+        .register(Box::new(actions_by_status.clone()))
+        .expect("unique gauge");
+    let is_leader = IntGauge::new(
+        "quilt_orchestrator_is_leader",
+        "1 while this instance holds the orchestrator lease",
+    )
+    .expect("valid gauge");
+    registry
+        .register(Box::new(is_leader.clone()))
+        .expect("unique gauge");
+    let dispatch_in_flight = IntGauge::new(
+        "quilt_orchestrator_dispatch_in_flight",
+        "Actions currently being dispatched or polled",
+    )
+    .expect("valid gauge");
+    registry
+        .register(Box::new(dispatch_in_flight.clone()))
+        .expect("unique gauge");
+    let breaker_state = IntGaugeVec::new(
+        Opts::new(
+            "quilt_orchestrator_breaker_state",
+            "1 for the current state of the elasticity control circuit breaker",
+        ),
+        &["state"],
+    )
+    .expect("valid gauge");
+    registry
+        .register(Box::new(breaker_state.clone()))
+        .expect("unique gauge");
+    breaker_state
+        .with_label_values(&[BreakerState::Closed.as_str()])
+        .set(1);
+
+    Metrics {
+        loop_duration: histogram(
+            &registry,
+            "quilt_orchestrator_loop_duration_seconds",
+            "Duration of orchestrator loop runs",
+            &["loop"],
+            vec![0.005, 0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0],
+        ),
+        loop_runs: counter(
+            &registry,
+            "quilt_orchestrator_loop_runs_total",
+            "Orchestrator loop runs by outcome",
+            &["loop", "outcome"],
+        ),
+        actions_enqueued: counter(
+            &registry,
+            "quilt_orchestrator_actions_enqueued_total",
+            "Actions enqueued, excluding idempotent duplicates",
+            &["action_type"],
+        ),
+        action_retries: counter(
+            &registry,
+            "quilt_orchestrator_action_retries_total",
+            "Dispatch attempts rescheduled for retry",
+            &["action_type", "reason_code"],
+        ),
+        actions_terminal: counter(
+            &registry,
+            "quilt_orchestrator_actions_terminal_total",
+            "Actions that reached a terminal status",
+            &["action_type", "status", "reason_code"],
+        ),
+        action_latency: histogram(
+            &registry,
+            "quilt_orchestrator_action_latency_seconds",
+            "Time from enqueue to terminal status",
+            &["action_type", "status"],
+            vec![1.0, 2.0, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0, 600.0, 1800.0],
+        ),
+        breaker_transitions: counter(
+            &registry,
+            "quilt_orchestrator_breaker_transitions_total",
+            "Elasticity control circuit breaker transitions by new state",
+            &["state"],
+        ),
+        observations_ingested: counter(
+            &registry,
+            "quilt_orchestrator_observations_ingested_total",
+            "Observations ingested",
+            &["kind"],
+        ),
+        http_requests: counter(
+            &registry,
+            "quilt_http_requests_total",
+            "HTTP requests handled",
+            &["method", "route", "status"],
+        ),
+        http_duration: histogram(
+            &registry,
+            "quilt_http_request_duration_seconds",
+            "HTTP request latency",
+            &["method", "route"],
+            prometheus::DEFAULT_BUCKETS.to_vec(),
+        ),
+        actions_by_status,
+        is_leader,
+        dispatch_in_flight,
+        breaker_state,
+        registry,
+    }
+});
+
+/// Run one loop iteration, recording its duration and outcome.
+pub async fn observe_loop<T, F>(name: &str, run: F) -> Result<T>
+where
+    F: Future<Output = Result<T>>,
+{
+    let started = Instant::now();
+    let result = run.await;
+    let m = &*METRICS;
+    m.loop_duration
+        .with_label_values(&[name])
+        .observe(started.elapsed().as_secs_f64());
+    let outcome = if result.is_ok() { "ok" } else { "error" };
+    m.loop_runs.with_label_values(&[name, outcome]).inc();
+    result
+}
+
+thread_local! {
+    static MUTED: Cell<bool> = const { Cell::new(false) };
+}
+
+struct MuteGuard(bool);
+
+impl Drop for MuteGuard {
+    fn drop(&mut self) {
+        MUTED.set(self.0);
+    }
+}
+
+/// Run `f` with the action recorders muted on this thread. Used for dry runs
+/// whose writes are rolled back; DB closures run on a single blocking thread,
+/// so everything they record is covered.
+pub fn muted<T>(f: impl FnOnce() -> T) -> T {
+    let _guard = MuteGuard(MUTED.replace(true));
+    f()
+}
+
+fn is_muted() -> bool {
+    MUTED.get()
+}
+
+pub fn action_enqueued(action_type: &str) {
+    if is_muted() {
+        return;
+    }
+    METRICS
+        .actions_enqueued
+        .with_label_values(&[action_type])
+        .inc();
+}
+
+pub fn action_retried(action_type: &str, reason_code: &str) {
+    if is_muted() {
+        return;
+    }
+    METRICS
+        .action_retries
+        .with_label_values(&[action_type, reason_code])
+        .inc();
+}
+
+pub fn action_terminal(
+    action_type: &str,
+    status: &str,
+    reason_code: Option<&str>,
+    latency_ms: i64,
+) {
+    if is_muted() {
+        return;
+    }
+    let m = &*METRICS;
+    m.actions_terminal
+        .with_label_values(&[action_type, status, reason_code.unwrap_or("")])
+        .inc();
+    m.action_latency
+        .with_label_values(&[action_type, status])
+        .observe(latency_ms.max(0) as f64 / 1000.0);
+}
+
+pub fn observations_ingested(workloads: usize, node_groups: usize) {
+    let m = &*METRICS;
+    m.observations_ingested
+        .with_label_values(&["workload"])
+        .inc_by(workloads as u64);
+    m.observations_ingested
+        .with_label_values(&["node_group"])
+        .inc_by(node_groups as u64);
+}
+
+pub fn http_request(method: &str, route: &str, status: u16, elapsed: Duration) {
+    let m = &*METRICS;
+    m.http_requests
+        .with_label_values(&[method, route, &status.to_string()])
+        .inc();
+    m.http_duration
+        .with_label_values(&[method, route])
+        .observe(elapsed.as_secs_f64());
+}
+
+/// Replace the status distribution with a fresh count from the DB, so
+/// statuses that drained to zero stop being reported.
+pub fn set_action_status_counts(counts: &[(String, i64)]) {
+    let m = &*METRICS;
+    m.actions_by_status.reset();
+    for (status, count) in counts {
+        m.actions_by_status.with_label_values(&[status]).set(*count);
+    }
+}
+
+pub fn set_leader(is_leader: bool) {
+    METRICS.is_leader.set(i64::from(is_leader));
+}
+
+pub fn breaker_transition(to: BreakerState) {
+    let m = &*METRICS;
+    for state in [
+        BreakerState::Closed,
+        BreakerState::Open,
+        BreakerState::HalfOpen,
+    ] {
+        m.breaker_state
+            .with_label_values(&[state.as_str()])
+            .set(i64::from(state == to));
+    }
+    m.breaker_transitions
+        .with_label_values(&[to.as_str()])
+        .inc();
+}
+
+pub fn set_dispatch_in_flight(count: usize) {
+    METRICS.dispatch_in_flight.set(count as i64);
+}
+
+/// Encode every metric in the Prometheus text exposition format.
+pub fn render() -> Result<String> {
+    let mut buf = Vec::new();
+    TextEncoder::new().encode(&METRICS.registry.gather(), &mut buf)?;
+    Ok(String::from_utf8(buf)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recorded_metrics_are_rendered() {
+        action_enqueued("SetPoolTarget");
+        action_terminal(
+            "SetPoolTarget",
+            "failed",
+            Some("ELASTICITY_TARGET_NOT_FOUND"),
+            3000,
+        );
+        set_action_status_counts(&[("pending".to_string(), 4)]);
+        set_action_status_counts(&[("running".to_string(), 2)]);
+
+        let text = render().expect("render");
+        assert!(text
+            .contains("quilt_orchestrator_actions_enqueued_total{action_type=\"SetPoolTarget\"}"));
+        assert!(text.contains(
+            "quilt_orchestrator_actions_terminal_total{action_type=\"SetPoolTarget\",reason_code=\"ELASTICITY_TARGET_NOT_FOUND\",status=\"failed\"}"
+        ));
+        assert!(text.contains("quilt_orchestrator_actions{status=\"running\"} 2"));
+        assert!(!text.contains("quilt_orchestrator_actions{status=\"pending\"}"));
+    }
+
+    #[test]
+    fn muted_recorders_skip_and_restore() {
+        muted(|| action_enqueued("MutedOnly"));
+        action_enqueued("AfterMute");
+        let text = render().expect("render");
+        assert!(!text.contains("action_type=\"MutedOnly\""));
+        assert!(
+            text.contains("quilt_orchestrator_actions_enqueued_total{action_type=\"AfterMute\"} 1")
+        );
+    }
+
+    #[tokio::test]
+    async fn loop_outcome_is_labelled() {
+        observe_loop("test", async { Ok(()) }).await.expect("ok");
+        observe_loop("test", async { anyhow::bail!("boom") })
+            .await
+            .map(|_: ()| ())
+            .expect_err("error");
+        let text = render().expect("render");
+        assert!(text.contains("quilt_orchestrator_loop_runs_total{loop=\"test\",outcome=\"ok\"} 1"));
+        assert!(
+            text.contains("quilt_orchestrator_loop_runs_total{loop=\"test\",outcome=\"error\"} 1")
+        );
+    }
+}
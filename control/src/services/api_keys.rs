@@ -0,0 +1,188 @@
+use anyhow::Result;
+use sha2::{Digest, Sha256};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::db::{execute_async, params, Connection, DbPool, OptionalExtension, Row};
+use crate::services::error::Validator;
+use crate::services::orchestrator::now_unix_seconds;
+use crate::types::{ApiKey, CreateApiKeyRequest, CreateApiKeyResponse};
+
+pub const ROLE_ADMIN: &str = "admin";
+pub const ROLE_TENANT: &str = "tenant";
+
+const KEY_PREFIX: &str = "qk_";
+
+/// Keys are stored as SHA-256 hex digests; the plaintext is only returned
+/// once, at creation.
+pub fn hash_key(key: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(key.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+fn generate_key() -> String {
+    format!(
+        "{}{}{}",
+        KEY_PREFIX,
+        Uuid::new_v4().simple(),
+        Uuid::new_v4().simple()
+    )
+}
+
+pub fn validate_request(req: &CreateApiKeyRequest) -> Result<()> {
+    let tenant_id = req.tenant_id.as_deref().map(str::trim);
+    let mut v = Validator::new();
+    match req.role.as_str() {
+        ROLE_ADMIN => v.check(
+            tenant_id.is_none(),
+            "tenant_id",
+            "admin keys are not bound to a tenant",
+        ),
+        ROLE_TENANT => v.check(
+            tenant_id.is_some_and(|t| !t.is_empty()),
+            "tenant_id",
+            "tenant keys require a tenant_id",
+        ),
+        _ => v.check(false, "role", "must be admin or tenant"),
+    };
+    v.finish()
+}
+
+fn row_to_key(row: &Row) -> Result<ApiKey> {
+    Ok(ApiKey {
+        key_id: row.get(0)?,
+        role: row.get(1)?,
+        tenant_id: row.get(2)?,
+        created_at: row.get(3)?,
+        revoked_at: row.get(4)?,
+    })
+}
+
+fn create_key_tx(
+    conn: &Connection,
+    req: &CreateApiKeyRequest,
+    now: i64,
+) -> Result<CreateApiKeyResponse> {
+    validate_request(req)?;
+    // Stored as validated, so the key matches the tenant it was checked for.
+    let tenant_id = req.tenant_id.as_deref().map(|t| t.trim().to_string());
+    let key_id = Uuid::new_v4().to_string();
+    let api_key = generate_key();
+    conn.execute(
+        "INSERT INTO api_key (key_id, key_hash, role, tenant_id, created_at, revoked_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, NULL)",
+        params![key_id, hash_key(&api_key), req.role, tenant_id, now],
+    )?;
+    Ok(CreateApiKeyResponse {
+        key_id,
+        api_key,
+        role: req.role.clone(),
+        tenant_id,
+        created_at: now,
+    })
+}
+
+fn find_active_key_tx(conn: &Connection, key_hash: &str) -> Result<Option<ApiKey>> {
+    conn.query_row(
+        "SELECT key_id, role, tenant_id, created_at, revoked_at
+         FROM api_key
+         WHERE key_hash = ?1 AND revoked_at IS NULL",
+        params![key_hash],
+        row_to_key,
+    )
+    .optional()
+}
+
+fn revoke_key_tx(conn: &Connection, key_id: &str, now: i64) -> Result<bool> {
+    let rows = conn.execute(
+        "UPDATE api_key SET revoked_at = ?1 WHERE key_id = ?2 AND revoked_at IS NULL",
+        params![now, key_id],
+    )?;
+    Ok(rows > 0)
+}
+
+pub async fn create_key(db: &DbPool, req: CreateApiKeyRequest) -> Result<CreateApiKeyResponse> {
+    let now = now_unix_seconds();
+    let resp = execute_async(db, move |conn| create_key_tx(conn, &req, now)).await?;
+    info!(
+        "Created {} API key {} (tenant={})",
+        resp.role,
+        resp.key_id,
+        resp.tenant_id.as_deref().unwrap_or("-")
+    );
+    Ok(resp)
+}
+
+/// Resolve a presented key to its active record, if any.
+pub async fn find_active_key(db: &DbPool, presented: &str) -> Result<Option<ApiKey>> {
+    let key_hash = hash_key(presented);
+    execute_async(db, move |conn| find_active_key_tx(conn, &key_hash)).await
+}
+
+pub async fn list_keys(db: &DbPool, tenant_id: Option<&str>) -> Result<Vec<ApiKey>> {
+    let tenant_id = tenant_id.map(ToString::to_string);
+    execute_async(db, move |conn| {
+        conn.query_map(
+            "SELECT key_id, role, tenant_id, created_at, revoked_at
+             FROM api_key
+             WHERE CAST(?1 AS TEXT) IS NULL OR tenant_id = ?1
+             ORDER BY created_at, key_id",
+            params![tenant_id],
+            row_to_key,
+        )
+    })
+    .await
+}
+
+/// Revoke a key. Returns `false` if it does not exist or is already revoked.
+pub async fn revoke_key(db: &DbPool, key_id: &str) -> Result<bool> {
+    let now = now_unix_seconds();
+    let key_id = key_id.to_string();
+    execute_async(db, move |conn| revoke_key_tx(conn, &key_id, now)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(role: &str, tenant_id: Option<&str>) -> CreateApiKeyRequest {
+        CreateApiKeyRequest {
+            role: role.to_string(),
+            tenant_id: tenant_id.map(ToString::to_string),
+        }
+    }
+
+    #[test]
+    fn created_key_resolves_until_revoked() {
+        let conn = crate::db::test_conn();
+        let created =
+            create_key_tx(&conn, &request(ROLE_TENANT, Some(" tenant-a ")), 100).expect("create");
+        assert!(created.api_key.starts_with(KEY_PREFIX));
+        assert_eq!(created.tenant_id.as_deref(), Some("tenant-a"));
+
+        let found = find_active_key_tx(&conn, &hash_key(&created.api_key))
+            .expect("lookup")
+            .expect("active key");
+        assert_eq!(found.key_id, created.key_id);
+        assert_eq!(found.tenant_id.as_deref(), Some("tenant-a"));
+        assert!(find_active_key_tx(&conn, &hash_key("qk_wrong"))
+            .expect("lookup")
+            .is_none());
+
+        assert!(revoke_key_tx(&conn, &created.key_id, 200).expect("revoke"));
+        assert!(!revoke_key_tx(&conn, &created.key_id, 300).expect("revoke again"));
+        assert!(find_active_key_tx(&conn, &hash_key(&created.api_key))
+            .expect("lookup")
+            .is_none());
+    }
+
+    #[test]
+    fn role_and_tenant_must_agree() {
+        assert!(validate_request(&request(ROLE_ADMIN, None)).is_ok());
+        assert!(validate_request(&request(ROLE_ADMIN, Some("t"))).is_err());
+        assert!(validate_request(&request(ROLE_TENANT, None)).is_err());
+        assert!(validate_request(&request(ROLE_TENANT, Some(" "))).is_err());
+        assert!(validate_request(&request("owner", Some("t"))).is_err());
+    }
+}
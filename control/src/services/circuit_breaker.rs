@@ -0,0 +1,212 @@
+//! Circuit breaker around the elasticity control dependency.
+//!
+//! Consecutive dependency failures (connection errors, timeouts, 5xx) open
+//! the breaker. While open the dispatch loop does nothing at all, TTL expiry
+//! included, so an outage doesn't turn into a rollback storm against the same
+//! dead service. After `open_seconds` it goes half-open and lets one action
+//! through as a probe: success closes it, failure opens it again.
+//!
+//! State is per process; only the leader dispatches, so only its breaker
+//! moves.
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+use crate::metrics;
+use crate::types::{BreakerState, BreakerStatus};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BreakerConfig {
+    /// Consecutive dependency failures that open the breaker.
+    pub failure_threshold: u32,
+    /// How long the breaker stays open before probing.
+    pub open_seconds: u64,
+}
+
+impl Default for BreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            open_seconds: 30,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Inner {
+    state: BreakerState,
+    consecutive_failures: u32,
+    opened_at: Option<i64>,
+    probe_in_flight: bool,
+}
+
+impl Inner {
+    fn transition(&mut self, to: BreakerState, now: i64) {
+        if self.state == to {
+            return;
+        }
+        self.state = to;
+        match to {
+            BreakerState::Open => self.opened_at = Some(now),
+            BreakerState::Closed => {
+                self.opened_at = None;
+                self.consecutive_failures = 0;
+            }
+            BreakerState::HalfOpen => {}
+        }
+        metrics::breaker_transition(to);
+    }
+}
+
+/// What the dispatch loop may do this cycle.
+#[derive(Debug)]
+pub enum Gate {
+    Closed,
+    /// Dispatch exactly one action. Dropping the probe without an outcome
+    /// being recorded frees it for the next cycle.
+    Probe(Probe),
+    Open,
+}
+
+#[derive(Debug)]
+pub struct Probe {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl Drop for Probe {
+    fn drop(&mut self) {
+        self.inner.lock().expect("breaker lock").probe_in_flight = false;
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                state: BreakerState::Closed,
+                consecutive_failures: 0,
+                opened_at: None,
+                probe_in_flight: false,
+            })),
+        }
+    }
+}
+
+impl CircuitBreaker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn gate(&self, cfg: &BreakerConfig, now: i64) -> Gate {
+        let mut inner = self.inner.lock().expect("breaker lock");
+        if inner.state == BreakerState::Open
+            && now >= inner.opened_at.unwrap_or(now) + cfg.open_seconds as i64
+        {
+            inner.transition(BreakerState::HalfOpen, now);
+        }
+        match inner.state {
+            BreakerState::Closed => Gate::Closed,
+            BreakerState::HalfOpen if !inner.probe_in_flight => {
+                inner.probe_in_flight = true;
+                Gate::Probe(Probe {
+                    inner: self.inner.clone(),
+                })
+            }
+            _ => Gate::Open,
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.inner.lock().expect("breaker lock").state == BreakerState::Closed
+    }
+
+    /// Record the outcome of a call to the dependency.
+    pub fn record(&self, cfg: &BreakerConfig, failed: bool, now: i64) {
+        let mut inner = self.inner.lock().expect("breaker lock");
+        match (inner.state, failed) {
+            (BreakerState::Closed, false) => inner.consecutive_failures = 0,
+            (BreakerState::Closed, true) => {
+                inner.consecutive_failures += 1;
+                if inner.consecutive_failures >= cfg.failure_threshold {
+                    inner.transition(BreakerState::Open, now);
+                }
+            }
+            (BreakerState::HalfOpen, false) => inner.transition(BreakerState::Closed, now),
+            (BreakerState::HalfOpen, true) => {
+                inner.consecutive_failures += 1;
+                inner.transition(BreakerState::Open, now);
+            }
+            // Stragglers dispatched before the breaker opened.
+            (BreakerState::Open, _) => {}
+        }
+    }
+
+    pub fn status(&self) -> BreakerStatus {
+        let inner = self.inner.lock().expect("breaker lock");
+        BreakerStatus {
+            state: inner.state,
+            consecutive_failures: inner.consecutive_failures,
+            opened_at: inner.opened_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cfg() -> BreakerConfig {
+        BreakerConfig {
+            failure_threshold: 2,
+            open_seconds: 30,
+        }
+    }
+
+    #[test]
+    fn opens_after_consecutive_failures_and_probes_once() {
+        let breaker = CircuitBreaker::new();
+        let cfg = cfg();
+        breaker.record(&cfg, true, 100);
+        breaker.record(&cfg, false, 100);
+        breaker.record(&cfg, true, 101);
+        assert!(matches!(breaker.gate(&cfg, 101), Gate::Closed));
+        breaker.record(&cfg, true, 102);
+        assert_eq!(breaker.status().state, BreakerState::Open);
+        assert_eq!(breaker.status().opened_at, Some(102));
+        assert!(matches!(breaker.gate(&cfg, 131), Gate::Open));
+
+        let probe = breaker.gate(&cfg, 132);
+        assert!(matches!(probe, Gate::Probe(_)));
+        assert!(matches!(breaker.gate(&cfg, 132), Gate::Open));
+        // A probe that never reached the dependency is handed out again.
+        drop(probe);
+        let Gate::Probe(_probe) = breaker.gate(&cfg, 133) else {
+            panic!("expected a probe");
+        };
+        breaker.record(&cfg, true, 133);
+        assert_eq!(breaker.status().state, BreakerState::Open);
+        assert_eq!(breaker.status().opened_at, Some(133));
+    }
+
+    #[test]
+    fn successful_probe_closes() {
+        let breaker = CircuitBreaker::new();
+        let cfg = cfg();
+        breaker.record(&cfg, true, 100);
+        breaker.record(&cfg, true, 100);
+        let Gate::Probe(_probe) = breaker.gate(&cfg, 130) else {
+            panic!("expected a probe");
+        };
+        breaker.record(&cfg, false, 131);
+        let status = breaker.status();
+        assert_eq!(status.state, BreakerState::Closed);
+        assert_eq!((status.consecutive_failures, status.opened_at), (0, None));
+        assert!(matches!(breaker.gate(&cfg, 131), Gate::Closed));
+    }
+}
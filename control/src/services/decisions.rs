@@ -0,0 +1,148 @@
+//! Persisted fast-loop decision traces, one per workload evaluation.
+//!
+//! The trace is stored as JSON next to the columns needed to filter it, so
+//! new trace fields don't need a migration.
+
+use anyhow::Result;
+
+use crate::db::{execute_async, params, Connection, DbPool};
+use crate::types::DecisionTrace;
+
+/// Traces older than this are pruned by the slow loop.
+pub const DECISION_RETENTION_SECONDS: i64 = 24 * 60 * 60;
+
+pub fn record_decision_tx(conn: &Connection, trace: &DecisionTrace) -> Result<i64> {
+    conn.query_row(
+        "INSERT INTO orchestrator_decision
+         (tenant_id, workload_id, reason_code, applied, suppressed_by, trace_json, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+         RETURNING decision_id",
+        params![
+            trace.tenant_id,
+            trace.workload_id,
+            trace.proposed.reason_code,
+            if trace.applied { 1 } else { 0 },
+            trace.suppressed_by,
+            serde_json::to_string(trace)?,
+            trace.created_at
+        ],
+        |row| row.get(0),
+    )
+}
+
+fn list_decisions_tx(
+    conn: &Connection,
+    tenant_id: &str,
+    workload_id: &str,
+    before: Option<i64>,
+    limit: usize,
+) -> Result<Vec<DecisionTrace>> {
+    let rows = conn.query_map(
+        "SELECT decision_id, trace_json FROM orchestrator_decision
+         WHERE tenant_id = ?1 AND workload_id = ?2
+           AND (CAST(?3 AS BIGINT) IS NULL OR decision_id < ?3)
+         ORDER BY decision_id DESC
+         LIMIT ?4",
+        params![tenant_id, workload_id, before, limit as i64],
+        |row| Ok((row.get::<i64>(0)?, row.get::<String>(1)?)),
+    )?;
+    rows.into_iter()
+        .map(|(decision_id, raw)| {
+            let mut trace: DecisionTrace = serde_json::from_str(&raw)?;
+            trace.decision_id = decision_id;
+            Ok(trace)
+        })
+        .collect()
+}
+
+/// Decisions for a workload, newest first. `before` pages back from a
+/// previously returned `decision_id`.
+pub async fn list_decisions(
+    db: &DbPool,
+    tenant_id: &str,
+    workload_id: &str,
+    before: Option<i64>,
+    limit: usize,
+) -> Result<Vec<DecisionTrace>> {
+    let tenant_id = tenant_id.to_string();
+    let workload_id = workload_id.to_string();
+    execute_async(db, move |conn| {
+        list_decisions_tx(conn, &tenant_id, &workload_id, before, limit)
+    })
+    .await
+}
+
+pub fn prune_decisions_tx(conn: &Connection, now: i64) -> Result<usize> {
+    conn.execute(
+        "DELETE FROM orchestrator_decision WHERE created_at < ?1",
+        params![now - DECISION_RETENTION_SECONDS],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{OrchestratorIntent, WorkloadObservation};
+
+    fn trace(workload_id: &str, created_at: i64) -> DecisionTrace {
+        DecisionTrace {
+            decision_id: 0,
+            tenant_id: "t".to_string(),
+            workload_id: workload_id.to_string(),
+            observation: WorkloadObservation {
+                workload_id: workload_id.to_string(),
+                ..WorkloadObservation::fixture()
+            },
+            pressure: 0.1,
+            queue_boost: 1.0,
+            base_target: 1,
+            current_target: None,
+            conditions: Vec::new(),
+            clamps: Vec::new(),
+            proposed: OrchestratorIntent {
+                tenant_id: "t".to_string(),
+                workload_id: workload_id.to_string(),
+                target_concurrency: 2,
+                burst_cpu_cap: 1.0,
+                burst_mem_mb: 256,
+                burst_ttl_seconds: 60,
+                pool_min_ready: 1,
+                pool_target_ready: 1,
+                pool_max_ready: 1,
+                preferred_node_group: "g".to_string(),
+                anti_affinity: false,
+                reason_code: "STEADY_STATE".to_string(),
+                effective_at: created_at,
+                ttl_seconds: 60,
+                updated_at: created_at,
+            },
+            suppressed_by: None,
+            applied: true,
+            created_at,
+        }
+    }
+
+    #[test]
+    fn decisions_page_newest_first_per_workload() {
+        let conn = crate::db::test_conn();
+        let first = record_decision_tx(&conn, &trace("w", 100)).expect("first");
+        record_decision_tx(&conn, &trace("other", 101)).expect("other");
+        let second = record_decision_tx(&conn, &trace("w", 102)).expect("second");
+
+        let page = list_decisions_tx(&conn, "t", "w", None, 1).expect("page");
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].decision_id, second);
+        assert_eq!(page[0].proposed.reason_code, "STEADY_STATE");
+
+        let older = list_decisions_tx(&conn, "t", "w", Some(second), 10).expect("older");
+        assert_eq!(
+            older.iter().map(|d| d.decision_id).collect::<Vec<_>>(),
+            [first]
+        );
+
+        assert_eq!(
+            prune_decisions_tx(&conn, 102 + DECISION_RETENTION_SECONDS).expect("prune"),
+            2
+        );
+    }
+}
@@ -0,0 +1,178 @@
+//! Admission control for concurrent action dispatch.
+//!
+//! The dispatch loop hands each admitted action to its own task and does not
+//! wait for it, so a slow elasticity call holds one slot instead of stalling
+//! the cycle. Slots are bounded globally, per tenant and per target (the
+//! container, node group or workload an action changes); an action still in
+//! flight from an earlier cycle is skipped until its slot is released.
+//!
+//! Fairness across tenants comes from the candidate order: the loop loads
+//! candidates interleaved round-robin by tenant, and admission keeps that
+//! order while skipping anything over a limit.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use crate::metrics;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DispatchLimits {
+    pub max_in_flight: usize,
+    pub per_tenant: usize,
+    pub per_target: usize,
+}
+
+/// Identity of an action for admission.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotKey {
+    pub action_id: String,
+    pub tenant_id: String,
+    pub target: String,
+}
+
+#[derive(Debug, Default)]
+struct InFlight {
+    actions: HashSet<String>,
+    by_tenant: HashMap<String, usize>,
+    by_target: HashMap<String, usize>,
+}
+
+impl InFlight {
+    fn admits(&self, key: &SlotKey, limits: &DispatchLimits) -> bool {
+        self.actions.len() < limits.max_in_flight
+            && !self.actions.contains(&key.action_id)
+            && self.by_tenant.get(&key.tenant_id).copied().unwrap_or(0) < limits.per_tenant
+            && self.by_target.get(&key.target).copied().unwrap_or(0) < limits.per_target
+    }
+
+    fn reserve(&mut self, key: &SlotKey) {
+        self.actions.insert(key.action_id.clone());
+        *self.by_tenant.entry(key.tenant_id.clone()).or_default() += 1;
+        *self.by_target.entry(key.target.clone()).or_default() += 1;
+    }
+
+    fn release(&mut self, key: &SlotKey) {
+        self.actions.remove(&key.action_id);
+        for (map, k) in [
+            (&mut self.by_tenant, &key.tenant_id),
+            (&mut self.by_target, &key.target),
+        ] {
+            if let Some(n) = map.get_mut(k) {
+                *n -= 1;
+                if *n == 0 {
+                    map.remove(k);
+                }
+            }
+        }
+    }
+}
+
+/// In-flight dispatches shared by every cycle of the dispatch loop.
+#[derive(Debug, Clone, Default)]
+pub struct Dispatcher {
+    in_flight: Arc<Mutex<InFlight>>,
+}
+
+/// A reserved dispatch slot, released when dropped.
+#[derive(Debug)]
+pub struct Slot {
+    key: SlotKey,
+    in_flight: Arc<Mutex<InFlight>>,
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        let mut in_flight = self.in_flight.lock().expect("dispatch lock");
+        in_flight.release(&self.key);
+        metrics::set_dispatch_in_flight(in_flight.actions.len());
+    }
+}
+
+impl Dispatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Admit `candidates` in order as far as `limits` allow, reserving a slot
+    /// for each admitted one.
+    pub fn admit<T>(
+        &self,
+        candidates: Vec<T>,
+        limits: &DispatchLimits,
+        key: impl Fn(&T) -> SlotKey,
+    ) -> Vec<(T, Slot)> {
+        let mut in_flight = self.in_flight.lock().expect("dispatch lock");
+        let mut admitted = Vec::new();
+        for candidate in candidates {
+            if in_flight.actions.len() >= limits.max_in_flight {
+                break;
+            }
+            let key = key(&candidate);
+            if !in_flight.admits(&key, limits) {
+                continue;
+            }
+            in_flight.reserve(&key);
+            admitted.push((
+                candidate,
+                Slot {
+                    key,
+                    in_flight: self.in_flight.clone(),
+                },
+            ));
+        }
+        metrics::set_dispatch_in_flight(in_flight.actions.len());
+        admitted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Candidate = (&'static str, &'static str, &'static str);
+
+    fn key(c: &Candidate) -> SlotKey {
+        SlotKey {
+            action_id: c.0.to_string(),
+            tenant_id: c.1.to_string(),
+            target: c.2.to_string(),
+        }
+    }
+
+    fn ids(admitted: &[(Candidate, Slot)]) -> Vec<&'static str> {
+        admitted.iter().map(|((id, _, _), _)| *id).collect()
+    }
+
+    #[test]
+    fn admission_respects_every_limit_and_releases_on_drop() {
+        let dispatcher = Dispatcher::new();
+        let limits = DispatchLimits {
+            max_in_flight: 4,
+            per_tenant: 2,
+            per_target: 1,
+        };
+        let candidates = vec![
+            ("a1", "a", "x"),
+            ("b1", "b", "y"),
+            ("a2", "a", "x"),
+            ("a3", "a", "z"),
+            ("a4", "a", "w"),
+            ("b2", "b", "v"),
+        ];
+        let mut first = dispatcher.admit(candidates.clone(), &limits, key);
+        // a2 shares a1's target and a4 is over tenant a's limit.
+        assert_eq!(ids(&first), ["a1", "b1", "a3", "b2"]);
+
+        // Still in flight: nothing new fits.
+        assert!(dispatcher
+            .admit(candidates.clone(), &limits, key)
+            .is_empty());
+
+        // a1 finished and left the queue.
+        drop(first.remove(0));
+        assert_eq!(dispatcher.in_flight.lock().unwrap().actions.len(), 3);
+        let remaining = candidates.into_iter().filter(|c| c.0 != "a1").collect();
+        let second = dispatcher.admit(remaining, &limits, key);
+        assert_eq!(ids(&second), ["a2"]);
+    }
+}
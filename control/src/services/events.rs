@@ -0,0 +1,287 @@
+//! Persisted log of orchestrator action and intent changes.
+//!
+//! Events are written in the same transaction as the change they describe,
+//! so the log never runs ahead of or behind the tables. `seq` is the SSE
+//! event id: clients resume with `Last-Event-ID` from any control plane
+//! instance sharing the DB. Appends are serialized until commit, so events
+//! become visible in `seq` order and a reader past some `seq` never misses
+//! a smaller one committing late.
+
+use anyhow::Result;
+use serde_json::Value;
+
+use crate::db::{execute_async, params, Connection, DbPool, Transaction};
+use crate::types::OrchestratorEvent;
+
+pub const EVENT_ACTION_ENQUEUED: &str = "action.enqueued";
+pub const EVENT_ACTION_DISPATCHED: &str = "action.dispatched";
+pub const EVENT_ACTION_POLLED: &str = "action.polled";
+pub const EVENT_ACTION_RETRIED: &str = "action.retried";
+pub const EVENT_ACTION_ROLLED_BACK: &str = "action.rolled_back";
+pub const EVENT_ACTION_REPORTED: &str = "action.reported";
+pub const EVENT_ACTION_REQUEUED: &str = "action.requeued";
+pub const EVENT_ACTION_TERMINAL: &str = "action.terminal";
+pub const EVENT_INTENT_UPDATED: &str = "intent.updated";
+pub const EVENT_INTENT_CLEARED: &str = "intent.cleared";
+
+/// Events older than this are pruned by the slow loop. A client resuming
+/// from a pruned `Last-Event-ID` continues from the oldest retained event.
+pub const EVENT_RETENTION_SECONDS: i64 = 24 * 60 * 60;
+
+/// Event about to be appended to the log.
+#[derive(Debug, Clone, Default)]
+pub struct NewEvent<'a> {
+    pub event_type: &'a str,
+    pub tenant_id: &'a str,
+    pub workload_id: &'a str,
+    pub action_id: Option<&'a str>,
+    pub action_type: Option<&'a str>,
+    pub status: Option<&'a str>,
+    pub reason_code: Option<&'a str>,
+    pub data: Value,
+}
+
+/// Lock taken by every append until its transaction commits. PostgreSQL
+/// hands out `seq` at insert time, so without it a transaction holding a
+/// lower `seq` could commit after a stream has moved past it.
+const EVENT_LOG_LOCK: &str = "orchestrator_event";
+
+/// Open a transaction that will append events. The event log lock is taken
+/// first: taken at the first append instead, a transaction already holding
+/// rows another appender waits on could deadlock against it.
+pub fn transaction(conn: &Connection) -> Result<Transaction<'_>> {
+    let tx = conn.transaction()?;
+    tx.lock_until_commit(EVENT_LOG_LOCK)?;
+    Ok(tx)
+}
+
+/// [`execute_async`] in a [`transaction`] that commits when `f` succeeds.
+pub async fn execute_logged<F, T>(db: &DbPool, f: F) -> Result<T>
+where
+    F: FnOnce(&Connection) -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    execute_async(db, move |conn| {
+        let tx = transaction(conn)?;
+        let out = f(&tx)?;
+        tx.commit()?;
+        Ok(out)
+    })
+    .await
+}
+
+/// Append `event`, inside the transaction making the change; see
+/// [`transaction`].
+pub fn record_event_tx(conn: &Connection, event: &NewEvent<'_>, now: i64) -> Result<i64> {
+    conn.lock_until_commit(EVENT_LOG_LOCK)?;
+    let data = if event.data.is_null() {
+        Value::Object(Default::default())
+    } else {
+        event.data.clone()
+    };
+    conn.query_row(
+        "INSERT INTO orchestrator_event
+         (event_type, tenant_id, workload_id, action_id, action_type, status, reason_code, data_json, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+         RETURNING seq",
+        params![
+            event.event_type,
+            event.tenant_id,
+            event.workload_id,
+            event.action_id,
+            event.action_type,
+            event.status,
+            event.reason_code,
+            data.to_string(),
+            now
+        ],
+        |row| row.get(0),
+    )
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    pub tenant_id: Option<String>,
+    pub workload_id: Option<String>,
+    pub action_type: Option<String>,
+}
+
+pub(crate) fn list_events_tx(
+    conn: &Connection,
+    filter: &EventFilter,
+    after_seq: i64,
+    limit: usize,
+) -> Result<Vec<OrchestratorEvent>> {
+    conn.query_map(
+        "SELECT seq, event_type, tenant_id, workload_id, action_id, action_type, status, reason_code, data_json, created_at
+         FROM orchestrator_event
+         WHERE seq > ?1
+           AND (CAST(?2 AS TEXT) IS NULL OR tenant_id = ?2)
+           AND (CAST(?3 AS TEXT) IS NULL OR workload_id = ?3)
+           AND (CAST(?4 AS TEXT) IS NULL OR action_type = ?4)
+         ORDER BY seq ASC
+         LIMIT ?5",
+        params![
+            after_seq,
+            filter.tenant_id,
+            filter.workload_id,
+            filter.action_type,
+            limit as i64
+        ],
+        |row| {
+            let data: String = row.get(8)?;
+            Ok(OrchestratorEvent {
+                seq: row.get(0)?,
+                event_type: row.get(1)?,
+                tenant_id: row.get(2)?,
+                workload_id: row.get(3)?,
+                action_id: row.get(4)?,
+                action_type: row.get(5)?,
+                status: row.get(6)?,
+                reason_code: row.get(7)?,
+                data: serde_json::from_str(&data).unwrap_or(Value::Null),
+                created_at: row.get(9)?,
+            })
+        },
+    )
+}
+
+/// Events after `after_seq` matching `filter`, oldest first.
+pub async fn list_events(
+    db: &DbPool,
+    filter: &EventFilter,
+    after_seq: i64,
+    limit: usize,
+) -> Result<Vec<OrchestratorEvent>> {
+    let filter = filter.clone();
+    execute_async(db, move |conn| {
+        list_events_tx(conn, &filter, after_seq, limit)
+    })
+    .await
+}
+
+/// Sequence number of the newest event, 0 when the log is empty.
+pub async fn latest_seq(db: &DbPool) -> Result<i64> {
+    execute_async(db, |conn| {
+        conn.query_row(
+            "SELECT COALESCE(MAX(seq), 0) FROM orchestrator_event",
+            params![],
+            |row| row.get(0),
+        )
+    })
+    .await
+}
+
+pub fn prune_events_tx(conn: &Connection, now: i64) -> Result<usize> {
+    conn.execute(
+        "DELETE FROM orchestrator_event WHERE created_at < ?1",
+        params![now - EVENT_RETENTION_SECONDS],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn action_event<'a>(tenant_id: &'a str, action_type: &'a str) -> NewEvent<'a> {
+        NewEvent {
+            event_type: EVENT_ACTION_ENQUEUED,
+            tenant_id,
+            workload_id: "w",
+            action_id: Some("a1"),
+            action_type: Some(action_type),
+            data: json!({ "attempt": 1 }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn events_resume_after_seq_and_filter() {
+        let conn = crate::db::test_conn();
+        let first = record_event_tx(&conn, &action_event("t1", "SetPoolTarget"), 100).expect("1");
+        let second = record_event_tx(&conn, &action_event("t2", "SetPoolTarget"), 101).expect("2");
+        let third = record_event_tx(&conn, &action_event("t1", "SetBurstPolicy"), 102).expect("3");
+        assert!(first < second && second < third);
+
+        let all = list_events_tx(&conn, &EventFilter::default(), 0, 10).expect("all");
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].data["attempt"], 1);
+
+        let resumed = list_events_tx(&conn, &EventFilter::default(), second, 10).expect("resume");
+        assert_eq!(resumed.iter().map(|e| e.seq).collect::<Vec<_>>(), [third]);
+
+        let filter = EventFilter {
+            tenant_id: Some("t1".to_string()),
+            action_type: Some("SetPoolTarget".to_string()),
+            ..Default::default()
+        };
+        let filtered = list_events_tx(&conn, &filter, 0, 10).expect("filtered");
+        assert_eq!(filtered.iter().map(|e| e.seq).collect::<Vec<_>>(), [first]);
+    }
+
+    #[test]
+    fn old_events_are_pruned() {
+        let conn = crate::db::test_conn();
+        record_event_tx(&conn, &action_event("t", "SetPoolTarget"), 100).expect("old");
+        let kept = record_event_tx(
+            &conn,
+            &action_event("t", "SetPoolTarget"),
+            100 + EVENT_RETENTION_SECONDS,
+        )
+        .expect("new");
+        assert_eq!(
+            prune_events_tx(&conn, 101 + EVENT_RETENTION_SECONDS).expect("prune"),
+            1
+        );
+        let left = list_events_tx(&conn, &EventFilter::default(), 0, 10).expect("left");
+        assert_eq!(left.iter().map(|e| e.seq).collect::<Vec<_>>(), [kept]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[ignore = "needs PostgreSQL: set QUILT_TEST_POSTGRES_URL and run with --ignored"]
+    async fn postgres_resuming_readers_never_skip_a_late_commit() {
+        let db = tokio::task::spawn_blocking(|| crate::db::postgres_test_pool("events"))
+            .await
+            .expect("join");
+        let writers = tokio::spawn(futures::future::join_all((0..6).map(|_| {
+            let db = db.clone();
+            async move {
+                for _ in 0..10 {
+                    execute_logged(&db, |conn| {
+                        record_event_tx(conn, &action_event("t", "SetPoolTarget"), 100)?;
+                        // Keep the transaction open so commits interleave
+                        // with reads.
+                        std::thread::sleep(std::time::Duration::from_millis(2));
+                        record_event_tx(conn, &action_event("t", "SetBurstPolicy"), 100)
+                    })
+                    .await
+                    .expect("append");
+                }
+            }
+        })));
+
+        // Follow the log the way an SSE stream resumes from Last-Event-ID.
+        let (mut seen, mut after) = (Vec::new(), 0);
+        loop {
+            let done = writers.is_finished();
+            for event in list_events(&db, &EventFilter::default(), after, 1000)
+                .await
+                .expect("list")
+            {
+                after = event.seq;
+                seen.push(event.seq);
+            }
+            if done {
+                break;
+            }
+        }
+        writers.await.expect("writers");
+
+        let all = list_events(&db, &EventFilter::default(), 0, 1000)
+            .await
+            .expect("all");
+        assert_eq!(all.len(), 120);
+        assert_eq!(seen, all.iter().map(|e| e.seq).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,520 @@
+//! Executors apply dispatched orchestrator actions to the outside world.
+//!
+//! The dispatch loop owns the action state machine (TTL, retries, rollback,
+//! terminal bookkeeping); an [`ActionExecutor`] only starts an action and
+//! reports on it. Executors are looked up per action type in an
+//! [`ExecutorRegistry`], so a new backend plugs in without touching dispatch.
+//!
+//! Failures are returned as `anyhow` errors carrying a reason code, either
+//! bare (`ELASTICITY_INVALID_ARGUMENT`) or as
+//! `CODE|retryable=<true|false|unknown>|detail`, which the dispatch loop uses
+//! to decide between retry and terminal failure.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::info;
+
+use crate::services::orchestrator::{
+    normalize_reason_code, RC_IDEMPOTENCY_REPLAY, RC_INVALID_ARGUMENT, RC_ORCH_ACTION_UNSUPPORTED,
+    RC_ORCH_DEPENDENCY_UNAVAILABLE, RUNTIME_SUCCESS,
+};
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct NodeGroupScale {
+    pub node_group: String,
+    pub delta_units: i64,
+}
+
+/// Typed payload of an orchestrator action, keyed by `action_type`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "action_type", content = "payload")]
+pub enum ActionPayload {
+    SetPoolTarget {
+        function_id: String,
+        min_instances: u32,
+        max_instances: u32,
+    },
+    SetBurstPolicy {
+        container_id: String,
+        memory_limit_mb: u32,
+        cpu_limit_percent: u32,
+    },
+    SetPlacementPreference {
+        workload_id: String,
+        node_group: String,
+        #[serde(default)]
+        anti_affinity: bool,
+    },
+    ScaleNodeGroupUp(NodeGroupScale),
+    ScaleNodeGroupDown(NodeGroupScale),
+    RollbackAction {
+        target_action_id: Option<String>,
+        target_operation_id: Option<String>,
+        reason_code: Option<String>,
+        reason_message: Option<String>,
+        payload: Option<Value>,
+    },
+}
+
+pub const ACTION_TYPES: [&str; 6] = [
+    "SetPoolTarget",
+    "SetBurstPolicy",
+    "SetPlacementPreference",
+    "ScaleNodeGroupUp",
+    "ScaleNodeGroupDown",
+    "RollbackAction",
+];
+
+fn required(value: &mut String) -> Result<()> {
+    *value = value.trim().to_string();
+    if value.is_empty() {
+        anyhow::bail!(RC_INVALID_ARGUMENT);
+    }
+    Ok(())
+}
+
+impl ActionPayload {
+    /// Decode a stored `payload_json`. Unknown action types fail with
+    /// `ORCH_ACTION_UNSUPPORTED`, malformed payloads with
+    /// `ELASTICITY_INVALID_ARGUMENT`. Identifiers are trimmed and must be
+    /// non-empty.
+    pub fn decode(action_type: &str, payload: &Value) -> Result<Self> {
+        if !ACTION_TYPES.contains(&action_type) {
+            anyhow::bail!(RC_ORCH_ACTION_UNSUPPORTED);
+        }
+        let mut decoded: ActionPayload =
+            serde_json::from_value(json!({ "action_type": action_type, "payload": payload }))
+                .map_err(|e| anyhow::anyhow!("{}|retryable=false|{}", RC_INVALID_ARGUMENT, e))?;
+        match &mut decoded {
+            ActionPayload::SetPoolTarget { function_id, .. } => required(function_id)?,
+            ActionPayload::SetBurstPolicy { container_id, .. } => required(container_id)?,
+            ActionPayload::SetPlacementPreference {
+                workload_id,
+                node_group,
+                ..
+            } => {
+                required(workload_id)?;
+                required(node_group)?;
+            }
+            ActionPayload::ScaleNodeGroupUp(scale) | ActionPayload::ScaleNodeGroupDown(scale) => {
+                required(&mut scale.node_group)?
+            }
+            ActionPayload::RollbackAction { .. } => {}
+        }
+        Ok(decoded)
+    }
+
+    pub fn action_type(&self) -> &'static str {
+        match self {
+            ActionPayload::SetPoolTarget { .. } => "SetPoolTarget",
+            ActionPayload::SetBurstPolicy { .. } => "SetBurstPolicy",
+            ActionPayload::SetPlacementPreference { .. } => "SetPlacementPreference",
+            ActionPayload::ScaleNodeGroupUp(_) => "ScaleNodeGroupUp",
+            ActionPayload::ScaleNodeGroupDown(_) => "ScaleNodeGroupDown",
+            ActionPayload::RollbackAction { .. } => "RollbackAction",
+        }
+    }
+}
+
+/// An action as handed to an executor.
+#[derive(Debug, Clone, Copy)]
+pub struct ActionRequest<'a> {
+    pub action_id: &'a str,
+    pub tenant_id: &'a str,
+    pub workload_id: &'a str,
+    pub idempotency_key: &'a str,
+    pub payload: &'a ActionPayload,
+}
+
+/// State of an operation started by an executor.
+#[derive(Debug, Clone)]
+pub struct RuntimeOperation {
+    pub operation_id: String,
+    pub operation_type: Option<String>,
+    pub status: String,
+    pub reason_code: Option<String>,
+    pub reason_message: Option<String>,
+}
+
+#[async_trait]
+pub trait ActionExecutor: Send + Sync {
+    /// Start the action. `Ok(None)` means it completed synchronously and
+    /// there is no operation to poll.
+    async fn dispatch(&self, action: &ActionRequest<'_>) -> Result<Option<RuntimeOperation>>;
+
+    /// Current state of an operation returned by [`ActionExecutor::dispatch`].
+    async fn poll(
+        &self,
+        action: &ActionRequest<'_>,
+        operation_id: &str,
+    ) -> Result<RuntimeOperation>;
+}
+
+/// Executors by action type.
+#[derive(Clone, Default)]
+pub struct ExecutorRegistry {
+    executors: HashMap<&'static str, Arc<dyn ActionExecutor>>,
+}
+
+impl ExecutorRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registry with `executor` handling every known action type.
+    pub fn uniform(executor: Arc<dyn ActionExecutor>) -> Self {
+        let mut registry = Self::new();
+        for action_type in ACTION_TYPES {
+            registry.register(action_type, executor.clone());
+        }
+        registry
+    }
+
+    /// Route `action_type` to `executor`, replacing any earlier registration.
+    pub fn register(
+        &mut self,
+        action_type: &'static str,
+        executor: Arc<dyn ActionExecutor>,
+    ) -> &mut Self {
+        self.executors.insert(action_type, executor);
+        self
+    }
+
+    /// Decode an action's payload and find the executor for it.
+    pub fn resolve(
+        &self,
+        action_type: &str,
+        payload: &Value,
+    ) -> Result<(Arc<dyn ActionExecutor>, ActionPayload)> {
+        let payload = ActionPayload::decode(action_type, payload)?;
+        let executor = self
+            .executors
+            .get(payload.action_type())
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!(RC_ORCH_ACTION_UNSUPPORTED))?;
+        Ok((executor, payload))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ExecutionConfig {
+    pub control_base_url: Option<String>,
+    pub control_api_key: Option<String>,
+    pub connect_timeout: Duration,
+    /// Whole-request deadline, so a hung call frees its dispatch slot.
+    pub request_timeout: Duration,
+}
+
+/// Drives the elasticity control API over HTTP. One pooled client is shared
+/// by every concurrent dispatch.
+pub struct HttpExecutor {
+    http: Client,
+    cfg: ExecutionConfig,
+}
+
+impl HttpExecutor {
+    pub fn new(cfg: ExecutionConfig) -> Result<Self> {
+        let http = Client::builder()
+            .connect_timeout(cfg.connect_timeout)
+            .timeout(cfg.request_timeout)
+            .pool_idle_timeout(Duration::from_secs(90))
+            .build()
+            .context("failed to build elasticity control HTTP client")?;
+        Ok(Self { http, cfg })
+    }
+
+    fn base_url(&self) -> Result<&str> {
+        match &self.cfg.control_base_url {
+            Some(base) => Ok(base),
+            None => anyhow::bail!("{}", RC_ORCH_DEPENDENCY_UNAVAILABLE),
+        }
+    }
+}
+
+/// Path and body of the elasticity control call for an action.
+fn control_request(action: &ActionRequest<'_>) -> (String, Value) {
+    match action.payload {
+        ActionPayload::SetPoolTarget {
+            function_id,
+            min_instances,
+            max_instances,
+        } => (
+            format!("/api/elasticity/control/functions/{function_id}/pool-target"),
+            json!({ "min_instances": min_instances, "max_instances": max_instances }),
+        ),
+        ActionPayload::SetBurstPolicy {
+            container_id,
+            memory_limit_mb,
+            cpu_limit_percent,
+        } => (
+            format!("/api/elasticity/control/containers/{container_id}/resize"),
+            json!({
+                "memory_limit_mb": memory_limit_mb,
+                "cpu_limit_percent": cpu_limit_percent
+            }),
+        ),
+        ActionPayload::SetPlacementPreference {
+            workload_id,
+            node_group,
+            anti_affinity,
+        } => (
+            format!("/api/elasticity/control/workloads/{workload_id}/placement-preference"),
+            json!({ "node_group": node_group, "anti_affinity": anti_affinity }),
+        ),
+        ActionPayload::ScaleNodeGroupUp(scale) | ActionPayload::ScaleNodeGroupDown(scale) => (
+            format!(
+                "/api/elasticity/control/node-groups/{}/scale",
+                scale.node_group
+            ),
+            json!({ "delta_units": scale.delta_units }),
+        ),
+        ActionPayload::RollbackAction {
+            target_action_id,
+            target_operation_id,
+            reason_code,
+            reason_message,
+            payload,
+        } => (
+            format!(
+                "/api/elasticity/control/actions/{}/rollback",
+                target_action_id
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|v| !v.is_empty())
+                    .unwrap_or(action.action_id)
+            ),
+            json!({
+                "target_action_id": target_action_id,
+                "target_operation_id": target_operation_id,
+                "reason_code": reason_code,
+                "reason_message": reason_message,
+                "payload": payload
+            }),
+        ),
+    }
+}
+
+#[async_trait]
+impl ActionExecutor for HttpExecutor {
+    async fn dispatch(&self, action: &ActionRequest<'_>) -> Result<Option<RuntimeOperation>> {
+        let (path, payload) = control_request(action);
+        let url = format!("{}{path}", self.base_url()?);
+        let mut req = self
+            .http
+            .post(url)
+            .header("Idempotency-Key", action.idempotency_key)
+            .header("X-Tenant-Id", action.tenant_id)
+            .header("X-Orch-Action-Id", action.action_id)
+            .json(&payload);
+        if let Some(api_key) = &self.cfg.control_api_key {
+            req = req.header("X-Api-Key", api_key);
+        }
+        let resp = req.send().await.context("runtime request failed")?;
+        parse_runtime_response(resp.status(), resp.text().await.unwrap_or_default()).map(Some)
+    }
+
+    async fn poll(
+        &self,
+        action: &ActionRequest<'_>,
+        operation_id: &str,
+    ) -> Result<RuntimeOperation> {
+        let url = format!(
+            "{}/api/elasticity/control/operations/{operation_id}",
+            self.base_url()?
+        );
+        let mut req = self
+            .http
+            .get(url)
+            .header("X-Tenant-Id", action.tenant_id)
+            .header("X-Orch-Action-Id", action.action_id);
+        if let Some(api_key) = &self.cfg.control_api_key {
+            req = req.header("X-Api-Key", api_key);
+        }
+        let resp = req.send().await.context("runtime poll failed")?;
+        parse_runtime_response(resp.status(), resp.text().await.unwrap_or_default())
+    }
+}
+
+pub(crate) fn parse_runtime_response(status: StatusCode, body: String) -> Result<RuntimeOperation> {
+    let value: Value = serde_json::from_str(&body).unwrap_or_else(|_| json!({}));
+    let reason_code = value
+        .get("reason_code")
+        .and_then(Value::as_str)
+        .map(ToString::to_string);
+    let reason_message = value
+        .get("reason_message")
+        .and_then(Value::as_str)
+        .map(ToString::to_string);
+    let retryable = value.get("retryable").and_then(Value::as_bool);
+
+    if status.is_server_error() {
+        anyhow::bail!("{}|retryable=true|{}", RC_ORCH_DEPENDENCY_UNAVAILABLE, body);
+    }
+
+    let op = RuntimeOperation {
+        operation_id: value
+            .get("operation_id")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string(),
+        operation_type: value
+            .get("operation_type")
+            .and_then(Value::as_str)
+            .map(ToString::to_string),
+        status: value
+            .get("status")
+            .and_then(Value::as_str)
+            .unwrap_or("accepted")
+            .to_string(),
+        reason_code,
+        reason_message,
+    };
+    if status.is_client_error() {
+        if op.reason_code.as_deref() == Some(RC_IDEMPOTENCY_REPLAY) && !op.operation_id.is_empty() {
+            return Ok(op);
+        }
+        let code = normalize_reason_code(op.reason_code.as_deref());
+        anyhow::bail!(
+            "{}|retryable={}|{}",
+            code,
+            retryable
+                .map(|v| if v { "true" } else { "false" })
+                .unwrap_or("unknown"),
+            op.reason_message
+                .clone()
+                .unwrap_or_else(|| "client_error".to_string())
+        );
+    }
+    Ok(op)
+}
+
+/// Logs each action and reports it succeeded without applying it. Useful
+/// for running the control plane without an elasticity backend.
+pub struct LoggingExecutor;
+
+#[async_trait]
+impl ActionExecutor for LoggingExecutor {
+    async fn dispatch(&self, action: &ActionRequest<'_>) -> Result<Option<RuntimeOperation>> {
+        info!(
+            "Dry-run action {} for {}/{}: {}",
+            action.action_id,
+            action.tenant_id,
+            action.workload_id,
+            serde_json::to_string(action.payload)?
+        );
+        Ok(None)
+    }
+
+    async fn poll(
+        &self,
+        _action: &ActionRequest<'_>,
+        operation_id: &str,
+    ) -> Result<RuntimeOperation> {
+        Ok(RuntimeOperation {
+            operation_id: operation_id.to_string(),
+            operation_type: None,
+            status: RUNTIME_SUCCESS.to_string(),
+            reason_code: None,
+            reason_message: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(payload: &ActionPayload) -> ActionRequest<'_> {
+        ActionRequest {
+            action_id: "a1",
+            tenant_id: "t",
+            workload_id: "w",
+            idempotency_key: "k",
+            payload,
+        }
+    }
+
+    #[test]
+    fn payloads_decode_to_typed_control_requests() {
+        let payload = ActionPayload::decode(
+            "SetPoolTarget",
+            &json!({ "function_id": " fn-1 ", "min_instances": 1, "max_instances": 3 }),
+        )
+        .expect("pool target");
+        let (path, body) = control_request(&request(&payload));
+        assert_eq!(path, "/api/elasticity/control/functions/fn-1/pool-target");
+        assert_eq!(body, json!({ "min_instances": 1, "max_instances": 3 }));
+
+        let payload = ActionPayload::decode(
+            "ScaleNodeGroupDown",
+            &json!({ "node_group": "g", "delta_units": 1 }),
+        )
+        .expect("scale");
+        assert_eq!(payload.action_type(), "ScaleNodeGroupDown");
+        let (path, _) = control_request(&request(&payload));
+        assert_eq!(path, "/api/elasticity/control/node-groups/g/scale");
+
+        let payload = ActionPayload::decode("RollbackAction", &json!({})).expect("rollback");
+        let (path, _) = control_request(&request(&payload));
+        assert_eq!(path, "/api/elasticity/control/actions/a1/rollback");
+    }
+
+    #[test]
+    fn bad_payloads_carry_reason_codes() {
+        let err = ActionPayload::decode("Reboot", &json!({})).expect_err("unknown type");
+        assert_eq!(err.to_string(), RC_ORCH_ACTION_UNSUPPORTED);
+        let err = ActionPayload::decode(
+            "SetPoolTarget",
+            &json!({ "function_id": "fn", "min_instances": 1 }),
+        )
+        .expect_err("missing field");
+        assert!(err.to_string().starts_with(RC_INVALID_ARGUMENT));
+        let err = ActionPayload::decode(
+            "SetPlacementPreference",
+            &json!({ "workload_id": "w", "node_group": "  " }),
+        )
+        .expect_err("blank node group");
+        assert_eq!(err.to_string(), RC_INVALID_ARGUMENT);
+    }
+
+    #[test]
+    fn registry_rejects_unregistered_types() {
+        let mut registry = ExecutorRegistry::new();
+        registry.register("SetBurstPolicy", Arc::new(LoggingExecutor));
+        let payload =
+            json!({ "container_id": "c", "memory_limit_mb": 512, "cpu_limit_percent": 150 });
+        assert!(registry.resolve("SetBurstPolicy", &payload).is_ok());
+        let err = registry
+            .resolve(
+                "ScaleNodeGroupUp",
+                &json!({ "node_group": "g", "delta_units": 1 }),
+            )
+            .err()
+            .expect("unregistered");
+        assert_eq!(err.to_string(), RC_ORCH_ACTION_UNSUPPORTED);
+    }
+
+    #[test]
+    fn replay_reason_code_is_treated_as_success_path() {
+        let op = parse_runtime_response(
+            StatusCode::CONFLICT,
+            json!({
+                "operation_id": "op_123",
+                "operation_type": "elasticity.resize_container",
+                "status": "accepted",
+                "reason_code": RC_IDEMPOTENCY_REPLAY,
+                "reason_message": "replayed"
+            })
+            .to_string(),
+        )
+        .expect("replay should not fail");
+        assert_eq!(op.operation_id, "op_123");
+        assert_eq!(op.reason_code.as_deref(), Some(RC_IDEMPOTENCY_REPLAY));
+    }
+}
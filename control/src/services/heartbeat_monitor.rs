@@ -0,0 +1,216 @@
+use anyhow::Result;
+use tracing::{info, warn};
+
+use crate::db::ipam::SubnetAllocator;
+use crate::db::{execute_async, params, Connection, DbPool};
+use crate::services::leader_election::LeaderElection;
+use crate::services::node_registry::{
+    transition_node, NODE_STATUS_DOWN, NODE_STATUS_SUSPECT, NODE_STATUS_UP,
+    TRANSITION_HEARTBEAT_EXPIRED, TRANSITION_HEARTBEAT_MISSED,
+};
+use crate::services::orchestrator::now_unix_seconds;
+use crate::types::NodeTransition;
+
+#[derive(Debug, Clone)]
+pub struct HeartbeatConfig {
+    /// Seconds without a heartbeat before an `up` node becomes `suspect`.
+    pub suspect_after_seconds: u64,
+    /// Seconds without a heartbeat before a node is declared `down`.
+    pub down_after_seconds: u64,
+    pub check_interval_seconds: u64,
+}
+
+impl HeartbeatConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.suspect_after_seconds == 0 || self.check_interval_seconds == 0 {
+            anyhow::bail!("heartbeat thresholds and check interval must be non-zero");
+        }
+        if self.down_after_seconds <= self.suspect_after_seconds {
+            anyhow::bail!(
+                "heartbeat down threshold ({}s) must exceed suspect threshold ({}s)",
+                self.down_after_seconds,
+                self.suspect_after_seconds
+            );
+        }
+        Ok(())
+    }
+}
+
+fn next_status(
+    status: &str,
+    silence: i64,
+    cfg: &HeartbeatConfig,
+) -> Option<(&'static str, &'static str)> {
+    if silence >= cfg.down_after_seconds as i64 {
+        return Some((NODE_STATUS_DOWN, TRANSITION_HEARTBEAT_EXPIRED));
+    }
+    if status == NODE_STATUS_UP && silence >= cfg.suspect_after_seconds as i64 {
+        return Some((NODE_STATUS_SUSPECT, TRANSITION_HEARTBEAT_MISSED));
+    }
+    None
+}
+
+fn sweep_tx(
+    conn: &Connection,
+    ipam: &SubnetAllocator,
+    cfg: &HeartbeatConfig,
+    now: i64,
+) -> Result<Vec<NodeTransition>> {
+    let tx = conn.transaction()?;
+    let stale = tx.query_map(
+        "SELECT node_id, status, last_heartbeat FROM nodes
+         WHERE status IN (?1, ?2) AND last_heartbeat <= ?3",
+        params![
+            NODE_STATUS_UP,
+            NODE_STATUS_SUSPECT,
+            now - cfg.suspect_after_seconds as i64
+        ],
+        |row| {
+            Ok((
+                row.get::<String>(0)?,
+                row.get::<String>(1)?,
+                row.get::<i64>(2)?,
+            ))
+        },
+    )?;
+
+    let mut transitions = Vec::new();
+    for (node_id, status, last_heartbeat) in stale {
+        let Some((to_status, reason)) = next_status(&status, now - last_heartbeat, cfg) else {
+            continue;
+        };
+        // A heartbeat committed since the read keeps the node where it is.
+        transitions.extend(transition_node(
+            &tx,
+            ipam,
+            &node_id,
+            &status,
+            to_status,
+            reason,
+            Some(last_heartbeat),
+            now,
+        )?);
+    }
+    tx.commit()?;
+    Ok(transitions)
+}
+
+/// Run one pass over the registry, demoting nodes whose heartbeats stopped.
+pub async fn sweep(
+    db: &DbPool,
+    ipam: &SubnetAllocator,
+    cfg: &HeartbeatConfig,
+) -> Result<Vec<NodeTransition>> {
+    let now = now_unix_seconds();
+    let ipam = ipam.clone();
+    let cfg = cfg.clone();
+    execute_async(db, move |conn| sweep_tx(conn, &ipam, &cfg, now)).await
+}
+
+/// Spawn the sweep loop. Sweeps only run on the instance holding the leader
+/// lease so replicas do not record duplicate transitions.
+pub async fn start(
+    db: DbPool,
+    ipam: SubnetAllocator,
+    config: HeartbeatConfig,
+    leader: LeaderElection,
+) -> Result<()> {
+    config.validate()?;
+    info!(
+        "Heartbeat monitor started (suspect={}s, down={}s, check={}s)",
+        config.suspect_after_seconds, config.down_after_seconds, config.check_interval_seconds
+    );
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(std::time::Duration::from_secs(
+            config.check_interval_seconds,
+        ));
+        loop {
+            ticker.tick().await;
+            if !leader.is_leader() {
+                continue;
+            }
+            match sweep(&db, &ipam, &config).await {
+                Ok(transitions) => {
+                    for t in transitions {
+                        warn!(
+                            "Node {} {} -> {} ({})",
+                            t.node_id,
+                            t.from_status.as_deref().unwrap_or("-"),
+                            t.to_status,
+                            t.reason
+                        );
+                    }
+                }
+                Err(e) => tracing::error!("Heartbeat monitor failed: {}", e),
+            }
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> HeartbeatConfig {
+        HeartbeatConfig {
+            suspect_after_seconds: 30,
+            down_after_seconds: 60,
+            check_interval_seconds: 5,
+        }
+    }
+
+    #[test]
+    fn stale_nodes_move_up_suspect_down() {
+        assert_stale_nodes_move_up_suspect_down(&crate::db::test_conn());
+    }
+
+    #[test]
+    #[ignore = "needs PostgreSQL: set QUILT_TEST_POSTGRES_URL and run with --ignored"]
+    fn postgres_stale_nodes_move_up_suspect_down() {
+        let pool = crate::db::postgres_test_pool("heartbeat");
+        assert_stale_nodes_move_up_suspect_down(&pool.get().expect("connection"));
+    }
+
+    fn assert_stale_nodes_move_up_suspect_down(conn: &Connection) {
+        let ipam = SubnetAllocator::new("10.42.0.0/16", 24, 600).expect("allocator");
+        ipam.allocate(conn, "n1", 0).expect("allocate");
+        conn.execute(
+            "INSERT INTO nodes (node_id, hostname, host_ip, subnet, status, registered_at, last_heartbeat)
+             VALUES ('n1', 'host', '192.168.1.10', '10.42.1.0/24', 'up', 0, 100)",
+            params![],
+        )
+        .expect("insert node");
+        let cfg = config();
+
+        assert!(sweep_tx(conn, &ipam, &cfg, 129).expect("sweep").is_empty());
+
+        let t = sweep_tx(conn, &ipam, &cfg, 130).expect("sweep");
+        assert_eq!(t.len(), 1);
+        assert_eq!(t[0].to_status, NODE_STATUS_SUSPECT);
+        assert!(sweep_tx(conn, &ipam, &cfg, 159).expect("sweep").is_empty());
+
+        let t = sweep_tx(conn, &ipam, &cfg, 160).expect("sweep");
+        assert_eq!(t[0].from_status.as_deref(), Some(NODE_STATUS_SUSPECT));
+        assert_eq!(t[0].to_status, NODE_STATUS_DOWN);
+        assert_eq!(t[0].reason, TRANSITION_HEARTBEAT_EXPIRED);
+
+        let released_at: Option<i64> = conn
+            .query_row(
+                "SELECT released_at FROM subnet_allocation WHERE node_id = 'n1'",
+                params![],
+                |row| row.get(0),
+            )
+            .expect("allocation");
+        assert_eq!(released_at, Some(160));
+        assert!(sweep_tx(conn, &ipam, &cfg, 500).expect("sweep").is_empty());
+    }
+
+    #[test]
+    fn config_requires_down_after_suspect() {
+        let mut cfg = config();
+        assert!(cfg.validate().is_ok());
+        cfg.down_after_seconds = cfg.suspect_after_seconds;
+        assert!(cfg.validate().is_err());
+    }
+}
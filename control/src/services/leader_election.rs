@@ -0,0 +1,284 @@
+use anyhow::Result;
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::db::{execute_async, params, Connection, DbPool, OptionalExtension};
+use crate::services::orchestrator::now_unix_seconds;
+use crate::types::LeaderStatusResponse;
+
+/// Lease that gates the orchestrator loops and the heartbeat monitor.
+pub const ORCHESTRATOR_LEASE: &str = "orchestrator";
+
+#[derive(Debug, Clone)]
+pub struct LeaderConfig {
+    pub instance_id: String,
+    /// Seconds a lease stays valid without renewal.
+    pub lease_seconds: u64,
+    pub renew_interval_seconds: u64,
+}
+
+impl LeaderConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.instance_id.trim().is_empty() {
+            anyhow::bail!("leader instance id must be non-empty");
+        }
+        if self.renew_interval_seconds == 0 || self.renew_interval_seconds >= self.lease_seconds {
+            anyhow::bail!(
+                "leader renew interval ({}s) must be non-zero and shorter than the lease ({}s)",
+                self.renew_interval_seconds,
+                self.lease_seconds
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Acquire or renew `name` for `holder`. A single conditional upsert, so two
+/// instances racing for an expired lease cannot both win.
+fn try_acquire_tx(
+    conn: &Connection,
+    name: &str,
+    holder: &str,
+    now: i64,
+    lease_seconds: i64,
+) -> Result<bool> {
+    let rows = conn.execute(
+        "INSERT INTO leader_lease (name, holder_id, acquired_at, renewed_at, expires_at)
+         VALUES (?1, ?2, ?3, ?3, ?4)
+         ON CONFLICT(name) DO UPDATE SET
+           acquired_at = CASE WHEN leader_lease.holder_id = excluded.holder_id
+                              THEN leader_lease.acquired_at ELSE excluded.acquired_at END,
+           holder_id = excluded.holder_id,
+           renewed_at = excluded.renewed_at,
+           expires_at = excluded.expires_at
+         WHERE leader_lease.holder_id = excluded.holder_id
+            OR leader_lease.expires_at <= excluded.renewed_at",
+        params![name, holder, now, now + lease_seconds],
+    )?;
+    Ok(rows > 0)
+}
+
+fn release_tx(conn: &Connection, name: &str, holder: &str) -> Result<bool> {
+    let rows = conn.execute(
+        "DELETE FROM leader_lease WHERE name = ?1 AND holder_id = ?2",
+        params![name, holder],
+    )?;
+    Ok(rows > 0)
+}
+
+fn lease_status_tx(
+    conn: &Connection,
+    name: &str,
+    now: i64,
+) -> Result<Option<(String, i64, i64, i64)>> {
+    let row = conn
+        .query_row(
+            "SELECT holder_id, acquired_at, renewed_at, expires_at
+             FROM leader_lease
+             WHERE name = ?1 AND expires_at > ?2",
+            params![name, now],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .optional()?;
+    Ok(row)
+}
+
+struct Inner {
+    db: DbPool,
+    config: LeaderConfig,
+    /// Local deadline of the lease we hold, 0 when not leader. Measured from
+    /// before the renewal write, so it never outlives the row in the DB.
+    held_until: AtomicI64,
+    stopped: AtomicBool,
+    /// Serializes renewals with step-down so a renewal in flight cannot
+    /// re-acquire the lease right after it was released.
+    renew_lock: Mutex<()>,
+}
+
+/// Lease-based leader election over the shared control DB.
+#[derive(Clone)]
+pub struct LeaderElection {
+    inner: Arc<Inner>,
+}
+
+impl LeaderElection {
+    pub fn new(db: DbPool, config: LeaderConfig) -> Result<Self> {
+        config.validate()?;
+        Ok(Self {
+            inner: Arc::new(Inner {
+                db,
+                config,
+                held_until: AtomicI64::new(0),
+                stopped: AtomicBool::new(false),
+                renew_lock: Mutex::new(()),
+            }),
+        })
+    }
+
+    pub fn instance_id(&self) -> &str {
+        &self.inner.config.instance_id
+    }
+
+    /// Whether this instance currently holds an unexpired lease. Loops check
+    /// this on every tick.
+    pub fn is_leader(&self) -> bool {
+        now_unix_seconds() < self.inner.held_until.load(Ordering::SeqCst)
+    }
+
+    async fn renew(&self) -> Result<()> {
+        let _guard = self.inner.renew_lock.lock().await;
+        if self.inner.stopped.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        let was_leader = self.is_leader();
+        let now = now_unix_seconds();
+        let holder = self.inner.config.instance_id.clone();
+        let lease_seconds = self.inner.config.lease_seconds as i64;
+        let acquired = execute_async(&self.inner.db, move |conn| {
+            try_acquire_tx(conn, ORCHESTRATOR_LEASE, &holder, now, lease_seconds)
+        })
+        .await;
+
+        match acquired {
+            Ok(true) => {
+                self.inner
+                    .held_until
+                    .store(now + lease_seconds, Ordering::SeqCst);
+                if !was_leader {
+                    info!(
+                        "Instance {} acquired the {} lease",
+                        self.instance_id(),
+                        ORCHESTRATOR_LEASE
+                    );
+                }
+            }
+            Ok(false) => {
+                self.inner.held_until.store(0, Ordering::SeqCst);
+                if was_leader {
+                    warn!(
+                        "Instance {} lost the {} lease",
+                        self.instance_id(),
+                        ORCHESTRATOR_LEASE
+                    );
+                }
+            }
+            // Keep the local deadline: it expires on its own if the DB stays
+            // unreachable, and a peer cannot take over before then either.
+            Err(e) => tracing::error!("Leader lease renewal failed: {}", e),
+        }
+        Ok(())
+    }
+
+    /// Stop renewing and release the lease so a peer can take over on its
+    /// next renewal instead of waiting for expiry.
+    pub async fn step_down(&self) -> Result<()> {
+        let _guard = self.inner.renew_lock.lock().await;
+        self.inner.stopped.store(true, Ordering::SeqCst);
+        self.inner.held_until.store(0, Ordering::SeqCst);
+        let holder = self.inner.config.instance_id.clone();
+        let released = execute_async(&self.inner.db, move |conn| {
+            release_tx(conn, ORCHESTRATOR_LEASE, &holder)
+        })
+        .await?;
+        if released {
+            info!(
+                "Instance {} released the {} lease",
+                self.instance_id(),
+                ORCHESTRATOR_LEASE
+            );
+        }
+        Ok(())
+    }
+
+    /// Current leader as recorded in the DB, from this instance's view.
+    pub async fn status(&self) -> Result<LeaderStatusResponse> {
+        let now = now_unix_seconds();
+        let lease = execute_async(&self.inner.db, move |conn| {
+            lease_status_tx(conn, ORCHESTRATOR_LEASE, now)
+        })
+        .await?;
+        let mut status = LeaderStatusResponse {
+            instance_id: self.inner.config.instance_id.clone(),
+            is_leader: self.is_leader(),
+            leader_id: None,
+            lease_acquired_at: None,
+            lease_renewed_at: None,
+            lease_expires_at: None,
+            lease_age_seconds: None,
+            seconds_since_renewal: None,
+        };
+        if let Some((holder, acquired_at, renewed_at, expires_at)) = lease {
+            status.leader_id = Some(holder);
+            status.lease_acquired_at = Some(acquired_at);
+            status.lease_renewed_at = Some(renewed_at);
+            status.lease_expires_at = Some(expires_at);
+            status.lease_age_seconds = Some(now - acquired_at);
+            status.seconds_since_renewal = Some(now - renewed_at);
+        }
+        Ok(status)
+    }
+
+    /// Try to acquire the lease immediately, then keep renewing it.
+    pub async fn start(&self) -> Result<()> {
+        self.renew().await?;
+        info!(
+            "Leader election started (instance={}, lease={}s, renew={}s, leader={})",
+            self.instance_id(),
+            self.inner.config.lease_seconds,
+            self.inner.config.renew_interval_seconds,
+            self.is_leader()
+        );
+        let election = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(std::time::Duration::from_secs(
+                election.inner.config.renew_interval_seconds,
+            ));
+            loop {
+                ticker.tick().await;
+                if election.inner.stopped.load(Ordering::SeqCst) {
+                    break;
+                }
+                if let Err(e) = election.renew().await {
+                    tracing::error!("Leader election failed: {}", e);
+                }
+            }
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lease_is_exclusive_until_expiry() {
+        let conn = crate::db::test_conn();
+        assert!(try_acquire_tx(&conn, ORCHESTRATOR_LEASE, "a", 100, 15).expect("acquire"));
+        assert!(!try_acquire_tx(&conn, ORCHESTRATOR_LEASE, "b", 110, 15).expect("acquire"));
+        assert!(try_acquire_tx(&conn, ORCHESTRATOR_LEASE, "a", 110, 15).expect("renew"));
+        assert!(!try_acquire_tx(&conn, ORCHESTRATOR_LEASE, "b", 124, 15).expect("acquire"));
+
+        assert!(try_acquire_tx(&conn, ORCHESTRATOR_LEASE, "b", 125, 15).expect("take over"));
+        let (holder, acquired_at, renewed_at, expires_at) =
+            lease_status_tx(&conn, ORCHESTRATOR_LEASE, 126)
+                .expect("status")
+                .expect("lease");
+        assert_eq!(holder, "b");
+        assert_eq!((acquired_at, renewed_at, expires_at), (125, 125, 140));
+    }
+
+    #[test]
+    fn release_hands_over_immediately() {
+        let conn = crate::db::test_conn();
+        assert!(try_acquire_tx(&conn, ORCHESTRATOR_LEASE, "a", 100, 15).expect("acquire"));
+        assert!(!release_tx(&conn, ORCHESTRATOR_LEASE, "b").expect("release"));
+        assert!(release_tx(&conn, ORCHESTRATOR_LEASE, "a").expect("release"));
+        assert!(lease_status_tx(&conn, ORCHESTRATOR_LEASE, 101)
+            .expect("status")
+            .is_none());
+        assert!(try_acquire_tx(&conn, ORCHESTRATOR_LEASE, "b", 101, 15).expect("acquire"));
+    }
+}
@@ -1 +1,16 @@
+pub mod api_keys;
+pub mod circuit_breaker;
+pub mod decisions;
+pub mod dispatcher;
+pub mod error;
+pub mod events;
+pub mod executor;
+pub mod heartbeat_monitor;
+pub mod leader_election;
+pub mod node_registry;
+pub mod observations;
 pub mod orchestrator;
+pub mod orchestrator_config;
+pub mod retry;
+pub mod scaling;
+pub mod warm_pool;
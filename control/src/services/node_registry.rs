@@ -0,0 +1,657 @@
+use anyhow::{Context, Result};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::db::ipam::SubnetAllocator;
+use crate::db::{
+    execute_async, params, Connection, DbPool, OptionalExtension, Row, TransactionBehavior,
+};
+use crate::services::error::Validator;
+use crate::services::orchestrator::now_unix_seconds;
+use crate::types::{Node, NodeTransition, RegisterNodeRequest, RegisterNodeResponse};
+
+pub const NODE_STATUS_UP: &str = "up";
+pub const NODE_STATUS_SUSPECT: &str = "suspect";
+pub const NODE_STATUS_DOWN: &str = "down";
+
+pub const TRANSITION_REGISTERED: &str = "REGISTERED";
+pub const TRANSITION_HEARTBEAT: &str = "HEARTBEAT";
+pub const TRANSITION_DEREGISTERED: &str = "DEREGISTERED";
+pub const TRANSITION_HEARTBEAT_MISSED: &str = "HEARTBEAT_MISSED";
+pub const TRANSITION_HEARTBEAT_EXPIRED: &str = "HEARTBEAT_EXPIRED";
+
+/// Lock serializing registrations, which scan and claim from the shared
+/// subnet pool.
+const SUBNET_POOL_LOCK: &str = "subnet_pool";
+
+const NODE_COLUMNS: &str = "node_id, hostname, host_ip, subnet, cpu_cores, ram_mb, status, registered_at, last_heartbeat,
+    COALESCE((SELECT MAX(t.transitioned_at) FROM node_status_transition t WHERE t.node_id = nodes.node_id), registered_at)";
+
+fn find_node_by_identity(
+    conn: &Connection,
+    hostname: &str,
+    host_ip: &str,
+) -> Result<Option<(String, String, String)>> {
+    let row = conn
+        .query_row(
+            "SELECT node_id, subnet, status FROM nodes
+             WHERE hostname = ?1 AND host_ip = ?2
+             ORDER BY registered_at DESC
+             LIMIT 1",
+            params![hostname, host_ip],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()?;
+    Ok(row)
+}
+
+fn current_status(conn: &Connection, node_id: &str) -> Result<Option<String>> {
+    let status = conn
+        .query_row(
+            "SELECT status FROM nodes WHERE node_id = ?1",
+            params![node_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(status)
+}
+
+fn insert_transition(
+    conn: &Connection,
+    node_id: &str,
+    from_status: Option<&str>,
+    to_status: &str,
+    reason: &str,
+    now: i64,
+) -> Result<NodeTransition> {
+    let seq = conn.query_row(
+        "INSERT INTO node_status_transition (node_id, from_status, to_status, reason, transitioned_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         RETURNING seq",
+        params![node_id, from_status, to_status, reason, now],
+        |row| row.get(0),
+    )?;
+    Ok(NodeTransition {
+        seq,
+        node_id: node_id.to_string(),
+        from_status: from_status.map(ToString::to_string),
+        to_status: to_status.to_string(),
+        reason: reason.to_string(),
+        transitioned_at: now,
+    })
+}
+
+/// Allocate a subnet for `node_id` from the shared pool. A node whose
+/// quarantined subnet is taken over loses its row.
+fn allocate_subnet(
+    conn: &Connection,
+    ipam: &SubnetAllocator,
+    node_id: &str,
+    now: i64,
+) -> Result<String> {
+    conn.lock_until_commit(SUBNET_POOL_LOCK)?;
+    let lease = ipam.allocate(conn, node_id, now)?;
+    if let Some(previous) = &lease.reclaimed_from {
+        conn.execute("DELETE FROM nodes WHERE node_id = ?1", params![previous])?;
+    }
+    Ok(lease.subnet)
+}
+
+/// Move a node from `from_status` to `to_status`, recording the transition.
+/// Going down starts the subnet quarantine clock; coming back from down
+/// re-activates the subnet lease, or allocates a fresh subnet if the old one
+/// was reclaimed while the node was gone.
+///
+/// Only applies while the node is still in `from_status` and, when
+/// `heartbeat_before` is set, has not heartbeated since; returns `None`
+/// without touching anything otherwise, so a concurrent heartbeat or sweep
+/// wins instead of being overwritten.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn transition_node(
+    conn: &Connection,
+    ipam: &SubnetAllocator,
+    node_id: &str,
+    from_status: &str,
+    to_status: &str,
+    reason: &str,
+    heartbeat_before: Option<i64>,
+    now: i64,
+) -> Result<Option<NodeTransition>> {
+    let changed = conn.execute(
+        "UPDATE nodes SET status = ?1
+         WHERE node_id = ?2 AND status = ?3
+           AND (CAST(?4 AS BIGINT) IS NULL OR last_heartbeat <= ?4)",
+        params![to_status, node_id, from_status, heartbeat_before],
+    )?;
+    if changed == 0 {
+        return Ok(None);
+    }
+    if to_status == NODE_STATUS_DOWN {
+        ipam.release(conn, node_id, now)?;
+    } else if from_status == NODE_STATUS_DOWN {
+        let subnet: String = conn.query_row(
+            "SELECT subnet FROM nodes WHERE node_id = ?1",
+            params![node_id],
+            |row| row.get(0),
+        )?;
+        if !ipam.renew(conn, node_id, &subnet)? {
+            let fresh = allocate_subnet(conn, ipam, node_id, now)?;
+            conn.execute(
+                "UPDATE nodes SET subnet = ?1 WHERE node_id = ?2",
+                params![fresh, node_id],
+            )?;
+            info!(
+                "Node {} came back after subnet {} was reclaimed; moved to {}",
+                node_id, subnet, fresh
+            );
+        }
+    }
+    insert_transition(conn, node_id, Some(from_status), to_status, reason, now).map(Some)
+}
+
+fn register_node_tx(
+    conn: &Connection,
+    ipam: &SubnetAllocator,
+    req: &RegisterNodeRequest,
+) -> Result<RegisterNodeResponse> {
+    // Lock the subnet pool up front so concurrent registrations serialize on
+    // the subnet scan instead of racing to the UNIQUE constraint.
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate(SUBNET_POOL_LOCK))?;
+    let now = now_unix_seconds();
+
+    // A node that restarts re-registers with the same identity and keeps its
+    // subnet, as long as the subnet has not been reclaimed by someone else.
+    if let Some((node_id, subnet, status)) =
+        find_node_by_identity(&tx, &req.hostname, &req.host_ip)?
+    {
+        if ipam.renew(&tx, &node_id, &subnet)? {
+            tx.execute(
+                "UPDATE nodes
+                 SET cpu_cores = ?1, ram_mb = ?2, last_heartbeat = ?3
+                 WHERE node_id = ?4",
+                params![req.cpu_cores, req.ram_mb.map(|v| v as i64), now, node_id],
+            )?;
+            if status != NODE_STATUS_UP {
+                transition_node(
+                    &tx,
+                    ipam,
+                    &node_id,
+                    &status,
+                    NODE_STATUS_UP,
+                    TRANSITION_REGISTERED,
+                    None,
+                    now,
+                )?;
+            }
+            tx.commit()?;
+            return Ok(RegisterNodeResponse { node_id, subnet });
+        }
+    }
+
+    let node_id = Uuid::new_v4().to_string();
+    let subnet = allocate_subnet(&tx, ipam, &node_id, now)?;
+    tx.execute(
+        "INSERT INTO nodes
+         (node_id, hostname, host_ip, subnet, cpu_cores, ram_mb, status, registered_at, last_heartbeat)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?8)",
+        params![
+            node_id,
+            req.hostname,
+            req.host_ip,
+            subnet,
+            req.cpu_cores,
+            req.ram_mb.map(|v| v as i64),
+            NODE_STATUS_UP,
+            now
+        ],
+    )
+    .context("Failed to insert node")?;
+    insert_transition(
+        &tx,
+        &node_id,
+        None,
+        NODE_STATUS_UP,
+        TRANSITION_REGISTERED,
+        now,
+    )?;
+    tx.commit()?;
+    Ok(RegisterNodeResponse { node_id, subnet })
+}
+
+fn record_heartbeat(
+    conn: &Connection,
+    ipam: &SubnetAllocator,
+    node_id: &str,
+    now: i64,
+) -> Result<bool> {
+    let tx = conn.transaction()?;
+    let Some(status) = current_status(&tx, node_id)? else {
+        return Ok(false);
+    };
+    tx.execute(
+        "UPDATE nodes SET last_heartbeat = ?1 WHERE node_id = ?2",
+        params![now, node_id],
+    )?;
+    if status != NODE_STATUS_UP {
+        if let Some(t) = transition_node(
+            &tx,
+            ipam,
+            node_id,
+            &status,
+            NODE_STATUS_UP,
+            TRANSITION_HEARTBEAT,
+            None,
+            now,
+        )? {
+            info!(
+                "Node {} recovered ({} -> up)",
+                node_id,
+                t.from_status.unwrap_or_default()
+            );
+        }
+    }
+    tx.commit()?;
+    Ok(true)
+}
+
+fn mark_node_down(
+    conn: &Connection,
+    ipam: &SubnetAllocator,
+    node_id: &str,
+    now: i64,
+) -> Result<bool> {
+    let tx = conn.transaction()?;
+    let Some(status) = current_status(&tx, node_id)? else {
+        return Ok(false);
+    };
+    if status != NODE_STATUS_DOWN {
+        transition_node(
+            &tx,
+            ipam,
+            node_id,
+            &status,
+            NODE_STATUS_DOWN,
+            TRANSITION_DEREGISTERED,
+            None,
+            now,
+        )?;
+    }
+    tx.commit()?;
+    Ok(true)
+}
+
+fn row_to_node(row: &Row) -> Result<Node> {
+    Ok(Node {
+        node_id: row.get(0)?,
+        hostname: row.get(1)?,
+        host_ip: row.get(2)?,
+        subnet: row.get(3)?,
+        cpu_cores: row.get(4)?,
+        ram_mb: row.get::<Option<i64>>(5)?.map(|v| v as u64),
+        status: row.get(6)?,
+        registered_at: row.get(7)?,
+        last_heartbeat: row.get(8)?,
+        status_changed_at: row.get(9)?,
+    })
+}
+
+fn list_nodes_tx(conn: &Connection) -> Result<Vec<Node>> {
+    conn.query_map(
+        &format!("SELECT {NODE_COLUMNS} FROM nodes ORDER BY registered_at, node_id"),
+        params![],
+        row_to_node,
+    )
+}
+
+fn list_transitions_tx(
+    conn: &Connection,
+    node_id: Option<&str>,
+    after_seq: i64,
+    limit: usize,
+) -> Result<Vec<NodeTransition>> {
+    conn.query_map(
+        "SELECT seq, node_id, from_status, to_status, reason, transitioned_at
+         FROM node_status_transition
+         WHERE seq > ?1 AND (CAST(?2 AS TEXT) IS NULL OR node_id = ?2)
+         ORDER BY seq ASC
+         LIMIT ?3",
+        params![after_seq, node_id, limit as i64],
+        |row| {
+            Ok(NodeTransition {
+                seq: row.get(0)?,
+                node_id: row.get(1)?,
+                from_status: row.get(2)?,
+                to_status: row.get(3)?,
+                reason: row.get(4)?,
+                transitioned_at: row.get(5)?,
+            })
+        },
+    )
+}
+
+pub fn validate_register_request(req: &RegisterNodeRequest) -> Result<()> {
+    Validator::new()
+        .check(
+            !req.hostname.trim().is_empty(),
+            "hostname",
+            "must be non-empty",
+        )
+        .check(
+            req.host_ip.parse::<std::net::Ipv4Addr>().is_ok(),
+            "host_ip",
+            "must be an IPv4 address",
+        )
+        .finish()
+}
+
+pub async fn register_node(
+    db: &DbPool,
+    ipam: &SubnetAllocator,
+    req: RegisterNodeRequest,
+) -> Result<RegisterNodeResponse> {
+    validate_register_request(&req)?;
+    let ipam = ipam.clone();
+    let resp = execute_async(db, move |conn| register_node_tx(conn, &ipam, &req)).await?;
+    info!(
+        "Registered node {} with subnet {}",
+        resp.node_id, resp.subnet
+    );
+    Ok(resp)
+}
+
+/// Record a heartbeat, bringing a suspect or down node back up. Returns
+/// `false` if the node is unknown.
+pub async fn heartbeat(db: &DbPool, ipam: &SubnetAllocator, node_id: &str) -> Result<bool> {
+    let now = now_unix_seconds();
+    let ipam = ipam.clone();
+    let node_id = node_id.to_string();
+    execute_async(db, move |conn| record_heartbeat(conn, &ipam, &node_id, now)).await
+}
+
+/// Mark a node down immediately (graceful agent shutdown) and start the
+/// quarantine clock on its subnet. Returns `false` if the node is unknown.
+pub async fn deregister(db: &DbPool, ipam: &SubnetAllocator, node_id: &str) -> Result<bool> {
+    let now = now_unix_seconds();
+    let ipam = ipam.clone();
+    let node_id = node_id.to_string();
+    execute_async(db, move |conn| mark_node_down(conn, &ipam, &node_id, now)).await
+}
+
+pub async fn get_node(db: &DbPool, node_id: &str) -> Result<Option<Node>> {
+    let node_id = node_id.to_string();
+    execute_async(db, move |conn| {
+        let node = conn
+            .query_row(
+                &format!("SELECT {NODE_COLUMNS} FROM nodes WHERE node_id = ?1"),
+                params![node_id],
+                row_to_node,
+            )
+            .optional()?;
+        Ok(node)
+    })
+    .await
+}
+
+pub async fn list_nodes(db: &DbPool) -> Result<Vec<Node>> {
+    execute_async(db, list_nodes_tx).await
+}
+
+/// Status transitions with `seq > after_seq`, oldest first, so callers can
+/// tail the log by passing the last `seq` they saw.
+pub async fn list_transitions(
+    db: &DbPool,
+    node_id: Option<&str>,
+    after_seq: i64,
+    limit: usize,
+) -> Result<Vec<NodeTransition>> {
+    let node_id = node_id.map(ToString::to_string);
+    execute_async(db, move |conn| {
+        list_transitions_tx(conn, node_id.as_deref(), after_seq, limit)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::error::ServiceError;
+
+    fn ipam() -> SubnetAllocator {
+        SubnetAllocator::new("10.42.0.0/16", 24, 600).expect("allocator")
+    }
+
+    fn request(hostname: &str, host_ip: &str) -> RegisterNodeRequest {
+        RegisterNodeRequest {
+            hostname: hostname.to_string(),
+            host_ip: host_ip.to_string(),
+            cpu_cores: Some(4),
+            ram_mb: Some(8192),
+        }
+    }
+
+    #[test]
+    fn registration_rejects_bad_fields() {
+        assert!(validate_register_request(&request("node-1", "192.168.1.10")).is_ok());
+        let err = validate_register_request(&request(" ", "not-an-ip")).expect_err("invalid");
+        let Some(ServiceError::Validation(fields)) = err.downcast_ref::<ServiceError>() else {
+            panic!("expected validation error, got {err}");
+        };
+        let names: Vec<&str> = fields.iter().map(|f| f.field.as_str()).collect();
+        assert_eq!(names, ["hostname", "host_ip"]);
+    }
+
+    #[test]
+    fn registration_hands_out_sequential_unique_subnets() {
+        let conn = crate::db::test_conn();
+        let ipam = ipam();
+        let a =
+            register_node_tx(&conn, &ipam, &request("node-a", "192.168.1.10")).expect("register a");
+        let b =
+            register_node_tx(&conn, &ipam, &request("node-b", "192.168.1.11")).expect("register b");
+        assert_eq!(a.subnet, "10.42.1.0/24");
+        assert_eq!(b.subnet, "10.42.2.0/24");
+        assert_ne!(a.node_id, b.node_id);
+
+        let nodes = list_nodes_tx(&conn).expect("list");
+        assert_eq!(nodes.len(), 2);
+        assert!(nodes.iter().all(|n| n.status == NODE_STATUS_UP));
+        assert_eq!(nodes[0].ram_mb, Some(8192));
+    }
+
+    #[test]
+    fn re_registration_after_deregister_keeps_node_and_subnet() {
+        let conn = crate::db::test_conn();
+        let ipam = ipam();
+        let a =
+            register_node_tx(&conn, &ipam, &request("node-a", "192.168.1.10")).expect("register");
+        assert!(mark_node_down(&conn, &ipam, &a.node_id, 10).expect("mark down"));
+        assert!(!mark_node_down(&conn, &ipam, "missing", 10).expect("mark down"));
+        assert_eq!(
+            list_nodes_tx(&conn).expect("list")[0].status,
+            NODE_STATUS_DOWN
+        );
+
+        let again = register_node_tx(&conn, &ipam, &request("node-a", "192.168.1.10"))
+            .expect("re-register");
+        assert_eq!(again.node_id, a.node_id);
+        assert_eq!(again.subnet, a.subnet);
+
+        let nodes = list_nodes_tx(&conn).expect("list");
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(nodes[0].status, NODE_STATUS_UP);
+        assert!(record_heartbeat(&conn, &ipam, &a.node_id, 20).expect("heartbeat"));
+        assert!(!record_heartbeat(&conn, &ipam, "missing", 20).expect("heartbeat"));
+
+        let transitions = list_transitions_tx(&conn, Some(&a.node_id), 0, 10).expect("list");
+        let steps: Vec<_> = transitions
+            .iter()
+            .map(|t| {
+                (
+                    t.from_status.as_deref(),
+                    t.to_status.as_str(),
+                    t.reason.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            steps,
+            vec![
+                (None, NODE_STATUS_UP, TRANSITION_REGISTERED),
+                (
+                    Some(NODE_STATUS_UP),
+                    NODE_STATUS_DOWN,
+                    TRANSITION_DEREGISTERED
+                ),
+                (
+                    Some(NODE_STATUS_DOWN),
+                    NODE_STATUS_UP,
+                    TRANSITION_REGISTERED
+                ),
+            ]
+        );
+        assert_eq!(nodes[0].status_changed_at, transitions[2].transitioned_at);
+    }
+
+    #[test]
+    fn reclaimed_subnet_evicts_stale_node() {
+        let conn = crate::db::test_conn();
+        let ipam = SubnetAllocator::new("10.42.0.0/23", 24, 0).expect("allocator");
+        let a =
+            register_node_tx(&conn, &ipam, &request("node-a", "192.168.1.10")).expect("register a");
+        assert!(mark_node_down(&conn, &ipam, &a.node_id, 0).expect("mark down"));
+
+        let b =
+            register_node_tx(&conn, &ipam, &request("node-b", "192.168.1.11")).expect("register b");
+        assert_eq!(b.subnet, a.subnet);
+        let nodes = list_nodes_tx(&conn).expect("list");
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(nodes[0].node_id, b.node_id);
+    }
+
+    #[test]
+    fn heartbeat_after_subnet_reclaimed_moves_node_to_a_fresh_subnet() {
+        let conn = crate::db::test_conn();
+        let ipam = SubnetAllocator::new("10.42.0.0/22", 24, 0).expect("allocator");
+        let a =
+            register_node_tx(&conn, &ipam, &request("node-a", "192.168.1.10")).expect("register a");
+        assert!(mark_node_down(&conn, &ipam, &a.node_id, 0).expect("mark down"));
+        // Its subnet leaves quarantine and goes to another owner while the
+        // node row is still around.
+        let taken = ipam.allocate(&conn, "other", 5).expect("allocate");
+        assert_eq!(taken.subnet, a.subnet);
+
+        assert!(record_heartbeat(&conn, &ipam, &a.node_id, 10).expect("heartbeat"));
+        let nodes = list_nodes_tx(&conn).expect("list");
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(nodes[0].status, NODE_STATUS_UP);
+        assert_ne!(nodes[0].subnet, a.subnet);
+        let owner: String = conn
+            .query_row(
+                "SELECT node_id FROM subnet_allocation WHERE subnet = ?1 AND released_at IS NULL",
+                params![nodes[0].subnet],
+                |row| row.get(0),
+            )
+            .expect("allocation");
+        assert_eq!(owner, a.node_id);
+    }
+
+    #[test]
+    fn stale_transition_loses_to_a_newer_heartbeat() {
+        let conn = crate::db::test_conn();
+        let ipam = ipam();
+        let a =
+            register_node_tx(&conn, &ipam, &request("node-a", "192.168.1.10")).expect("register");
+        let read_at: i64 = conn
+            .query_row(
+                "SELECT last_heartbeat FROM nodes WHERE node_id = ?1",
+                params![a.node_id],
+                |row| row.get(0),
+            )
+            .expect("heartbeat");
+        // The node heartbeats after a sweep read it as stale.
+        assert!(record_heartbeat(&conn, &ipam, &a.node_id, read_at + 60).expect("heartbeat"));
+
+        let swept = transition_node(
+            &conn,
+            &ipam,
+            &a.node_id,
+            NODE_STATUS_UP,
+            NODE_STATUS_DOWN,
+            TRANSITION_HEARTBEAT_EXPIRED,
+            Some(read_at),
+            read_at + 61,
+        )
+        .expect("transition");
+        assert!(swept.is_none());
+        // Nor does a transition from a status the node already left apply.
+        let moved = transition_node(
+            &conn,
+            &ipam,
+            &a.node_id,
+            NODE_STATUS_SUSPECT,
+            NODE_STATUS_DOWN,
+            TRANSITION_HEARTBEAT_EXPIRED,
+            None,
+            read_at + 61,
+        )
+        .expect("transition");
+        assert!(moved.is_none());
+
+        assert_eq!(
+            list_nodes_tx(&conn).expect("list")[0].status,
+            NODE_STATUS_UP
+        );
+        let released: Option<i64> = conn
+            .query_row(
+                "SELECT released_at FROM subnet_allocation WHERE node_id = ?1",
+                params![a.node_id],
+                |row| row.get(0),
+            )
+            .expect("allocation");
+        assert_eq!(released, None);
+        assert_eq!(
+            list_transitions_tx(&conn, Some(&a.node_id), 0, 10)
+                .expect("transitions")
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[ignore = "needs PostgreSQL: set QUILT_TEST_POSTGRES_URL and run with --ignored"]
+    async fn postgres_concurrent_registrations_get_distinct_subnets() {
+        let db = tokio::task::spawn_blocking(|| crate::db::postgres_test_pool("node_registry"))
+            .await
+            .expect("join");
+        let ipam = ipam();
+        let registrations = futures::future::join_all((0..8).map(|i| {
+            let (db, ipam) = (db.clone(), ipam.clone());
+            async move {
+                let req = request(&format!("node-{i}"), &format!("192.168.1.{}", 10 + i));
+                register_node(&db, &ipam, req).await
+            }
+        }))
+        .await;
+        let mut subnets: Vec<_> = registrations
+            .into_iter()
+            .map(|r| r.expect("register").subnet)
+            .collect();
+        subnets.sort();
+        subnets.dedup();
+        assert_eq!(subnets.len(), 8);
+
+        let nodes = list_nodes(&db).await.expect("list");
+        assert!(deregister(&db, &ipam, &nodes[0].node_id)
+            .await
+            .expect("deregister"));
+        let own = list_transitions(&db, Some(&nodes[0].node_id), 0, 10)
+            .await
+            .expect("transitions");
+        assert_eq!(own.len(), 2);
+        assert!(own[1].seq > own[0].seq);
+        let all = list_transitions(&db, None, 0, 100)
+            .await
+            .expect("transitions");
+        assert_eq!(all.len(), 9);
+    }
+}
@@ -0,0 +1,334 @@
+//! Append-only observation history and the windowed aggregation the loops
+//! read, so a single noisy sample can't drive a scaling decision.
+//!
+//! Ingest still upserts the latest-sample tables; those are what operators
+//! see and what a policy delete cascades through. The loops only look at
+//! samples inside the configured window.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::db::{params, Connection};
+use crate::types::{NodeGroupObservation, WorkloadObservation};
+
+/// How samples inside the window collapse into one observation. Applied to
+/// every numeric field independently.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum Aggregation {
+    /// Exponentially weighted moving average, oldest sample first. `alpha`
+    /// is the weight of each newer sample, in `(0, 1]`.
+    Ewma {
+        alpha: f64,
+    },
+    Max,
+    /// Nearest-rank 95th percentile.
+    P95,
+}
+
+impl Aggregation {
+    /// `samples` must be non-empty and ordered oldest first.
+    fn apply(&self, samples: &[f64]) -> f64 {
+        match self {
+            Aggregation::Ewma { alpha } => samples[1..]
+                .iter()
+                .fold(samples[0], |acc, x| alpha * x + (1.0 - alpha) * acc),
+            Aggregation::Max => samples.iter().copied().fold(f64::MIN, f64::max),
+            Aggregation::P95 => {
+                let mut sorted = samples.to_vec();
+                sorted.sort_by(f64::total_cmp);
+                let rank = (sorted.len() as f64 * 0.95).ceil() as usize;
+                sorted[rank.max(1) - 1]
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ObservationConfig {
+    pub aggregation: Aggregation,
+    /// Only samples this recent are aggregated.
+    pub window_seconds: u64,
+    /// A workload or node group whose newest sample is older than this is
+    /// left out of the loops entirely.
+    pub stale_after_seconds: u64,
+    /// Samples older than this are pruned by the slow loop.
+    pub retention_seconds: u64,
+}
+
+impl Default for ObservationConfig {
+    fn default() -> Self {
+        Self {
+            aggregation: Aggregation::Ewma { alpha: 0.3 },
+            window_seconds: 120,
+            stale_after_seconds: 60,
+            retention_seconds: 3600,
+        }
+    }
+}
+
+pub fn record_samples_tx(
+    conn: &Connection,
+    workloads: &[WorkloadObservation],
+    node_groups: &[NodeGroupObservation],
+    now: i64,
+) -> Result<()> {
+    for w in workloads {
+        conn.execute(
+            "INSERT INTO orchestrator_workload_observation_sample
+             (tenant_id, workload_id, node_group, queue_depth, cpu_pressure, mem_pressure, io_pressure, cold_start_pct, invoke_p95_ms, reject_pct, active_compute_units, cost_per_compute_unit, observed_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            params![
+                w.tenant_id,
+                w.workload_id,
+                w.node_group,
+                w.queue_depth,
+                w.cpu_pressure,
+                w.mem_pressure,
+                w.io_pressure,
+                w.cold_start_pct,
+                w.invoke_p95_ms,
+                w.reject_pct,
+                w.active_compute_units,
+                w.cost_per_compute_unit,
+                now
+            ],
+        )?;
+    }
+    for n in node_groups {
+        conn.execute(
+            "INSERT INTO orchestrator_node_group_observation_sample
+             (node_group, cpu_pressure, mem_pressure, io_pressure, warm_ready, warm_hit_rate, capacity_units, used_units, observed_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                n.node_group,
+                n.cpu_pressure,
+                n.mem_pressure,
+                n.io_pressure,
+                n.warm_ready,
+                n.warm_hit_rate,
+                n.capacity_units,
+                n.used_units,
+                now
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+/// Split rows ordered by key then age into per-key runs, dropping keys
+/// whose newest sample is stale.
+fn fresh_runs<T, K: PartialEq>(
+    rows: Vec<(T, i64)>,
+    key: impl Fn(&T) -> K,
+    stale_before: i64,
+) -> Vec<Vec<T>> {
+    let mut runs: Vec<(Vec<T>, i64)> = Vec::new();
+    for (sample, observed_at) in rows {
+        match runs.last_mut() {
+            Some((run, newest)) if key(&run[0]) == key(&sample) => {
+                run.push(sample);
+                *newest = observed_at;
+            }
+            _ => runs.push((vec![sample], observed_at)),
+        }
+    }
+    runs.into_iter()
+        .filter(|(_, newest)| *newest >= stale_before)
+        .map(|(run, _)| run)
+        .collect()
+}
+
+/// One aggregated observation per workload with a fresh sample in the window.
+pub fn workload_observations_tx(
+    conn: &Connection,
+    cfg: &ObservationConfig,
+    now: i64,
+) -> Result<Vec<WorkloadObservation>> {
+    let rows = conn.query_map(
+        "SELECT tenant_id, workload_id, node_group, queue_depth, cpu_pressure, mem_pressure, io_pressure, cold_start_pct, invoke_p95_ms, reject_pct, active_compute_units, cost_per_compute_unit, observed_at
+         FROM orchestrator_workload_observation_sample
+         WHERE observed_at >= ?1
+         ORDER BY tenant_id, workload_id, sample_id",
+        params![now - cfg.window_seconds as i64],
+        |row| {
+            Ok((
+                WorkloadObservation {
+                    tenant_id: row.get(0)?,
+                    workload_id: row.get(1)?,
+                    node_group: row.get(2)?,
+                    queue_depth: row.get(3)?,
+                    cpu_pressure: row.get(4)?,
+                    mem_pressure: row.get(5)?,
+                    io_pressure: row.get(6)?,
+                    cold_start_pct: row.get(7)?,
+                    invoke_p95_ms: row.get(8)?,
+                    reject_pct: row.get(9)?,
+                    active_compute_units: row.get(10)?,
+                    cost_per_compute_unit: row.get(11)?,
+                },
+                row.get::<i64>(12)?,
+            ))
+        },
+    )?;
+    let stale_before = now - cfg.stale_after_seconds as i64;
+    Ok(fresh_runs(
+        rows,
+        |w| (w.tenant_id.clone(), w.workload_id.clone()),
+        stale_before,
+    )
+    .into_iter()
+    .map(|run| aggregate_workload(cfg.aggregation, run))
+    .collect())
+}
+
+fn aggregate_workload(agg: Aggregation, mut run: Vec<WorkloadObservation>) -> WorkloadObservation {
+    let field =
+        |get: fn(&WorkloadObservation) -> f64| agg.apply(&run.iter().map(get).collect::<Vec<_>>());
+    let queue_depth = field(|w| w.queue_depth as f64).round() as u32;
+    let cpu_pressure = field(|w| w.cpu_pressure);
+    let mem_pressure = field(|w| w.mem_pressure);
+    let io_pressure = field(|w| w.io_pressure);
+    let cold_start_pct = field(|w| w.cold_start_pct);
+    let invoke_p95_ms = field(|w| w.invoke_p95_ms as f64).round() as u32;
+    let reject_pct = field(|w| w.reject_pct);
+    let active_compute_units = field(|w| w.active_compute_units as f64).round() as u32;
+    let cost_per_compute_unit = field(|w| w.cost_per_compute_unit);
+    // Identity and placement come from the newest sample.
+    let newest = run.pop().expect("runs are non-empty");
+    WorkloadObservation {
+        queue_depth,
+        cpu_pressure,
+        mem_pressure,
+        io_pressure,
+        cold_start_pct,
+        invoke_p95_ms,
+        reject_pct,
+        active_compute_units,
+        cost_per_compute_unit,
+        ..newest
+    }
+}
+
+/// One aggregated observation per node group with a fresh sample in the window.
+pub fn node_group_observations_tx(
+    conn: &Connection,
+    cfg: &ObservationConfig,
+    now: i64,
+) -> Result<Vec<NodeGroupObservation>> {
+    let rows = conn.query_map(
+        "SELECT node_group, cpu_pressure, mem_pressure, io_pressure, warm_ready, warm_hit_rate, capacity_units, used_units, observed_at
+         FROM orchestrator_node_group_observation_sample
+         WHERE observed_at >= ?1
+         ORDER BY node_group, sample_id",
+        params![now - cfg.window_seconds as i64],
+        |row| {
+            Ok((
+                NodeGroupObservation {
+                    node_group: row.get(0)?,
+                    cpu_pressure: row.get(1)?,
+                    mem_pressure: row.get(2)?,
+                    io_pressure: row.get(3)?,
+                    warm_ready: row.get(4)?,
+                    warm_hit_rate: row.get(5)?,
+                    capacity_units: row.get(6)?,
+                    used_units: row.get(7)?,
+                },
+                row.get::<i64>(8)?,
+            ))
+        },
+    )?;
+    let stale_before = now - cfg.stale_after_seconds as i64;
+    Ok(fresh_runs(rows, |n| n.node_group.clone(), stale_before)
+        .into_iter()
+        .map(|run| aggregate_node_group(cfg.aggregation, run))
+        .collect())
+}
+
+fn aggregate_node_group(agg: Aggregation, run: Vec<NodeGroupObservation>) -> NodeGroupObservation {
+    let field =
+        |get: fn(&NodeGroupObservation) -> f64| agg.apply(&run.iter().map(get).collect::<Vec<_>>());
+    NodeGroupObservation {
+        cpu_pressure: field(|n| n.cpu_pressure),
+        mem_pressure: field(|n| n.mem_pressure),
+        io_pressure: field(|n| n.io_pressure),
+        warm_ready: field(|n| n.warm_ready as f64).round() as u32,
+        warm_hit_rate: field(|n| n.warm_hit_rate),
+        capacity_units: field(|n| n.capacity_units as f64).round() as u32,
+        used_units: field(|n| n.used_units as f64).round() as u32,
+        node_group: run[0].node_group.clone(),
+    }
+}
+
+pub fn delete_workload_samples_tx(
+    conn: &Connection,
+    tenant_id: &str,
+    workload_id: &str,
+) -> Result<usize> {
+    conn.execute(
+        "DELETE FROM orchestrator_workload_observation_sample WHERE tenant_id = ?1 AND workload_id = ?2",
+        params![tenant_id, workload_id],
+    )
+}
+
+pub fn prune_samples_tx(conn: &Connection, cfg: &ObservationConfig, now: i64) -> Result<usize> {
+    let cutoff = now - cfg.retention_seconds as i64;
+    let workloads = conn.execute(
+        "DELETE FROM orchestrator_workload_observation_sample WHERE observed_at < ?1",
+        params![cutoff],
+    )?;
+    let node_groups = conn.execute(
+        "DELETE FROM orchestrator_node_group_observation_sample WHERE observed_at < ?1",
+        params![cutoff],
+    )?;
+    Ok(workloads + node_groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(workload_id: &str, queue_depth: u32) -> WorkloadObservation {
+        WorkloadObservation {
+            workload_id: workload_id.to_string(),
+            queue_depth,
+            active_compute_units: 2,
+            ..WorkloadObservation::fixture()
+        }
+    }
+
+    #[test]
+    fn aggregations_over_samples() {
+        let samples = [10.0, 0.0, 0.0, 0.0];
+        let ewma = Aggregation::Ewma { alpha: 0.5 }.apply(&samples);
+        assert!((ewma - 1.25).abs() < 1e-9);
+        assert_eq!(Aggregation::Max.apply(&samples), 10.0);
+        let hundred: Vec<f64> = (1..=100).map(f64::from).collect();
+        assert_eq!(Aggregation::P95.apply(&hundred), 95.0);
+        assert_eq!(Aggregation::P95.apply(&[7.0]), 7.0);
+    }
+
+    #[test]
+    fn one_spike_is_smoothed_and_stale_workloads_dropped() {
+        let conn = crate::db::test_conn();
+        let cfg = ObservationConfig {
+            aggregation: Aggregation::Ewma { alpha: 0.3 },
+            window_seconds: 60,
+            stale_after_seconds: 30,
+            retention_seconds: 600,
+        };
+        for (at, depth) in [(900, 500), (960, 0), (970, 0), (980, 200)] {
+            record_samples_tx(&conn, &[sample("w", depth)], &[], at).expect("sample");
+        }
+        record_samples_tx(&conn, &[sample("quiet", 1)], &[], 950).expect("sample");
+
+        // The 900 sample is outside the window; "quiet" is outside the TTL.
+        let observed = workload_observations_tx(&conn, &cfg, 1000).expect("aggregate");
+        assert_eq!(observed.len(), 1);
+        assert_eq!(observed[0].workload_id, "w");
+        assert_eq!(observed[0].queue_depth, 60);
+
+        assert_eq!(prune_samples_tx(&conn, &cfg, 1560).expect("prune"), 2);
+    }
+}
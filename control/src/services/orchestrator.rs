@@ -1,43 +1,57 @@
 use anyhow::{Context, Result};
-use reqwest::{Client, StatusCode};
-use rusqlite::{params, Connection, OptionalExtension};
 use serde_json::{json, Map, Value};
 use sha2::{Digest, Sha256};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tracing::info;
 use uuid::Uuid;
 
-use crate::db::{execute_async, DbPool};
+use crate::db::{self, execute_async, params, Connection, DbPool, OptionalExtension, Row};
+use crate::metrics;
+use crate::services::circuit_breaker::{CircuitBreaker, Gate};
+use crate::services::decisions;
+use crate::services::dispatcher::{DispatchLimits, Dispatcher, SlotKey};
+use crate::services::error::{ServiceError, Validator};
+use crate::services::events::{self, NewEvent};
+use crate::services::executor::{ActionPayload, ActionRequest, ExecutorRegistry};
+use crate::services::leader_election::LeaderElection;
+use crate::services::observations;
+use crate::services::orchestrator_config::{self, ConfigHandle, OrchestratorConfig, Tuning};
+use crate::services::retry::{self, RetryDecision, RetryPolicy, DEAD_LETTER};
+use crate::services::scaling::{self, Recommendation, StrategyState};
+use crate::services::warm_pool::{self, WarmPoolState};
 use crate::types::{
-    NodeGroupObservation, ObservationIngestRequest, OrchestratorAction, OrchestratorIntent,
-    WorkloadObservation, WorkloadPolicyRequest, WorkloadSloRequest,
+    ActionAttempt, AdaptiveWarmPool, DeadLetterAction, DecisionClamp, DecisionCondition,
+    DecisionTrace, NodeGroupObservation, ObservationIngestRequest, OrchestratorAction,
+    OrchestratorIntent, ScalingStrategy, SimulationRequest, SimulationResponse,
+    WorkloadObservation, WorkloadPolicy, WorkloadPolicyRequest, WorkloadSlo, WorkloadSloRequest,
 };
 
-const FAST_LOOP_SECONDS: u64 = 5;
-const SLOW_LOOP_SECONDS: u64 = 120;
-const DISPATCH_LOOP_SECONDS: u64 = 2;
-const MAX_DISPATCH_BATCH: usize = 100;
 const PLATFORM_TENANT_ID: &str = "platform";
 
 const RUNTIME_NON_TERMINAL: [&str; 3] = ["accepted", "queued", "running"];
-const RUNTIME_SUCCESS: &str = "succeeded";
+pub(crate) const RUNTIME_SUCCESS: &str = "succeeded";
 const RUNTIME_FAILURES: [&str; 3] = ["failed", "cancelled", "timed_out"];
 
+/// Highest accepted `WorkloadPolicyRequest.priority`; higher is more important.
+pub const MAX_POLICY_PRIORITY: u8 = 10;
+
 const RC_TARGET_NOT_FOUND: &str = "ELASTICITY_TARGET_NOT_FOUND";
-const RC_INVALID_ARGUMENT: &str = "ELASTICITY_INVALID_ARGUMENT";
+pub(crate) const RC_INVALID_ARGUMENT: &str = "ELASTICITY_INVALID_ARGUMENT";
 const RC_RESOURCE_PRESSURE: &str = "ELASTICITY_RESOURCE_PRESSURE";
 const RC_OPERATION_FAILED: &str = "ELASTICITY_OPERATION_FAILED";
-const RC_IDEMPOTENCY_REPLAY: &str = "ELASTICITY_IDEMPOTENCY_REPLAY";
+pub(crate) const RC_IDEMPOTENCY_REPLAY: &str = "ELASTICITY_IDEMPOTENCY_REPLAY";
 const RC_ORCH_TTL_EXPIRED: &str = "ORCH_TTL_EXPIRED";
 const RC_ORCH_ROLLBACK_TRIGGERED: &str = "ORCH_ROLLBACK_TRIGGERED";
-const RC_ORCH_ACTION_UNSUPPORTED: &str = "ORCH_ACTION_UNSUPPORTED";
-const RC_ORCH_DEPENDENCY_UNAVAILABLE: &str = "ORCH_DEPENDENCY_UNAVAILABLE";
-
-#[derive(Debug, Clone)]
-pub struct ExecutionConfig {
-    pub control_base_url: Option<String>,
-    pub control_api_key: Option<String>,
-}
+pub(crate) const RC_ORCH_ACTION_UNSUPPORTED: &str = "ORCH_ACTION_UNSUPPORTED";
+pub(crate) const RC_ORCH_DEPENDENCY_UNAVAILABLE: &str = "ORCH_DEPENDENCY_UNAVAILABLE";
+const RC_ORCH_WORKLOAD_DELETED: &str = "ORCH_WORKLOAD_DELETED";
+const RC_ORCH_CANCELLED: &str = "ORCH_CANCELLED";
+const RC_ORCH_REQUEUED: &str = "ORCH_REQUEUED";
+const RC_ORCH_SUPERSEDED: &str = "ORCH_SUPERSEDED";
+const RC_ORCH_RETRIES_EXHAUSTED: &str = "ORCH_RETRIES_EXHAUSTED";
+const RC_ORCH_REPLAYED: &str = "ORCH_REPLAYED";
+const RC_ORCH_PRIORITY_PREEMPTION: &str = "PRIORITY_PREEMPTION";
 
 #[derive(Debug, Clone)]
 struct WorkloadPolicyRow {
@@ -46,12 +60,16 @@ struct WorkloadPolicyRow {
     hard_quota: u32,
     soft_burst: u32,
     absolute_limit: u32,
+    priority: u8,
     cooldown_seconds: u32,
     hysteresis_pct: f64,
     burst_cpu_cap: f64,
     burst_mem_mb: u32,
     burst_ttl_seconds: u32,
     target_container_ids: Vec<String>,
+    scale_to_zero_idle_seconds: Option<u32>,
+    scaling_strategy: ScalingStrategy,
+    warm_pool: Option<AdaptiveWarmPool>,
 }
 
 #[derive(Debug, Clone)]
@@ -79,16 +97,45 @@ struct DispatchAction {
     created_at: i64,
 }
 
-#[derive(Debug, Clone)]
-struct RuntimeOperation {
-    operation_id: String,
-    operation_type: Option<String>,
-    status: String,
-    reason_code: Option<String>,
-    reason_message: Option<String>,
+impl DispatchAction {
+    fn ttl_expired(&self, now: i64) -> bool {
+        now > self.effective_at + self.ttl_seconds as i64
+    }
+
+    /// What the action changes outside the control plane, for per-target
+    /// dispatch limits. Falls back to the workload.
+    fn target(&self) -> String {
+        let field = |name: &str| self.payload_json.get(name).and_then(Value::as_str);
+        match self.action_type.as_str() {
+            "SetBurstPolicy" => field("container_id").map(|c| format!("container/{c}")),
+            "ScaleNodeGroupUp" | "ScaleNodeGroupDown" => {
+                field("node_group").map(|g| format!("node_group/{g}"))
+            }
+            _ => None,
+        }
+        .unwrap_or_else(|| format!("workload/{}/{}", self.tenant_id, self.workload_id))
+    }
+
+    fn slot_key(&self) -> SlotKey {
+        SlotKey {
+            action_id: self.action_id.clone(),
+            tenant_id: self.tenant_id.clone(),
+            target: self.target(),
+        }
+    }
+
+    fn request<'a>(&'a self, payload: &'a ActionPayload) -> ActionRequest<'a> {
+        ActionRequest {
+            action_id: &self.action_id,
+            tenant_id: &self.tenant_id,
+            workload_id: &self.workload_id,
+            idempotency_key: &self.idempotency_key,
+            payload,
+        }
+    }
 }
 
-fn now_unix_seconds() -> i64 {
+pub(crate) fn now_unix_seconds() -> i64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .expect("clock should be monotonic enough")
@@ -144,6 +191,15 @@ fn deterministic_idempotency_key(
     hex::encode(hasher.finalize())
 }
 
+/// Key for another run of an action that was keyed `previous`. Chaining
+/// keeps it deterministic per reopening while making sure the elasticity
+/// service starts a new operation instead of replaying the one that failed.
+fn reopened_idempotency_key(previous: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(format!("{}|reopened", previous).as_bytes());
+    hex::encode(hasher.finalize())
+}
+
 fn is_runtime_non_terminal(status: &str) -> bool {
     RUNTIME_NON_TERMINAL.contains(&status)
 }
@@ -156,7 +212,23 @@ fn is_runtime_terminal_failure(status: &str) -> bool {
     RUNTIME_FAILURES.contains(&status)
 }
 
-fn normalize_reason_code(code: Option<&str>) -> String {
+/// Statuses an executor may report for an action in each dispatched status,
+/// besides finishing it. Reports on pending, dead-lettered or finished
+/// actions are never valid.
+const RESULT_TRANSITIONS: [(&str, &[&str]); 3] = [
+    ("accepted", &["accepted", "queued", "running"]),
+    ("queued", &["queued", "running"]),
+    ("running", &["running"]),
+];
+
+fn result_transition_allowed(from: &str, to: &str) -> bool {
+    let finishing = is_runtime_terminal_success(to) || is_runtime_terminal_failure(to);
+    RESULT_TRANSITIONS
+        .iter()
+        .any(|(status, next)| *status == from && (finishing || next.contains(&to)))
+}
+
+pub(crate) fn normalize_reason_code(code: Option<&str>) -> String {
     match code {
         Some(RC_TARGET_NOT_FOUND) => RC_TARGET_NOT_FOUND.to_string(),
         Some(RC_INVALID_ARGUMENT) => RC_INVALID_ARGUMENT.to_string(),
@@ -206,94 +278,461 @@ fn parse_dispatch_error(message: &str) -> (String, Option<bool>, String) {
     }
 }
 
+fn is_pct(v: f64) -> bool {
+    v.is_finite() && (0.0..=100.0).contains(&v)
+}
+
+pub fn validate_workload_policy(req: &WorkloadPolicyRequest) -> Result<()> {
+    let mut v = Validator::new();
+    scaling::check(&req.scaling_strategy, &mut v, "scaling_strategy.");
+    if let Some(pool) = &req.warm_pool {
+        warm_pool::check(pool, &mut v, "warm_pool.");
+    }
+    v.check(
+        !req.runtime_function_id.trim().is_empty(),
+        "runtime_function_id",
+        "must be non-empty",
+    )
+    .check(req.max_concurrency >= 1, "max_concurrency", "must be >= 1")
+    .check(
+        req.hard_quota >= req.max_concurrency,
+        "hard_quota",
+        format!("must be >= max_concurrency ({})", req.max_concurrency),
+    )
+    .check(
+        req.absolute_limit >= req.hard_quota,
+        "absolute_limit",
+        format!("must be >= hard_quota ({})", req.hard_quota),
+    )
+    .check(
+        req.priority <= MAX_POLICY_PRIORITY,
+        "priority",
+        format!("must be between 0 and {}", MAX_POLICY_PRIORITY),
+    )
+    .check(
+        is_pct(req.hysteresis_pct),
+        "hysteresis_pct",
+        "must be between 0 and 100",
+    )
+    .check(
+        req.burst_cpu_cap.is_finite() && req.burst_cpu_cap > 0.0,
+        "burst_cpu_cap",
+        "must be > 0",
+    )
+    .check(
+        req.burst_ttl_seconds >= 1,
+        "burst_ttl_seconds",
+        "must be >= 1",
+    )
+    .check(
+        req.target_container_ids
+            .iter()
+            .all(|id| !id.trim().is_empty()),
+        "target_container_ids",
+        "must not contain empty ids",
+    )
+    .check(
+        req.scale_to_zero_idle_seconds.is_none_or(|s| s >= 1),
+        "scale_to_zero_idle_seconds",
+        "must be >= 1",
+    )
+    .finish()
+}
+
+pub fn validate_workload_slo(req: &WorkloadSloRequest) -> Result<()> {
+    Validator::new()
+        .check(req.p95_latency_ms >= 1, "p95_latency_ms", "must be >= 1")
+        .check(
+            is_pct(req.max_cold_start_pct),
+            "max_cold_start_pct",
+            "must be between 0 and 100",
+        )
+        .check(
+            is_pct(req.max_reject_pct),
+            "max_reject_pct",
+            "must be between 0 and 100",
+        )
+        .check(
+            req.max_cost_per_compute_unit.is_finite() && req.max_cost_per_compute_unit >= 0.0,
+            "max_cost_per_compute_unit",
+            "must be >= 0",
+        )
+        .finish()
+}
+
 pub async fn upsert_workload_policy(
     db: &DbPool,
     tenant_id: &str,
     workload_id: &str,
     req: WorkloadPolicyRequest,
 ) -> Result<()> {
-    if req.runtime_function_id.trim().is_empty() {
-        anyhow::bail!("runtime_function_id must be non-empty");
-    }
+    validate_workload_policy(&req)?;
     let now = now_unix_seconds();
     let tenant_id = tenant_id.to_string();
     let workload_id = workload_id.to_string();
     execute_async(db, move |conn| {
-        conn.execute(
-            "INSERT INTO orchestrator_workload_policy
-             (tenant_id, workload_id, runtime_function_id, max_concurrency, hard_quota, soft_burst, absolute_limit, priority, cooldown_seconds, hysteresis_pct, burst_cpu_cap, burst_mem_mb, burst_ttl_seconds, target_container_ids_json, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
-             ON CONFLICT(tenant_id, workload_id) DO UPDATE SET
-               runtime_function_id=excluded.runtime_function_id,
-               max_concurrency=excluded.max_concurrency,
-               hard_quota=excluded.hard_quota,
-               soft_burst=excluded.soft_burst,
-               absolute_limit=excluded.absolute_limit,
-               priority=excluded.priority,
-               cooldown_seconds=excluded.cooldown_seconds,
-               hysteresis_pct=excluded.hysteresis_pct,
-               burst_cpu_cap=excluded.burst_cpu_cap,
-               burst_mem_mb=excluded.burst_mem_mb,
-               burst_ttl_seconds=excluded.burst_ttl_seconds,
-               target_container_ids_json=excluded.target_container_ids_json,
-               updated_at=excluded.updated_at",
-            params![
-                tenant_id,
-                workload_id,
-                req.runtime_function_id,
-                req.max_concurrency,
-                req.hard_quota,
-                req.soft_burst,
-                req.absolute_limit,
-                req.priority,
-                req.cooldown_seconds,
-                req.hysteresis_pct,
-                req.burst_cpu_cap,
-                req.burst_mem_mb,
-                req.burst_ttl_seconds,
-                serde_json::to_string(&req.target_container_ids)?,
-                now
-            ],
-        )
-        .context("Failed to upsert workload policy")?;
-        Ok(())
+        upsert_workload_policy_tx(conn, &tenant_id, &workload_id, &req, now)
     })
     .await
 }
 
+fn upsert_workload_policy_tx(
+    conn: &Connection,
+    tenant_id: &str,
+    workload_id: &str,
+    req: &WorkloadPolicyRequest,
+    now: i64,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO orchestrator_workload_policy
+         (tenant_id, workload_id, runtime_function_id, max_concurrency, hard_quota, soft_burst, absolute_limit, priority, cooldown_seconds, hysteresis_pct, burst_cpu_cap, burst_mem_mb, burst_ttl_seconds, target_container_ids_json, scale_to_zero_idle_seconds, scaling_strategy_json, warm_pool_json, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)
+         ON CONFLICT(tenant_id, workload_id) DO UPDATE SET
+           runtime_function_id=excluded.runtime_function_id,
+           max_concurrency=excluded.max_concurrency,
+           hard_quota=excluded.hard_quota,
+           soft_burst=excluded.soft_burst,
+           absolute_limit=excluded.absolute_limit,
+           priority=excluded.priority,
+           cooldown_seconds=excluded.cooldown_seconds,
+           hysteresis_pct=excluded.hysteresis_pct,
+           burst_cpu_cap=excluded.burst_cpu_cap,
+           burst_mem_mb=excluded.burst_mem_mb,
+           burst_ttl_seconds=excluded.burst_ttl_seconds,
+           target_container_ids_json=excluded.target_container_ids_json,
+           scale_to_zero_idle_seconds=excluded.scale_to_zero_idle_seconds,
+           scaling_strategy_json=excluded.scaling_strategy_json,
+           warm_pool_json=excluded.warm_pool_json,
+           updated_at=excluded.updated_at",
+        params![
+            tenant_id,
+            workload_id,
+            req.runtime_function_id,
+            req.max_concurrency,
+            req.hard_quota,
+            req.soft_burst,
+            req.absolute_limit,
+            req.priority,
+            req.cooldown_seconds,
+            req.hysteresis_pct,
+            req.burst_cpu_cap,
+            req.burst_mem_mb,
+            req.burst_ttl_seconds,
+            serde_json::to_string(&req.target_container_ids)?,
+            req.scale_to_zero_idle_seconds,
+            serde_json::to_string(&req.scaling_strategy)?,
+            req.warm_pool.as_ref().map(serde_json::to_string).transpose()?,
+            now
+        ],
+    )
+    .context("Failed to upsert workload policy")?;
+    Ok(())
+}
+
 pub async fn upsert_workload_slo(
     db: &DbPool,
     tenant_id: &str,
     workload_id: &str,
     req: WorkloadSloRequest,
 ) -> Result<()> {
+    validate_workload_slo(&req)?;
     let now = now_unix_seconds();
     let tenant_id = tenant_id.to_string();
     let workload_id = workload_id.to_string();
     execute_async(db, move |conn| {
-        conn.execute(
-            "INSERT INTO orchestrator_workload_slo
-             (tenant_id, workload_id, p95_latency_ms, max_cold_start_pct, max_reject_pct, rto_seconds, max_cost_per_compute_unit, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
-             ON CONFLICT(tenant_id, workload_id) DO UPDATE SET
-               p95_latency_ms=excluded.p95_latency_ms,
-               max_cold_start_pct=excluded.max_cold_start_pct,
-               max_reject_pct=excluded.max_reject_pct,
-               rto_seconds=excluded.rto_seconds,
-               max_cost_per_compute_unit=excluded.max_cost_per_compute_unit,
-               updated_at=excluded.updated_at",
-            params![
+        upsert_workload_slo_tx(conn, &tenant_id, &workload_id, &req, now)
+    })
+    .await
+}
+
+fn upsert_workload_slo_tx(
+    conn: &Connection,
+    tenant_id: &str,
+    workload_id: &str,
+    req: &WorkloadSloRequest,
+    now: i64,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO orchestrator_workload_slo
+         (tenant_id, workload_id, p95_latency_ms, max_cold_start_pct, max_reject_pct, rto_seconds, max_cost_per_compute_unit, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+         ON CONFLICT(tenant_id, workload_id) DO UPDATE SET
+           p95_latency_ms=excluded.p95_latency_ms,
+           max_cold_start_pct=excluded.max_cold_start_pct,
+           max_reject_pct=excluded.max_reject_pct,
+           rto_seconds=excluded.rto_seconds,
+           max_cost_per_compute_unit=excluded.max_cost_per_compute_unit,
+           updated_at=excluded.updated_at",
+        params![
+            tenant_id,
+            workload_id,
+            req.p95_latency_ms,
+            req.max_cold_start_pct,
+            req.max_reject_pct,
+            req.rto_seconds,
+            req.max_cost_per_compute_unit,
+            now
+        ],
+    )
+    .context("Failed to upsert workload SLO")?;
+    Ok(())
+}
+
+const POLICY_COLUMNS: &str = "tenant_id, workload_id, runtime_function_id, max_concurrency, hard_quota, soft_burst, absolute_limit, priority, cooldown_seconds, hysteresis_pct, burst_cpu_cap, burst_mem_mb, burst_ttl_seconds, target_container_ids_json, updated_at, scale_to_zero_idle_seconds, scaling_strategy_json, warm_pool_json";
+
+fn row_to_policy(row: &Row) -> Result<WorkloadPolicy> {
+    let targets: String = row.get(13)?;
+    Ok(WorkloadPolicy {
+        tenant_id: row.get(0)?,
+        workload_id: row.get(1)?,
+        policy: WorkloadPolicyRequest {
+            runtime_function_id: row.get(2)?,
+            max_concurrency: row.get(3)?,
+            hard_quota: row.get(4)?,
+            soft_burst: row.get(5)?,
+            absolute_limit: row.get(6)?,
+            priority: row.get(7)?,
+            cooldown_seconds: row.get(8)?,
+            hysteresis_pct: row.get(9)?,
+            burst_cpu_cap: row.get(10)?,
+            burst_mem_mb: row.get(11)?,
+            burst_ttl_seconds: row.get(12)?,
+            target_container_ids: serde_json::from_str(&targets).unwrap_or_default(),
+            scale_to_zero_idle_seconds: row.get(15)?,
+            scaling_strategy: serde_json::from_str(&row.get::<String>(16)?).unwrap_or_default(),
+            warm_pool: row
+                .get::<Option<String>>(17)?
+                .and_then(|raw| serde_json::from_str(&raw).ok()),
+        },
+        updated_at: row.get(14)?,
+    })
+}
+
+const SLO_COLUMNS: &str = "tenant_id, workload_id, p95_latency_ms, max_cold_start_pct, max_reject_pct, rto_seconds, max_cost_per_compute_unit, updated_at";
+
+fn row_to_slo(row: &Row) -> Result<WorkloadSlo> {
+    Ok(WorkloadSlo {
+        tenant_id: row.get(0)?,
+        workload_id: row.get(1)?,
+        slo: WorkloadSloRequest {
+            p95_latency_ms: row.get(2)?,
+            max_cold_start_pct: row.get(3)?,
+            max_reject_pct: row.get(4)?,
+            rto_seconds: row.get(5)?,
+            max_cost_per_compute_unit: row.get(6)?,
+        },
+        updated_at: row.get(7)?,
+    })
+}
+
+pub async fn get_workload_policy(
+    db: &DbPool,
+    tenant_id: &str,
+    workload_id: &str,
+) -> Result<Option<WorkloadPolicy>> {
+    let tenant_id = tenant_id.to_string();
+    let workload_id = workload_id.to_string();
+    execute_async(db, move |conn| {
+        conn.query_row(
+            &format!(
+                "SELECT {POLICY_COLUMNS} FROM orchestrator_workload_policy
+                 WHERE tenant_id = ?1 AND workload_id = ?2"
+            ),
+            params![tenant_id, workload_id],
+            row_to_policy,
+        )
+        .optional()
+    })
+    .await
+}
+
+pub async fn list_workload_policies(
+    db: &DbPool,
+    tenant_id: Option<&str>,
+) -> Result<Vec<WorkloadPolicy>> {
+    let tenant_id = tenant_id.map(ToString::to_string);
+    execute_async(db, move |conn| {
+        conn.query_map(
+            &format!(
+                "SELECT {POLICY_COLUMNS} FROM orchestrator_workload_policy
+                 WHERE CAST(?1 AS TEXT) IS NULL OR tenant_id = ?1
+                 ORDER BY tenant_id, workload_id"
+            ),
+            params![tenant_id],
+            row_to_policy,
+        )
+    })
+    .await
+}
+
+/// What deleting a workload policy cleaned up.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct PolicyCascade {
+    intents: usize,
+    actions_cancelled: usize,
+    observations: usize,
+}
+
+/// Delete a policy and everything the fast loop derived from it, so a
+/// removed workload stops producing intents from stale observations.
+/// Actions already handed to the runtime are left to finish.
+fn delete_workload_policy_tx(
+    conn: &Connection,
+    tenant_id: &str,
+    workload_id: &str,
+    now: i64,
+) -> Result<PolicyCascade> {
+    let deleted = conn.execute(
+        "DELETE FROM orchestrator_workload_policy WHERE tenant_id = ?1 AND workload_id = ?2",
+        params![tenant_id, workload_id],
+    )?;
+    if deleted == 0 {
+        return Err(ServiceError::NotFound(format!(
+            "No policy for workload {}/{}",
+            tenant_id, workload_id
+        ))
+        .into());
+    }
+    let intents = conn.execute(
+        "DELETE FROM orchestrator_intent WHERE tenant_id = ?1 AND workload_id = ?2",
+        params![tenant_id, workload_id],
+    )?;
+    conn.execute(
+        "DELETE FROM orchestrator_scaling_state WHERE tenant_id = ?1 AND workload_id = ?2",
+        params![tenant_id, workload_id],
+    )?;
+    let cancelled: Vec<(String, String, i64)> = conn.query_map(
+        "UPDATE orchestrator_action
+         SET status = 'cancelled', terminal_status = 'cancelled', reason_code = ?3, reason_message = ?4, terminal_at = ?5, total_latency_ms = (?5 - created_at) * 1000, updated_at = ?5
+         WHERE tenant_id = ?1 AND workload_id = ?2 AND status = 'pending'
+         RETURNING action_id, action_type, created_at",
+        params![
+            tenant_id,
+            workload_id,
+            RC_ORCH_WORKLOAD_DELETED,
+            "Workload policy deleted",
+            now
+        ],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )?;
+    for (action_id, action_type, created_at) in &cancelled {
+        let latency = (now - created_at) * 1000;
+        metrics::action_terminal(
+            action_type,
+            "cancelled",
+            Some(RC_ORCH_WORKLOAD_DELETED),
+            latency,
+        );
+        events::record_event_tx(
+            conn,
+            &NewEvent {
+                event_type: events::EVENT_ACTION_TERMINAL,
                 tenant_id,
                 workload_id,
-                req.p95_latency_ms,
-                req.max_cold_start_pct,
-                req.max_reject_pct,
-                req.rto_seconds,
-                req.max_cost_per_compute_unit,
-                now
-            ],
+                action_id: Some(action_id),
+                action_type: Some(action_type),
+                status: Some("cancelled"),
+                reason_code: Some(RC_ORCH_WORKLOAD_DELETED),
+                data: json!({ "total_latency_ms": latency }),
+            },
+            now,
+        )?;
+    }
+    if intents > 0 {
+        events::record_event_tx(
+            conn,
+            &NewEvent {
+                event_type: events::EVENT_INTENT_CLEARED,
+                tenant_id,
+                workload_id,
+                reason_code: Some(RC_ORCH_WORKLOAD_DELETED),
+                ..Default::default()
+            },
+            now,
+        )?;
+    }
+    let observations = conn.execute(
+        "DELETE FROM orchestrator_workload_observation WHERE tenant_id = ?1 AND workload_id = ?2",
+        params![tenant_id, workload_id],
+    )?;
+    observations::delete_workload_samples_tx(conn, tenant_id, workload_id)?;
+    Ok(PolicyCascade {
+        intents,
+        actions_cancelled: cancelled.len(),
+        observations,
+    })
+}
+
+pub async fn delete_workload_policy(db: &DbPool, tenant_id: &str, workload_id: &str) -> Result<()> {
+    let now = now_unix_seconds();
+    let tenant = tenant_id.to_string();
+    let workload = workload_id.to_string();
+    let cascade = execute_async(db, move |conn| {
+        let tx = events::transaction(conn)?;
+        let cascade = delete_workload_policy_tx(&tx, &tenant, &workload, now)?;
+        tx.commit()?;
+        Ok(cascade)
+    })
+    .await?;
+    info!(
+        "Deleted policy for {}/{} (intents={}, actions_cancelled={}, observations={})",
+        tenant_id, workload_id, cascade.intents, cascade.actions_cancelled, cascade.observations
+    );
+    Ok(())
+}
+
+pub async fn get_workload_slo(
+    db: &DbPool,
+    tenant_id: &str,
+    workload_id: &str,
+) -> Result<Option<WorkloadSlo>> {
+    let tenant_id = tenant_id.to_string();
+    let workload_id = workload_id.to_string();
+    execute_async(db, move |conn| {
+        conn.query_row(
+            &format!(
+                "SELECT {SLO_COLUMNS} FROM orchestrator_workload_slo
+                 WHERE tenant_id = ?1 AND workload_id = ?2"
+            ),
+            params![tenant_id, workload_id],
+            row_to_slo,
+        )
+        .optional()
+    })
+    .await
+}
+
+pub async fn list_workload_slos(db: &DbPool, tenant_id: Option<&str>) -> Result<Vec<WorkloadSlo>> {
+    let tenant_id = tenant_id.map(ToString::to_string);
+    execute_async(db, move |conn| {
+        conn.query_map(
+            &format!(
+                "SELECT {SLO_COLUMNS} FROM orchestrator_workload_slo
+                 WHERE CAST(?1 AS TEXT) IS NULL OR tenant_id = ?1
+                 ORDER BY tenant_id, workload_id"
+            ),
+            params![tenant_id],
+            row_to_slo,
         )
-        .context("Failed to upsert workload SLO")?;
+    })
+    .await
+}
+
+pub async fn delete_workload_slo(db: &DbPool, tenant_id: &str, workload_id: &str) -> Result<()> {
+    let tenant_id = tenant_id.to_string();
+    let workload_id = workload_id.to_string();
+    execute_async(db, move |conn| {
+        let deleted = conn.execute(
+            "DELETE FROM orchestrator_workload_slo WHERE tenant_id = ?1 AND workload_id = ?2",
+            params![tenant_id, workload_id],
+        )?;
+        if deleted == 0 {
+            return Err(ServiceError::NotFound(format!(
+                "No SLO for workload {}/{}",
+                tenant_id, workload_id
+            ))
+            .into());
+        }
         Ok(())
     })
     .await
@@ -301,86 +740,100 @@ pub async fn upsert_workload_slo(
 
 pub async fn ingest_observations(db: &DbPool, req: ObservationIngestRequest) -> Result<()> {
     let now = now_unix_seconds();
+    let counts = (req.workloads.len(), req.node_groups.len());
     execute_async(db, move |conn| {
-        let tx = conn.unchecked_transaction()?;
-        for w in req.workloads {
-            tx.execute(
-                "INSERT INTO orchestrator_workload_observation
-                 (tenant_id, workload_id, node_group, queue_depth, cpu_pressure, mem_pressure, io_pressure, cold_start_pct, invoke_p95_ms, reject_pct, active_compute_units, cost_per_compute_unit, updated_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
-                 ON CONFLICT(tenant_id, workload_id) DO UPDATE SET
-                   node_group=excluded.node_group,
-                   queue_depth=excluded.queue_depth,
-                   cpu_pressure=excluded.cpu_pressure,
-                   mem_pressure=excluded.mem_pressure,
-                   io_pressure=excluded.io_pressure,
-                   cold_start_pct=excluded.cold_start_pct,
-                   invoke_p95_ms=excluded.invoke_p95_ms,
-                   reject_pct=excluded.reject_pct,
-                   active_compute_units=excluded.active_compute_units,
-                   cost_per_compute_unit=excluded.cost_per_compute_unit,
-                   updated_at=excluded.updated_at",
-                params![
-                    w.tenant_id,
-                    w.workload_id,
-                    w.node_group,
-                    w.queue_depth,
-                    w.cpu_pressure,
-                    w.mem_pressure,
-                    w.io_pressure,
-                    w.cold_start_pct,
-                    w.invoke_p95_ms,
-                    w.reject_pct,
-                    w.active_compute_units,
-                    w.cost_per_compute_unit,
-                    now
-                ],
-            )?;
-        }
-        for n in req.node_groups {
-            tx.execute(
-                "INSERT INTO orchestrator_node_group_observation
-                 (node_group, cpu_pressure, mem_pressure, io_pressure, warm_ready, warm_hit_rate, capacity_units, used_units, updated_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
-                 ON CONFLICT(node_group) DO UPDATE SET
-                   cpu_pressure=excluded.cpu_pressure,
-                   mem_pressure=excluded.mem_pressure,
-                   io_pressure=excluded.io_pressure,
-                   warm_ready=excluded.warm_ready,
-                   warm_hit_rate=excluded.warm_hit_rate,
-                   capacity_units=excluded.capacity_units,
-                   used_units=excluded.used_units,
-                   updated_at=excluded.updated_at",
-                params![
-                    n.node_group,
-                    n.cpu_pressure,
-                    n.mem_pressure,
-                    n.io_pressure,
-                    n.warm_ready,
-                    n.warm_hit_rate,
-                    n.capacity_units,
-                    n.used_units,
-                    now
-                ],
-            )?;
-        }
+        let tx = conn.transaction()?;
+        ingest_observations_tx(&tx, &req.workloads, &req.node_groups, now)?;
         tx.commit()?;
         Ok(())
     })
-    .await
+    .await?;
+    metrics::observations_ingested(counts.0, counts.1);
+    Ok(())
+}
+
+fn ingest_observations_tx(
+    conn: &Connection,
+    workloads: &[WorkloadObservation],
+    node_groups: &[NodeGroupObservation],
+    now: i64,
+) -> Result<()> {
+    for w in workloads {
+        conn.execute(
+            "INSERT INTO orchestrator_workload_observation
+             (tenant_id, workload_id, node_group, queue_depth, cpu_pressure, mem_pressure, io_pressure, cold_start_pct, invoke_p95_ms, reject_pct, active_compute_units, cost_per_compute_unit, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+             ON CONFLICT(tenant_id, workload_id) DO UPDATE SET
+               node_group=excluded.node_group,
+               queue_depth=excluded.queue_depth,
+               cpu_pressure=excluded.cpu_pressure,
+               mem_pressure=excluded.mem_pressure,
+               io_pressure=excluded.io_pressure,
+               cold_start_pct=excluded.cold_start_pct,
+               invoke_p95_ms=excluded.invoke_p95_ms,
+               reject_pct=excluded.reject_pct,
+               active_compute_units=excluded.active_compute_units,
+               cost_per_compute_unit=excluded.cost_per_compute_unit,
+               updated_at=excluded.updated_at",
+            params![
+                w.tenant_id,
+                w.workload_id,
+                w.node_group,
+                w.queue_depth,
+                w.cpu_pressure,
+                w.mem_pressure,
+                w.io_pressure,
+                w.cold_start_pct,
+                w.invoke_p95_ms,
+                w.reject_pct,
+                w.active_compute_units,
+                w.cost_per_compute_unit,
+                now
+            ],
+        )?;
+    }
+    for n in node_groups {
+        conn.execute(
+            "INSERT INTO orchestrator_node_group_observation
+             (node_group, cpu_pressure, mem_pressure, io_pressure, warm_ready, warm_hit_rate, capacity_units, used_units, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+             ON CONFLICT(node_group) DO UPDATE SET
+               cpu_pressure=excluded.cpu_pressure,
+               mem_pressure=excluded.mem_pressure,
+               io_pressure=excluded.io_pressure,
+               warm_ready=excluded.warm_ready,
+               warm_hit_rate=excluded.warm_hit_rate,
+               capacity_units=excluded.capacity_units,
+               used_units=excluded.used_units,
+               updated_at=excluded.updated_at",
+            params![
+                n.node_group,
+                n.cpu_pressure,
+                n.mem_pressure,
+                n.io_pressure,
+                n.warm_ready,
+                n.warm_hit_rate,
+                n.capacity_units,
+                n.used_units,
+                now
+            ],
+        )?;
+    }
+    observations::record_samples_tx(conn, workloads, node_groups, now)
 }
 
-pub async fn list_intents(db: &DbPool) -> Result<Vec<OrchestratorIntent>> {
+pub async fn list_intents(db: &DbPool, tenant_id: Option<&str>) -> Result<Vec<OrchestratorIntent>> {
+    let tenant_id = tenant_id.map(ToString::to_string);
     execute_async(db, move |conn| {
-        let mut stmt = conn.prepare(
+        let rows = conn.query_map(
             "SELECT tenant_id, workload_id, target_concurrency, burst_cpu_cap, burst_mem_mb, burst_ttl_seconds,
                     pool_min_ready, pool_target_ready, pool_max_ready, preferred_node_group, anti_affinity,
                     reason_code, effective_at, ttl_seconds, updated_at
              FROM orchestrator_intent
+             WHERE CAST(?1 AS TEXT) IS NULL OR tenant_id = ?1
              ORDER BY tenant_id, workload_id",
-        )?;
-        let rows = stmt
-            .query_map([], |row| {
+            params![tenant_id],
+            |row| {
                 Ok(OrchestratorIntent {
                     tenant_id: row.get(0)?,
                     workload_id: row.get(1)?,
@@ -392,14 +845,14 @@ pub async fn list_intents(db: &DbPool) -> Result<Vec<OrchestratorIntent>> {
                     pool_target_ready: row.get(7)?,
                     pool_max_ready: row.get(8)?,
                     preferred_node_group: row.get(9)?,
-                    anti_affinity: row.get::<_, i64>(10)? != 0,
+                    anti_affinity: row.get::<bool>(10)?,
                     reason_code: row.get(11)?,
                     effective_at: row.get(12)?,
                     ttl_seconds: row.get(13)?,
                     updated_at: row.get(14)?,
                 })
-            })?
-            .collect::<Result<Vec<_>, _>>()?;
+            },
+        )?;
         Ok(rows)
     })
     .await
@@ -414,32 +867,90 @@ pub async fn list_actions(
     let tenant_id = tenant_id.map(ToString::to_string);
     let status = status.map(ToString::to_string);
     execute_async(db, move |conn| {
-        let sql = match (tenant_id.is_some(), status.is_some()) {
-            (true, true) => "SELECT action_id, tenant_id, workload_id, action_type, payload_json, ttl_seconds, rollback_action_json, parent_action_id, idempotency_key, decision_window_start, status, reason_code, reason_message, effective_at, outbound_requested_at, runtime_operation_id, runtime_operation_type, terminal_status, terminal_at, total_latency_ms, attempt_count, next_retry_at, created_at, updated_at FROM orchestrator_action WHERE tenant_id = ?1 AND status = ?2 ORDER BY created_at DESC LIMIT ?3",
-            (true, false) => "SELECT action_id, tenant_id, workload_id, action_type, payload_json, ttl_seconds, rollback_action_json, parent_action_id, idempotency_key, decision_window_start, status, reason_code, reason_message, effective_at, outbound_requested_at, runtime_operation_id, runtime_operation_type, terminal_status, terminal_at, total_latency_ms, attempt_count, next_retry_at, created_at, updated_at FROM orchestrator_action WHERE tenant_id = ?1 ORDER BY created_at DESC LIMIT ?2",
-            (false, true) => "SELECT action_id, tenant_id, workload_id, action_type, payload_json, ttl_seconds, rollback_action_json, parent_action_id, idempotency_key, decision_window_start, status, reason_code, reason_message, effective_at, outbound_requested_at, runtime_operation_id, runtime_operation_type, terminal_status, terminal_at, total_latency_ms, attempt_count, next_retry_at, created_at, updated_at FROM orchestrator_action WHERE status = ?1 ORDER BY created_at DESC LIMIT ?2",
-            (false, false) => "SELECT action_id, tenant_id, workload_id, action_type, payload_json, ttl_seconds, rollback_action_json, parent_action_id, idempotency_key, decision_window_start, status, reason_code, reason_message, effective_at, outbound_requested_at, runtime_operation_id, runtime_operation_type, terminal_status, terminal_at, total_latency_ms, attempt_count, next_retry_at, created_at, updated_at FROM orchestrator_action ORDER BY created_at DESC LIMIT ?1",
-        };
-        let mut stmt = conn.prepare(sql)?;
-        let rows = match (tenant_id, status) {
-            (Some(t), Some(s)) => stmt
-                .query_map(params![t, s, limit as i64], row_to_action)?
-                .collect::<Result<Vec<_>, _>>()?,
-            (Some(t), None) => stmt
-                .query_map(params![t, limit as i64], row_to_action)?
-                .collect::<Result<Vec<_>, _>>()?,
-            (None, Some(s)) => stmt
-                .query_map(params![s, limit as i64], row_to_action)?
-                .collect::<Result<Vec<_>, _>>()?,
-            (None, None) => stmt
-                .query_map(params![limit as i64], row_to_action)?
-                .collect::<Result<Vec<_>, _>>()?,
-        };
-        Ok(rows)
+        conn.query_map(
+            &format!(
+                "SELECT {ACTION_COLUMNS} FROM orchestrator_action
+                 WHERE (CAST(?1 AS TEXT) IS NULL OR tenant_id = ?1)
+                   AND (CAST(?2 AS TEXT) IS NULL OR status = ?2)
+                 ORDER BY created_at DESC
+                 LIMIT ?3"
+            ),
+            params![tenant_id, status, limit as i64],
+            row_to_action,
+        )
     })
     .await
 }
 
+/// Tenant that owns `action_id`, or `None` if the action does not exist.
+pub async fn action_tenant(db: &DbPool, action_id: &str) -> Result<Option<String>> {
+    let action_id = action_id.to_string();
+    execute_async(db, move |conn| {
+        conn.query_row(
+            "SELECT tenant_id FROM orchestrator_action WHERE action_id = ?1",
+            params![action_id],
+            |row| row.get(0),
+        )
+        .optional()
+    })
+    .await
+}
+
+/// Record an executor's report on a dispatched action. Finishing reports go
+/// through [`mark_terminal`]; see [`RESULT_TRANSITIONS`] for the rest.
+fn update_action_result_tx(
+    conn: &Connection,
+    action_id: &str,
+    status: &str,
+    reason_code: Option<&str>,
+    reason_message: Option<&str>,
+    now: i64,
+) -> Result<()> {
+    let Some(action) = load_dispatch_action(conn, action_id)? else {
+        return Err(ServiceError::NotFound(format!("Action {} not found", action_id)).into());
+    };
+    if !result_transition_allowed(&action.status, status) {
+        return Err(ServiceError::Conflict(format!(
+            "Action {} is {}; cannot report {}",
+            action_id, action.status, status
+        ))
+        .into());
+    }
+    record_action_event(
+        conn,
+        &action,
+        events::EVENT_ACTION_REPORTED,
+        Some(status),
+        reason_code,
+        json!({ "reason_message": reason_message }),
+    )?;
+    let applied = if is_runtime_non_terminal(status) {
+        conn.execute(
+            "UPDATE orchestrator_action
+             SET status = ?1, reason_code = ?2, reason_message = ?3, updated_at = ?4
+             WHERE action_id = ?5 AND status = ?6",
+            params![
+                status,
+                reason_code,
+                reason_message,
+                now,
+                action_id,
+                action.status
+            ],
+        )? > 0
+    } else {
+        mark_terminal(conn, &action, status, reason_code, reason_message)?
+    };
+    if !applied {
+        return Err(ServiceError::Conflict(format!(
+            "Action {} changed status while the result was recorded",
+            action_id
+        ))
+        .into());
+    }
+    Ok(())
+}
+
 pub async fn update_action_result(
     db: &DbPool,
     action_id: &str,
@@ -452,22 +963,331 @@ pub async fn update_action_result(
     let status = status.to_string();
     let reason_code = reason_code.map(ToString::to_string);
     let reason_message = reason_message.map(ToString::to_string);
-    execute_async(db, move |conn| {
-        let rows = conn.execute(
-            "UPDATE orchestrator_action
-             SET status = ?1, reason_code = ?2, reason_message = ?3, updated_at = ?4
-             WHERE action_id = ?5",
-            params![status, reason_code, reason_message, now, action_id],
-        )?;
-        if rows == 0 {
-            anyhow::bail!("Action not found");
+    let known = is_runtime_non_terminal(&status)
+        || is_runtime_terminal_success(&status)
+        || is_runtime_terminal_failure(&status);
+    Validator::new()
+        .check(
+            known,
+            "status",
+            format!(
+                "must be one of {}, {} or {}",
+                RUNTIME_NON_TERMINAL.join(", "),
+                RUNTIME_SUCCESS,
+                RUNTIME_FAILURES.join(", ")
+            ),
+        )
+        .finish()?;
+    events::execute_logged(db, move |conn| {
+        update_action_result_tx(
+            conn,
+            &action_id,
+            &status,
+            reason_code.as_deref(),
+            reason_message.as_deref(),
+            now,
+        )
+    })
+    .await
+}
+
+/// Cancel the pending actions matching `condition`, whose placeholders
+/// start at `?4`. Returns the ids cancelled.
+fn cancel_pending_actions_tx(
+    conn: &Connection,
+    condition: &str,
+    condition_params: &[db::Value],
+    reason_code: &str,
+    reason_message: &str,
+    data: Value,
+    now: i64,
+) -> Result<Vec<String>> {
+    let cancelled: Vec<(String, String, String, String, i64)> = conn.query_map(
+        &format!(
+            "UPDATE orchestrator_action
+             SET status = 'cancelled', terminal_status = 'cancelled', reason_code = ?1, reason_message = ?2, terminal_at = ?3, total_latency_ms = (?3 - created_at) * 1000, updated_at = ?3
+             WHERE status = 'pending' AND {condition}
+             RETURNING action_id, tenant_id, workload_id, action_type, created_at"
+        ),
+        &[params![reason_code, reason_message, now], condition_params].concat(),
+        |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+            ))
+        },
+    )?;
+    let mut ids = Vec::with_capacity(cancelled.len());
+    for (action_id, tenant_id, workload_id, action_type, created_at) in cancelled {
+        let latency = (now - created_at) * 1000;
+        metrics::action_terminal(&action_type, "cancelled", Some(reason_code), latency);
+        let mut data = data.clone();
+        if let Value::Object(map) = &mut data {
+            map.insert("total_latency_ms".to_string(), json!(latency));
+            map.insert("reason_message".to_string(), json!(reason_message));
         }
-        Ok(())
+        events::record_event_tx(
+            conn,
+            &NewEvent {
+                event_type: events::EVENT_ACTION_TERMINAL,
+                tenant_id: &tenant_id,
+                workload_id: &workload_id,
+                action_id: Some(&action_id),
+                action_type: Some(&action_type),
+                status: Some("cancelled"),
+                reason_code: Some(reason_code),
+                data,
+            },
+            now,
+        )?;
+        ids.push(action_id);
+    }
+    Ok(ids)
+}
+
+/// Cancel `action_id` if it is still pending. Returns whether it was.
+fn cancel_pending_action_tx(
+    conn: &Connection,
+    action_id: &str,
+    reason_code: &str,
+    reason_message: &str,
+    data: Value,
+    now: i64,
+) -> Result<bool> {
+    let cancelled = cancel_pending_actions_tx(
+        conn,
+        "action_id = ?4",
+        params![action_id],
+        reason_code,
+        reason_message,
+        data,
+        now,
+    )?;
+    Ok(!cancelled.is_empty())
+}
+
+fn cancel_action_tx(conn: &Connection, action_id: &str, now: i64) -> Result<OrchestratorAction> {
+    let action = get_action_tx(conn, action_id)?;
+    if !cancel_pending_action_tx(
+        conn,
+        action_id,
+        RC_ORCH_CANCELLED,
+        "Cancelled by operator",
+        json!({}),
+        now,
+    )? {
+        return Err(ServiceError::Conflict(format!(
+            "Action {} is {}; only pending actions can be cancelled",
+            action_id, action.status
+        ))
+        .into());
+    }
+    get_action_tx(conn, action_id)
+}
+
+/// Cancel an action before it reaches the runtime. Actions already
+/// dispatched have to run to completion.
+pub async fn cancel_action(db: &DbPool, action_id: &str) -> Result<OrchestratorAction> {
+    let now = now_unix_seconds();
+    let action_id = action_id.to_string();
+    execute_async(db, move |conn| {
+        let tx = events::transaction(conn)?;
+        let action = cancel_action_tx(&tx, &action_id, now)?;
+        tx.commit()?;
+        Ok(action)
+    })
+    .await
+}
+
+/// Put an action in one of the `from` statuses back in the queue as a
+/// fresh attempt under a new idempotency key. Refused when a newer action
+/// for the same target exists, since running this one would apply a stale
+/// target.
+fn reopen_action_tx(
+    conn: &Connection,
+    action_id: &str,
+    from: &[&str],
+    reason_code: &str,
+    reason_message: &str,
+    now: i64,
+) -> Result<OrchestratorAction> {
+    let action = get_action_tx(conn, action_id)?;
+    if !from.contains(&action.status.as_str()) {
+        return Err(ServiceError::Conflict(format!(
+            "Action {} is {}; expected {}",
+            action_id,
+            action.status,
+            from.join(" or ")
+        ))
+        .into());
+    }
+    let newer: Option<String> = match supersession_key(&action.action_type, &action.payload_json) {
+        Some(key) => conn
+            .query_row(
+                "SELECT action_id FROM orchestrator_action
+                 WHERE tenant_id = ?1 AND workload_id = ?2 AND action_type = ?3 AND supersession_key = ?4
+                   AND action_id <> ?5 AND created_at >= ?6
+                 LIMIT 1",
+                params![
+                    action.tenant_id,
+                    action.workload_id,
+                    action.action_type,
+                    key,
+                    action_id,
+                    action.created_at
+                ],
+                |row| row.get(0),
+            )
+            .optional()?,
+        None => None,
+    };
+    if let Some(newer) = newer {
+        return Err(ServiceError::Conflict(format!(
+            "Action {} is superseded by newer action {}",
+            action_id, newer
+        ))
+        .into());
+    }
+    let updated = conn.execute(
+        "UPDATE orchestrator_action
+         SET status = 'pending', reason_code = ?1, reason_message = ?2, effective_at = ?3, outbound_requested_at = NULL, runtime_operation_id = NULL, runtime_operation_type = NULL, terminal_status = NULL, terminal_at = NULL, total_latency_ms = NULL, attempt_count = 0, next_retry_at = ?3, updated_at = ?3, idempotency_key = ?6
+         WHERE action_id = ?4 AND status = ?5",
+        params![
+            reason_code,
+            reason_message,
+            now,
+            action_id,
+            action.status,
+            reopened_idempotency_key(&action.idempotency_key)
+        ],
+    )?;
+    if updated == 0 {
+        return Err(ServiceError::Conflict(format!(
+            "Action {} changed status concurrently",
+            action_id
+        ))
+        .into());
+    }
+    events::record_event_tx(
+        conn,
+        &NewEvent {
+            event_type: events::EVENT_ACTION_REQUEUED,
+            tenant_id: &action.tenant_id,
+            workload_id: &action.workload_id,
+            action_id: Some(action_id),
+            action_type: Some(&action.action_type),
+            status: Some("pending"),
+            reason_code: Some(reason_code),
+            data: json!({ "previous_status": action.status }),
+        },
+        now,
+    )?;
+    get_action_tx(conn, action_id)
+}
+
+fn requeue_action_tx(conn: &Connection, action_id: &str, now: i64) -> Result<OrchestratorAction> {
+    reopen_action_tx(
+        conn,
+        action_id,
+        &RUNTIME_FAILURES,
+        RC_ORCH_REQUEUED,
+        "Requeued by operator",
+        now,
+    )
+}
+
+/// Requeue a failed, cancelled or timed out action.
+pub async fn requeue_action(db: &DbPool, action_id: &str) -> Result<OrchestratorAction> {
+    let now = now_unix_seconds();
+    let action_id = action_id.to_string();
+    execute_async(db, move |conn| {
+        let tx = events::transaction(conn)?;
+        let action = requeue_action_tx(&tx, &action_id, now)?;
+        tx.commit()?;
+        Ok(action)
+    })
+    .await
+}
+
+fn replay_action_tx(conn: &Connection, action_id: &str, now: i64) -> Result<OrchestratorAction> {
+    reopen_action_tx(
+        conn,
+        action_id,
+        &[DEAD_LETTER],
+        RC_ORCH_REPLAYED,
+        "Replayed from dead letter",
+        now,
+    )
+}
+
+/// Requeue a dead-lettered action with a fresh retry budget. Its attempt
+/// history is kept.
+pub async fn replay_action(db: &DbPool, action_id: &str) -> Result<OrchestratorAction> {
+    let now = now_unix_seconds();
+    let action_id = action_id.to_string();
+    execute_async(db, move |conn| {
+        let tx = events::transaction(conn)?;
+        let action = replay_action_tx(&tx, &action_id, now)?;
+        tx.commit()?;
+        Ok(action)
+    })
+    .await
+}
+
+fn list_dead_letters_tx(
+    conn: &Connection,
+    tenant_id: Option<&str>,
+    limit: usize,
+) -> Result<Vec<DeadLetterAction>> {
+    let actions = conn.query_map(
+        &format!(
+            "SELECT {ACTION_COLUMNS} FROM orchestrator_action
+             WHERE status = ?1 AND (CAST(?2 AS TEXT) IS NULL OR tenant_id = ?2)
+             ORDER BY terminal_at DESC
+             LIMIT ?3"
+        ),
+        params![DEAD_LETTER, tenant_id, limit as i64],
+        row_to_action,
+    )?;
+    actions
+        .into_iter()
+        .map(|action| {
+            let attempts = retry::list_attempts_tx(conn, &action.action_id)?;
+            Ok(DeadLetterAction { action, attempts })
+        })
+        .collect()
+}
+
+/// Dead-lettered actions, most recently given up on first, each with its
+/// attempt history.
+pub async fn list_dead_letters(
+    db: &DbPool,
+    tenant_id: Option<&str>,
+    limit: usize,
+) -> Result<Vec<DeadLetterAction>> {
+    let tenant_id = tenant_id.map(ToString::to_string);
+    execute_async(db, move |conn| {
+        list_dead_letters_tx(conn, tenant_id.as_deref(), limit)
     })
     .await
 }
 
-fn row_to_action(row: &rusqlite::Row<'_>) -> rusqlite::Result<OrchestratorAction> {
+const ACTION_COLUMNS: &str = "action_id, tenant_id, workload_id, action_type, payload_json, ttl_seconds, rollback_action_json, parent_action_id, idempotency_key, decision_window_start, status, reason_code, reason_message, effective_at, outbound_requested_at, runtime_operation_id, runtime_operation_type, terminal_status, terminal_at, total_latency_ms, attempt_count, next_retry_at, created_at, updated_at";
+
+fn get_action_tx(conn: &Connection, action_id: &str) -> Result<OrchestratorAction> {
+    conn.query_row(
+        &format!("SELECT {ACTION_COLUMNS} FROM orchestrator_action WHERE action_id = ?1"),
+        params![action_id],
+        row_to_action,
+    )
+    .optional()?
+    .ok_or_else(|| ServiceError::NotFound(format!("Action {} not found", action_id)).into())
+}
+
+fn row_to_action(row: &Row) -> Result<OrchestratorAction> {
     let payload: String = row.get(4)?;
     let rollback_raw: Option<String> = row.get(6)?;
     Ok(OrchestratorAction {
@@ -503,13 +1323,12 @@ fn load_policy(
     tenant_id: &str,
     workload_id: &str,
 ) -> Result<Option<WorkloadPolicyRow>> {
-    let mut stmt = conn.prepare(
-        "SELECT runtime_function_id, max_concurrency, hard_quota, soft_burst, absolute_limit, cooldown_seconds, hysteresis_pct, burst_cpu_cap, burst_mem_mb, burst_ttl_seconds, target_container_ids_json
+    let row = conn.query_row(
+        "SELECT runtime_function_id, max_concurrency, hard_quota, soft_burst, absolute_limit, cooldown_seconds, hysteresis_pct, burst_cpu_cap, burst_mem_mb, burst_ttl_seconds, target_container_ids_json, priority, scale_to_zero_idle_seconds, scaling_strategy_json, warm_pool_json
          FROM orchestrator_workload_policy
          WHERE tenant_id = ?1 AND workload_id = ?2",
-    )?;
-    let row = stmt
-        .query_row(params![tenant_id, workload_id], |row| {
+        params![tenant_id, workload_id],
+        |row| {
             let targets: String = row.get(10)?;
             Ok(WorkloadPolicyRow {
                 runtime_function_id: row.get(0)?,
@@ -517,15 +1336,23 @@ fn load_policy(
                 hard_quota: row.get(2)?,
                 soft_burst: row.get(3)?,
                 absolute_limit: row.get(4)?,
+                priority: row.get(11)?,
                 cooldown_seconds: row.get(5)?,
                 hysteresis_pct: row.get(6)?,
                 burst_cpu_cap: row.get(7)?,
                 burst_mem_mb: row.get(8)?,
                 burst_ttl_seconds: row.get(9)?,
                 target_container_ids: serde_json::from_str(&targets).unwrap_or_default(),
+                scale_to_zero_idle_seconds: row.get(12)?,
+                scaling_strategy: serde_json::from_str(&row.get::<String>(13)?)
+                    .unwrap_or_default(),
+                warm_pool: row
+                    .get::<Option<String>>(14)?
+                    .and_then(|raw| serde_json::from_str(&raw).ok()),
             })
-        })
-        .optional()?;
+        },
+    )
+    .optional()?;
     Ok(row)
 }
 
@@ -534,88 +1361,92 @@ fn load_slo(
     tenant_id: &str,
     workload_id: &str,
 ) -> Result<Option<WorkloadSloRow>> {
-    let mut stmt = conn.prepare(
-        "SELECT p95_latency_ms, max_cold_start_pct, max_reject_pct, max_cost_per_compute_unit
+    let row = conn
+        .query_row(
+            "SELECT p95_latency_ms, max_cold_start_pct, max_reject_pct, max_cost_per_compute_unit
          FROM orchestrator_workload_slo
          WHERE tenant_id = ?1 AND workload_id = ?2",
-    )?;
-    let row = stmt
-        .query_row(params![tenant_id, workload_id], |row| {
-            Ok(WorkloadSloRow {
-                p95_latency_ms: row.get(0)?,
-                max_cold_start_pct: row.get(1)?,
-                max_reject_pct: row.get(2)?,
-                max_cost_per_compute_unit: row.get(3)?,
-            })
-        })
+            params![tenant_id, workload_id],
+            |row| {
+                Ok(WorkloadSloRow {
+                    p95_latency_ms: row.get(0)?,
+                    max_cold_start_pct: row.get(1)?,
+                    max_reject_pct: row.get(2)?,
+                    max_cost_per_compute_unit: row.get(3)?,
+                })
+            },
+        )
         .optional()?;
     Ok(row)
 }
 
+/// The stored intent a new evaluation is compared against.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct CurrentIntent {
+    target_concurrency: u32,
+    updated_at: i64,
+    /// Since when the workload has had no queue depth and no active units.
+    idle_since: Option<i64>,
+    /// Pool sizes as min, target and max ready.
+    pool_ready: [u32; 3],
+    warm_pool: WarmPoolState,
+}
+
 fn load_current_intent(
     conn: &Connection,
     tenant_id: &str,
     workload_id: &str,
-) -> Result<Option<(u32, i64)>> {
-    let mut stmt = conn.prepare(
-        "SELECT target_concurrency, updated_at
+) -> Result<Option<CurrentIntent>> {
+    let row = conn
+        .query_row(
+            "SELECT target_concurrency, updated_at, idle_since, warm_pool_adjustment, warm_pool_idle_ticks, pool_min_ready, pool_target_ready, pool_max_ready
          FROM orchestrator_intent
          WHERE tenant_id = ?1 AND workload_id = ?2",
-    )?;
-    let row = stmt
-        .query_row(params![tenant_id, workload_id], |row| {
-            Ok((row.get(0)?, row.get(1)?))
-        })
+            params![tenant_id, workload_id],
+            |row| {
+                Ok(CurrentIntent {
+                    target_concurrency: row.get(0)?,
+                    updated_at: row.get(1)?,
+                    idle_since: row.get(2)?,
+                    pool_ready: [row.get(5)?, row.get(6)?, row.get(7)?],
+                    warm_pool: WarmPoolState {
+                        adjustment: row.get(3)?,
+                        idle_ticks: row.get(4)?,
+                    },
+                })
+            },
+        )
         .optional()?;
     Ok(row)
 }
 
-fn list_workload_observations(conn: &Connection) -> Result<Vec<WorkloadObservation>> {
-    let mut stmt = conn.prepare(
-        "SELECT tenant_id, workload_id, node_group, queue_depth, cpu_pressure, mem_pressure, io_pressure, cold_start_pct, invoke_p95_ms, reject_pct, active_compute_units, cost_per_compute_unit
-         FROM orchestrator_workload_observation",
-    )?;
-    let rows = stmt
-        .query_map([], |row| {
-            Ok(WorkloadObservation {
-                tenant_id: row.get(0)?,
-                workload_id: row.get(1)?,
-                node_group: row.get(2)?,
-                queue_depth: row.get(3)?,
-                cpu_pressure: row.get(4)?,
-                mem_pressure: row.get(5)?,
-                io_pressure: row.get(6)?,
-                cold_start_pct: row.get(7)?,
-                invoke_p95_ms: row.get(8)?,
-                reject_pct: row.get(9)?,
-                active_compute_units: row.get(10)?,
-                cost_per_compute_unit: row.get(11)?,
-            })
-        })?
-        .collect::<Result<Vec<_>, _>>()?;
-    Ok(rows)
+/// Queue depth of the newest sample, unaggregated, so a workload scaled to
+/// zero wakes on the first request rather than once the window average
+/// moves off zero.
+fn latest_queue_depth(conn: &Connection, tenant_id: &str, workload_id: &str) -> Result<u32> {
+    let depth = conn
+        .query_row(
+            "SELECT queue_depth
+         FROM orchestrator_workload_observation
+         WHERE tenant_id = ?1 AND workload_id = ?2",
+            params![tenant_id, workload_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(depth.unwrap_or(0))
 }
 
-fn list_node_group_observations(conn: &Connection) -> Result<Vec<NodeGroupObservation>> {
-    let mut stmt = conn.prepare(
-        "SELECT node_group, cpu_pressure, mem_pressure, io_pressure, warm_ready, warm_hit_rate, capacity_units, used_units
-         FROM orchestrator_node_group_observation",
+fn update_idle_since(
+    conn: &Connection,
+    tenant_id: &str,
+    workload_id: &str,
+    idle_since: Option<i64>,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE orchestrator_intent SET idle_since = ?3 WHERE tenant_id = ?1 AND workload_id = ?2",
+        params![tenant_id, workload_id, idle_since],
     )?;
-    let rows = stmt
-        .query_map([], |row| {
-            Ok(NodeGroupObservation {
-                node_group: row.get(0)?,
-                cpu_pressure: row.get(1)?,
-                mem_pressure: row.get(2)?,
-                io_pressure: row.get(3)?,
-                warm_ready: row.get(4)?,
-                warm_hit_rate: row.get(5)?,
-                capacity_units: row.get(6)?,
-                used_units: row.get(7)?,
-            })
-        })?
-        .collect::<Result<Vec<_>, _>>()?;
-    Ok(rows)
+    Ok(())
 }
 
 fn upsert_intent(conn: &Connection, intent: &OrchestratorIntent) -> Result<()> {
@@ -655,9 +1486,47 @@ fn upsert_intent(conn: &Connection, intent: &OrchestratorIntent) -> Result<()> {
             intent.updated_at
         ],
     )?;
+    events::record_event_tx(
+        conn,
+        &NewEvent {
+            event_type: events::EVENT_INTENT_UPDATED,
+            tenant_id: &intent.tenant_id,
+            workload_id: &intent.workload_id,
+            reason_code: Some(&intent.reason_code),
+            data: json!({
+                "target_concurrency": intent.target_concurrency,
+                "pool_min_ready": intent.pool_min_ready,
+                "pool_target_ready": intent.pool_target_ready,
+                "pool_max_ready": intent.pool_max_ready,
+                "preferred_node_group": intent.preferred_node_group,
+                "anti_affinity": intent.anti_affinity
+            }),
+            ..Default::default()
+        },
+        intent.updated_at,
+    )?;
     Ok(())
 }
 
+/// What a newer action of the same type replaces: the whole workload for
+/// `SetPoolTarget`, one container for `SetBurstPolicy`. `None` for action
+/// types that are never superseded.
+fn supersession_key(action_type: &str, payload: &Value) -> Option<String> {
+    match action_type {
+        "SetPoolTarget" => Some(String::new()),
+        "SetBurstPolicy" => payload
+            .get("container_id")
+            .and_then(Value::as_str)
+            .map(ToString::to_string),
+        _ => None,
+    }
+}
+
+/// Insert a pending action unless one with the same idempotency key exists.
+/// A new `SetPoolTarget` or `SetBurstPolicy` cancels older pending ones for
+/// the same target, so a backlog never replays stale targets.
+/// Returns the new action id, or `None` for a duplicate.
+#[allow(clippy::too_many_arguments)]
 fn enqueue_action(
     conn: &Connection,
     tenant_id: &str,
@@ -668,7 +1537,7 @@ fn enqueue_action(
     decision_window_start: i64,
     rollback_action: Option<Value>,
     parent_action_id: Option<&str>,
-) -> Result<()> {
+) -> Result<Option<String>> {
     let now = now_unix_seconds();
     let idempotency_key = deterministic_idempotency_key(
         tenant_id,
@@ -677,29 +1546,69 @@ fn enqueue_action(
         &payload,
         decision_window_start,
     );
-    conn.execute(
+    let action_id = Uuid::new_v4().to_string();
+    let payload = canonical_json(&payload);
+    let supersedes = supersession_key(action_type, &payload);
+    let inserted = conn.execute(
         "INSERT INTO orchestrator_action
-         (action_id, tenant_id, workload_id, action_type, payload_json, ttl_seconds, rollback_action_json, parent_action_id, idempotency_key, decision_window_start, status, reason_code, reason_message, effective_at, outbound_requested_at, runtime_operation_id, runtime_operation_type, terminal_status, terminal_at, total_latency_ms, attempt_count, next_retry_at, created_at, updated_at)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, 'pending', NULL, NULL, ?11, NULL, NULL, NULL, NULL, NULL, NULL, 0, ?11, ?11, ?11)
+         (action_id, tenant_id, workload_id, action_type, payload_json, ttl_seconds, rollback_action_json, parent_action_id, idempotency_key, decision_window_start, status, reason_code, reason_message, effective_at, outbound_requested_at, runtime_operation_id, runtime_operation_type, terminal_status, terminal_at, total_latency_ms, attempt_count, next_retry_at, created_at, updated_at, supersession_key)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, 'pending', NULL, NULL, ?11, NULL, NULL, NULL, NULL, NULL, NULL, 0, ?11, ?11, ?11, ?12)
          ON CONFLICT(idempotency_key) DO NOTHING",
         params![
-            Uuid::new_v4().to_string(),
+            action_id,
             tenant_id,
             workload_id,
             action_type,
-            canonical_json(&payload).to_string(),
+            payload.to_string(),
             ttl_seconds,
             rollback_action.map(|v| canonical_json(&v).to_string()),
             parent_action_id,
             idempotency_key,
             decision_window_start,
-            now
+            now,
+            supersedes
         ],
     )?;
-    Ok(())
+    if inserted == 0 {
+        return Ok(None);
+    }
+    metrics::action_enqueued(action_type);
+    events::record_event_tx(
+        conn,
+        &NewEvent {
+            event_type: events::EVENT_ACTION_ENQUEUED,
+            tenant_id,
+            workload_id,
+            action_id: Some(&action_id),
+            action_type: Some(action_type),
+            status: Some("pending"),
+            data: json!({
+                "payload": payload,
+                "parent_action_id": parent_action_id
+            }),
+            ..Default::default()
+        },
+        now,
+    )?;
+    if let Some(key) = supersedes {
+        cancel_pending_actions_tx(
+            conn,
+            "tenant_id = ?4 AND workload_id = ?5 AND action_type = ?6 AND supersession_key = ?7 AND action_id <> ?8",
+            params![tenant_id, workload_id, action_type, key, action_id],
+            RC_ORCH_SUPERSEDED,
+            &format!("Superseded by action {}", action_id),
+            json!({ "superseded_by": action_id }),
+            now,
+        )?;
+    }
+    Ok(Some(action_id))
 }
 
-fn hottest_group_for(workload_node_group: &str, groups: &[NodeGroupObservation]) -> Option<String> {
+fn hottest_group_for(
+    workload_node_group: &str,
+    groups: &[NodeGroupObservation],
+    hot_pressure: f64,
+) -> Option<String> {
     let mut candidate = None;
     let mut max_pressure = 0.0;
     for g in groups {
@@ -709,7 +1618,7 @@ fn hottest_group_for(workload_node_group: &str, groups: &[NodeGroupObservation])
             candidate = Some(g.node_group.clone());
         }
     }
-    if max_pressure >= 0.85 && candidate.as_deref() == Some(workload_node_group) {
+    if max_pressure >= hot_pressure && candidate.as_deref() == Some(workload_node_group) {
         candidate
     } else {
         None
@@ -728,155 +1637,641 @@ fn coolest_group(groups: &[NodeGroupObservation], fallback: &str) -> String {
         .unwrap_or_else(|| fallback.to_string())
 }
 
-fn run_fast_loop_tx(conn: &Connection) -> Result<()> {
-    let observations = list_workload_observations(conn)?;
-    let node_groups = list_node_group_observations(conn)?;
-    let now = now_unix_seconds();
-    let window_start = decision_window_start(now, FAST_LOOP_SECONDS as i64);
+/// What a workload wants from its node group this tick.
+#[derive(Debug, Clone)]
+struct CapacityDemand<'a> {
+    node_group: &'a str,
+    priority: u8,
+    active_compute_units: u32,
+    desired: u32,
+}
 
-    for obs in observations {
-        let Some(policy) = load_policy(conn, &obs.tenant_id, &obs.workload_id)? else {
-            continue;
-        };
-        let slo = load_slo(conn, &obs.tenant_id, &obs.workload_id)?;
-        let pressure = obs.cpu_pressure.max(obs.mem_pressure).max(obs.io_pressure);
+/// Ceiling on a workload's target, imposed to make room for higher-priority
+/// workloads on a saturated node group.
+#[derive(Debug, Clone, PartialEq)]
+struct Preemption {
+    ceiling: u32,
+    detail: String,
+}
 
-        let queue_boost = if obs.queue_depth > 0 {
-            1.0 + (obs.queue_depth as f64 / 100.0).min(1.0)
-        } else {
-            1.0
-        };
-        let mut desired = (obs.active_compute_units as f64 * queue_boost).ceil() as i64;
-        desired = desired.max(policy.max_concurrency as i64);
-        desired = desired.min((policy.hard_quota + policy.soft_burst) as i64);
-        desired = desired.min(policy.absolute_limit as i64);
-
-        let mut reason_code = "STEADY_STATE";
-        let mut burst_cpu = policy.burst_cpu_cap;
-        let mut burst_mem = policy.burst_mem_mb as i64;
-        if let Some(s) = &slo {
-            let violation = obs.invoke_p95_ms > s.p95_latency_ms
-                || obs.cold_start_pct > s.max_cold_start_pct
-                || obs.reject_pct > s.max_reject_pct
-                || obs.cost_per_compute_unit > s.max_cost_per_compute_unit;
-            if violation {
-                desired = (desired + policy.soft_burst as i64).min(policy.absolute_limit as i64);
-                burst_cpu *= 1.2;
-                burst_mem = (burst_mem as f64 * 1.15) as i64;
-                reason_code = "SLO_GUARD";
-            }
+/// Share out each saturated node group by priority. A group is saturated
+/// when its utilization is at least `saturation` and its workloads together
+/// want more than it can hold: its free units plus what they already use.
+/// Higher priorities are served first, ties in `demands` order, and every
+/// workload that wants capacity keeps at least one unit. Returns one entry per demand, `None`
+/// where the workload gets all it wants.
+fn priority_ceilings(
+    demands: &[CapacityDemand<'_>],
+    node_groups: &[NodeGroupObservation],
+    saturation: f64,
+) -> Vec<Option<Preemption>> {
+    let mut ceilings = vec![None; demands.len()];
+    for group in node_groups {
+        if group.capacity_units == 0
+            || (group.used_units as f64 / group.capacity_units as f64) < saturation
+        {
+            continue;
         }
-        if pressure > 0.85 {
-            reason_code = "HOT_NODE_GROUP";
-        } else if obs.queue_depth > 0 {
-            reason_code = "QUEUE_PRESSURE";
+        let mut members: Vec<usize> = (0..demands.len())
+            .filter(|&i| demands[i].node_group == group.node_group)
+            .collect();
+        let in_use: i64 = members
+            .iter()
+            .map(|&i| demands[i].active_compute_units as i64)
+            .sum();
+        let wanted: i64 = members.iter().map(|&i| demands[i].desired as i64).sum();
+        let available = (group.capacity_units as i64 - group.used_units as i64 + in_use).max(0);
+        if wanted <= available {
+            continue;
         }
-
-        let min_ready = (desired as f64 * 0.10).ceil() as i64;
-        let target_ready = (desired as f64 * 0.20).ceil() as i64;
-        let max_ready = (desired as f64 * 0.30).ceil() as i64;
-        let anti_affinity = hottest_group_for(&obs.node_group, &node_groups).is_some();
-        let preferred_group = if anti_affinity {
-            coolest_group(&node_groups, &obs.node_group)
-        } else {
-            obs.node_group.clone()
-        };
-
-        let intent = OrchestratorIntent {
-            tenant_id: obs.tenant_id.clone(),
-            workload_id: obs.workload_id.clone(),
-            target_concurrency: clamp_u32(desired, 1, policy.absolute_limit),
-            burst_cpu_cap: burst_cpu,
-            burst_mem_mb: clamp_u32(burst_mem, 64, policy.burst_mem_mb.saturating_mul(2)),
-            burst_ttl_seconds: policy.burst_ttl_seconds,
-            pool_min_ready: clamp_u32(min_ready, 0, u32::MAX),
-            pool_target_ready: clamp_u32(target_ready, 0, u32::MAX),
-            pool_max_ready: clamp_u32(max_ready, 0, u32::MAX),
-            preferred_node_group: preferred_group.clone(),
-            anti_affinity,
-            reason_code: reason_code.to_string(),
-            effective_at: now,
-            ttl_seconds: policy.burst_ttl_seconds,
-            updated_at: now,
-        };
-        if let Some((current_target, current_updated_at)) =
-            load_current_intent(conn, &obs.tenant_id, &obs.workload_id)?
-        {
-            let cooldown_open = now - current_updated_at < policy.cooldown_seconds as i64;
-            let threshold = (current_target as f64 * (policy.hysteresis_pct / 100.0)).ceil() as u32;
-            let delta = intent.target_concurrency.abs_diff(current_target);
-            if cooldown_open || delta <= threshold.max(1) {
-                continue;
+        members.sort_by_key(|&i| std::cmp::Reverse(demands[i].priority));
+        let mut remaining = available;
+        for i in members {
+            let demand = &demands[i];
+            let granted = (demand.desired as i64)
+                .min(remaining)
+                .max(demand.desired.min(1) as i64);
+            remaining = (remaining - granted).max(0);
+            if granted < demand.desired as i64 {
+                ceilings[i] = Some(Preemption {
+                    ceiling: granted as u32,
+                    detail: format!(
+                        "node group {} at {}/{} units; priority {} granted {} of {}",
+                        group.node_group,
+                        group.used_units,
+                        group.capacity_units,
+                        demand.priority,
+                        granted,
+                        demand.desired
+                    ),
+                });
             }
         }
-        upsert_intent(conn, &intent)?;
+    }
+    ceilings
+}
 
-        enqueue_action(
-            conn,
-            &obs.tenant_id,
-            &obs.workload_id,
-            "SetPoolTarget",
-            json!({
-                "function_id": policy.runtime_function_id,
-                "min_instances": intent.pool_min_ready,
-                "max_instances": intent.pool_max_ready
-            }),
-            policy.burst_ttl_seconds,
-            window_start,
-            None,
-            None,
-        )?;
+/// Propose an intent for one workload and record why. The base target comes
+/// from the policy's scaling strategy, already run for this tick. Conditions are
+/// collected in evaluation order; when several fire, the intent's
+/// `reason_code` is the last of SLO_GUARD, HOT_NODE_GROUP, QUEUE_PRESSURE
+/// and PRIORITY_PREEMPTION, then SCALE_TO_ZERO or WAKE_FROM_ZERO. A
+/// preempted workload also loses its SLO burst.
+///
+/// With `warm_pool` set, the pool ratios are shifted by the controller's
+/// adjustment kept on `current` and the sizes held within the policy's
+/// bounds. A change in pool size then counts against hysteresis like a
+/// change in target, so the controller can resize the pool on its own.
+///
+/// With `scale_to_zero_idle_seconds` set, a workload idle for that long
+/// gets a zero target and an empty warm pool, skipping hysteresis. Any queue
+/// depth while at zero wakes it, skipping cooldown and hysteresis. Waking
+/// happens on the next fast loop tick, so a request arriving at zero waits
+/// up to `fast_loop_seconds` plus dispatch before capacity is requested.
+#[allow(clippy::too_many_arguments)]
+fn evaluate_workload(
+    obs: &WorkloadObservation,
+    policy: &WorkloadPolicyRow,
+    slo: Option<&WorkloadSloRow>,
+    node_groups: &[NodeGroupObservation],
+    current: Option<CurrentIntent>,
+    tuning: &Tuning,
+    recommendation: &Recommendation,
+    preemption: Option<&Preemption>,
+    now: i64,
+) -> DecisionTrace {
+    let mut conditions = Vec::new();
+    let mut clamps = Vec::new();
+    let mut condition = |code: &str, detail: String| {
+        conditions.push(DecisionCondition {
+            code: code.to_string(),
+            detail,
+        })
+    };
+    let mut clamp = |rule: &str, before: i64, after: i64| {
+        if before != after {
+            clamps.push(DecisionClamp {
+                rule: rule.to_string(),
+                before,
+                after,
+            });
+        }
+        after
+    };
 
-        for container_id in &policy.target_container_ids {
-            enqueue_action(
-                conn,
-                &obs.tenant_id,
-                &obs.workload_id,
-                "SetBurstPolicy",
-                json!({
-                    "container_id": container_id,
-                    "memory_limit_mb": intent.burst_mem_mb,
-                    "cpu_limit_percent": (intent.burst_cpu_cap * 100.0).round() as u32
-                }),
-                policy.burst_ttl_seconds,
-                window_start,
-                None,
-                None,
-            )?;
+    let pressure = obs.cpu_pressure.max(obs.mem_pressure).max(obs.io_pressure);
+    let queue_boost = recommendation.queue_boost;
+    let base_target = recommendation.base_target;
+    if let Some(detail) = &recommendation.detail {
+        condition("SCALING_STRATEGY", detail.clone());
+    }
+    let mut desired = clamp(
+        "max_concurrency_floor",
+        base_target,
+        base_target.max(policy.max_concurrency as i64),
+    );
+    desired = clamp(
+        "hard_quota_plus_soft_burst",
+        desired,
+        desired.min((policy.hard_quota + policy.soft_burst) as i64),
+    );
+    desired = clamp(
+        "absolute_limit",
+        desired,
+        desired.min(policy.absolute_limit as i64),
+    );
+
+    let mut reason_code = "STEADY_STATE";
+    let mut burst_cpu = policy.burst_cpu_cap;
+    let mut burst_mem = policy.burst_mem_mb as i64;
+    if let Some(s) = slo {
+        let mut violations = Vec::new();
+        if obs.invoke_p95_ms > s.p95_latency_ms {
+            violations.push(format!(
+                "invoke_p95_ms {} > {}",
+                obs.invoke_p95_ms, s.p95_latency_ms
+            ));
+        }
+        if obs.cold_start_pct > s.max_cold_start_pct {
+            violations.push(format!(
+                "cold_start_pct {} > {}",
+                obs.cold_start_pct, s.max_cold_start_pct
+            ));
+        }
+        if obs.reject_pct > s.max_reject_pct {
+            violations.push(format!(
+                "reject_pct {} > {}",
+                obs.reject_pct, s.max_reject_pct
+            ));
+        }
+        if obs.cost_per_compute_unit > s.max_cost_per_compute_unit {
+            violations.push(format!(
+                "cost_per_compute_unit {} > {}",
+                obs.cost_per_compute_unit, s.max_cost_per_compute_unit
+            ));
+        }
+        if !violations.is_empty() {
+            desired = clamp(
+                "slo_soft_burst",
+                desired,
+                (desired + policy.soft_burst as i64).min(policy.absolute_limit as i64),
+            );
+            burst_cpu *= tuning.slo_burst_cpu_multiplier;
+            burst_mem = (burst_mem as f64 * tuning.slo_burst_mem_multiplier) as i64;
+            reason_code = "SLO_GUARD";
+            condition("SLO_GUARD", violations.join("; "));
         }
+    }
+    if pressure > tuning.hot_group_pressure {
+        reason_code = "HOT_NODE_GROUP";
+        condition(
+            "HOT_NODE_GROUP",
+            format!(
+                "pressure {:.2} > {:.2}",
+                pressure, tuning.hot_group_pressure
+            ),
+        );
+    } else if obs.queue_depth > 0 {
+        reason_code = "QUEUE_PRESSURE";
+        condition(
+            "QUEUE_PRESSURE",
+            if queue_boost > 1.0 {
+                format!(
+                    "queue_depth {} boosts target x{:.2}",
+                    obs.queue_depth, queue_boost
+                )
+            } else {
+                format!("queue_depth {}", obs.queue_depth)
+            },
+        );
+    }
+    if let Some(p) = preemption {
+        desired = clamp(
+            "priority_preemption",
+            desired,
+            desired.min(p.ceiling as i64),
+        );
+        burst_cpu = policy.burst_cpu_cap;
+        burst_mem = policy.burst_mem_mb as i64;
+        reason_code = RC_ORCH_PRIORITY_PREEMPTION;
+        condition(RC_ORCH_PRIORITY_PREEMPTION, p.detail.clone());
+    }
 
-        enqueue_action(
-            conn,
-            &obs.tenant_id,
-            &obs.workload_id,
-            "SetPlacementPreference",
-            json!({
-                "tenant_id": obs.tenant_id,
-                "workload_id": obs.workload_id,
-                "node_group": preferred_group,
-                "anti_affinity": anti_affinity
+    let idle = obs.queue_depth == 0 && obs.active_compute_units == 0;
+    let idle_for = match (policy.scale_to_zero_idle_seconds, current) {
+        (
+            Some(after),
+            Some(CurrentIntent {
+                idle_since: Some(since),
+                ..
             }),
-            policy.burst_ttl_seconds,
-            window_start,
-            None,
-            None,
-        )?;
+        ) if idle && now - since >= after as i64 => Some(now - since),
+        _ => None,
+    };
+    let waking = policy.scale_to_zero_idle_seconds.is_some()
+        && current.is_some_and(|c| c.target_concurrency == 0)
+        && obs.queue_depth > 0;
+    if let Some(idle_for) = idle_for {
+        desired = clamp("scale_to_zero", desired, 0);
+        reason_code = "SCALE_TO_ZERO";
+        condition(
+            "SCALE_TO_ZERO",
+            format!("no queue depth or active units for {}s", idle_for),
+        );
+    } else if waking {
+        reason_code = "WAKE_FROM_ZERO";
+        condition(
+            "WAKE_FROM_ZERO",
+            format!("queue_depth {} while scaled to zero", obs.queue_depth),
+        );
     }
-    Ok(())
-}
 
-fn run_slow_loop_tx(conn: &Connection) -> Result<()> {
-    let groups = list_node_group_observations(conn)?;
-    let now = now_unix_seconds();
-    let window_start = decision_window_start(now, SLOW_LOOP_SECONDS as i64);
-    for group in groups {
-        let utilization = if group.capacity_units == 0 {
+    let adjustment = match (&policy.warm_pool, current) {
+        (Some(_), Some(c)) => c.warm_pool.adjustment,
+        _ => 0.0,
+    };
+    let pool = |ratio: f64| (desired as f64 * (ratio + adjustment).clamp(0.0, 1.0)).ceil() as i64;
+    let mut min_ready = pool(tuning.warm_pool_min_ratio);
+    let mut target_ready = pool(tuning.warm_pool_target_ratio);
+    let mut max_ready = pool(tuning.warm_pool_max_ratio);
+    // A workload scaled to zero drains its pool whatever the bounds say.
+    if let Some(bounds) = policy.warm_pool.as_ref().filter(|_| idle_for.is_none()) {
+        let (lo, hi) = (bounds.min_ready as i64, bounds.max_ready as i64);
+        min_ready = clamp("pool_min_ready_bounds", min_ready, min_ready.clamp(lo, hi));
+        target_ready = clamp(
+            "pool_target_ready_bounds",
+            target_ready,
+            target_ready.clamp(lo, hi),
+        );
+        max_ready = clamp("pool_max_ready_bounds", max_ready, max_ready.clamp(lo, hi));
+    }
+    let hot_group = hottest_group_for(&obs.node_group, node_groups, tuning.hot_group_pressure);
+    let anti_affinity = hot_group.is_some();
+    let preferred_group = if anti_affinity {
+        coolest_group(node_groups, &obs.node_group)
+    } else {
+        obs.node_group.clone()
+    };
+    if let Some(hot) = hot_group {
+        condition(
+            "ANTI_AFFINITY",
+            format!("node group {} is hot; preferring {}", hot, preferred_group),
+        );
+    }
+
+    let min_target = if idle_for.is_some() { 0 } else { 1 };
+    let target_concurrency = clamp_u32(desired, min_target, policy.absolute_limit);
+    clamp("target_range", desired, target_concurrency as i64);
+    let intent = OrchestratorIntent {
+        tenant_id: obs.tenant_id.clone(),
+        workload_id: obs.workload_id.clone(),
+        target_concurrency,
+        burst_cpu_cap: burst_cpu,
+        burst_mem_mb: clamp_u32(burst_mem, 64, policy.burst_mem_mb.saturating_mul(2)),
+        burst_ttl_seconds: policy.burst_ttl_seconds,
+        pool_min_ready: clamp_u32(min_ready, 0, u32::MAX),
+        pool_target_ready: clamp_u32(target_ready, 0, u32::MAX),
+        pool_max_ready: clamp_u32(max_ready, 0, u32::MAX),
+        preferred_node_group: preferred_group,
+        anti_affinity,
+        reason_code: reason_code.to_string(),
+        effective_at: now,
+        ttl_seconds: policy.burst_ttl_seconds,
+        updated_at: now,
+    };
+
+    let mut suppressed_by = None;
+    if let Some(c) = current.filter(|_| !waking) {
+        let (current_target, since_update) = (c.target_concurrency, now - c.updated_at);
+        let threshold = (current_target as f64 * (policy.hysteresis_pct / 100.0)).ceil() as u32;
+        let delta = intent.target_concurrency.abs_diff(current_target);
+        let pool_ready = [
+            intent.pool_min_ready,
+            intent.pool_target_ready,
+            intent.pool_max_ready,
+        ];
+        let pool_moved = policy.warm_pool.is_some() && pool_ready != c.pool_ready;
+        if since_update < policy.cooldown_seconds as i64 {
+            condition(
+                "COOLDOWN",
+                format!(
+                    "intent updated {}s ago, cooldown {}s",
+                    since_update, policy.cooldown_seconds
+                ),
+            );
+            suppressed_by = Some("cooldown".to_string());
+        } else if delta <= threshold.max(1)
+            && !pool_moved
+            && !(idle_for.is_some() && current_target > 0)
+        {
+            condition(
+                "HYSTERESIS",
+                format!(
+                    "target change {} -> {} within threshold {}",
+                    current_target,
+                    intent.target_concurrency,
+                    threshold.max(1)
+                ),
+            );
+            suppressed_by = Some("hysteresis".to_string());
+        }
+    }
+
+    DecisionTrace {
+        decision_id: 0,
+        tenant_id: obs.tenant_id.clone(),
+        workload_id: obs.workload_id.clone(),
+        observation: obs.clone(),
+        pressure,
+        queue_boost,
+        base_target,
+        current_target: current.map(|c| c.target_concurrency),
+        conditions,
+        clamps,
+        proposed: intent,
+        applied: suppressed_by.is_none(),
+        suppressed_by,
+        created_at: now,
+    }
+}
+
+/// What the fast loop gathered for one workload before evaluating it.
+struct WorkloadInputs {
+    obs: WorkloadObservation,
+    policy: WorkloadPolicyRow,
+    slo: Option<WorkloadSloRow>,
+    current: Option<CurrentIntent>,
+    tuning: Tuning,
+    recommendation: Recommendation,
+    /// Strategy state as loaded, before this tick's recommendation.
+    strategy_state: StrategyState,
+    pool_change: Option<PoolChange>,
+    idle_since: Option<i64>,
+}
+
+/// A warm pool adjustment the controller made this tick, saved once the
+/// intent is applied.
+struct PoolChange {
+    state: WarmPoolState,
+    detail: Option<String>,
+}
+
+/// Rows a loop run wrote, for callers that report on them.
+#[derive(Debug, Default)]
+struct LoopOutput {
+    decisions: Vec<DecisionTrace>,
+    enqueued: Vec<String>,
+}
+
+fn run_fast_loop_tx(conn: &Connection, cfg: &OrchestratorConfig) -> Result<LoopOutput> {
+    let mut output = LoopOutput::default();
+    let now = now_unix_seconds();
+    let workloads = observations::workload_observations_tx(conn, &cfg.observations, now)?;
+    let node_groups = observations::node_group_observations_tx(conn, &cfg.observations, now)?;
+    let overrides = orchestrator_config::tenant_overrides_tx(conn, None)?;
+    let window_start = decision_window_start(now, cfg.fast_loop_seconds as i64);
+
+    let mut inputs = Vec::new();
+    for mut obs in workloads {
+        let Some(policy) = load_policy(conn, &obs.tenant_id, &obs.workload_id)? else {
+            continue;
+        };
+        let slo = load_slo(conn, &obs.tenant_id, &obs.workload_id)?;
+        let mut current = load_current_intent(conn, &obs.tenant_id, &obs.workload_id)?;
+        let tuning = overrides
+            .get(&obs.tenant_id)
+            .map_or_else(|| cfg.tuning.clone(), |o| cfg.tuning.with_overrides(o));
+        let mut pool_change = None;
+        // Tracked from the first observation; without an intent row yet it
+        // is written with the one this tick creates.
+        let idle_since = (obs.queue_depth == 0 && obs.active_compute_units == 0)
+            .then(|| current.and_then(|c| c.idle_since).unwrap_or(now));
+        if let Some(c) = current.as_mut() {
+            if idle_since != c.idle_since {
+                update_idle_since(conn, &obs.tenant_id, &obs.workload_id, idle_since)?;
+                c.idle_since = idle_since;
+            }
+            if let Some(pool) = &policy.warm_pool {
+                let (state, why) = warm_pool::step(
+                    pool,
+                    c.warm_pool,
+                    &obs,
+                    node_groups.iter().find(|g| g.node_group == obs.node_group),
+                    slo.as_ref().map(|s| s.max_cold_start_pct),
+                    c.pool_ready[1],
+                    warm_pool::adjustment_range(
+                        pool,
+                        tuning.warm_pool_target_ratio,
+                        c.target_concurrency,
+                    ),
+                );
+                // Idle ticks are kept either way; a new adjustment only once
+                // the intent carrying it goes through, so it can't wind up
+                // while cooldown holds the pool where it is.
+                if state.adjustment == c.warm_pool.adjustment && state != c.warm_pool {
+                    warm_pool::save_state_tx(conn, &obs.tenant_id, &obs.workload_id, &state)?;
+                }
+                if state.adjustment != c.warm_pool.adjustment {
+                    pool_change = Some(PoolChange { state, detail: why });
+                }
+                c.warm_pool = state;
+            }
+            if policy.scale_to_zero_idle_seconds.is_some() && c.target_concurrency == 0 {
+                obs.queue_depth = obs.queue_depth.max(latest_queue_depth(
+                    conn,
+                    &obs.tenant_id,
+                    &obs.workload_id,
+                )?);
+            }
+        }
+        let strategy = &policy.scaling_strategy;
+        let strategy_state =
+            scaling::load_state_tx(conn, &obs.tenant_id, &obs.workload_id, strategy.kind())?;
+        let recommendation = scaling::recommend(
+            strategy,
+            &obs,
+            slo.as_ref().map(|s| s.p95_latency_ms),
+            current.map(|c| c.target_concurrency),
+            strategy_state.clone(),
+            now,
+        );
+        inputs.push(WorkloadInputs {
+            obs,
+            policy,
+            slo,
+            current,
+            tuning,
+            recommendation,
+            strategy_state,
+            pool_change,
+            idle_since,
+        });
+    }
+
+    // Size every workload unconstrained first, so saturated node groups can
+    // be shared out by priority before anything is decided.
+    let demands: Vec<CapacityDemand> = inputs
+        .iter()
+        .map(|w| CapacityDemand {
+            node_group: &w.obs.node_group,
+            priority: w.policy.priority,
+            active_compute_units: w.obs.active_compute_units,
+            desired: evaluate_workload(
+                &w.obs,
+                &w.policy,
+                w.slo.as_ref(),
+                &node_groups,
+                w.current,
+                &w.tuning,
+                &w.recommendation,
+                None,
+                now,
+            )
+            .proposed
+            .target_concurrency,
+        })
+        .collect();
+    let ceilings = priority_ceilings(&demands, &node_groups, cfg.preemption_utilization);
+
+    for (
+        WorkloadInputs {
+            obs,
+            policy,
+            slo,
+            current,
+            tuning,
+            recommendation,
+            strategy_state,
+            pool_change,
+            idle_since,
+        },
+        preemption,
+    ) in inputs.into_iter().zip(ceilings)
+    {
+        let mut trace = evaluate_workload(
+            &obs,
+            &policy,
+            slo.as_ref(),
+            &node_groups,
+            current,
+            &tuning,
+            &recommendation,
+            preemption.as_ref(),
+            now,
+        );
+        if let Some(detail) = pool_change.as_ref().and_then(|c| c.detail.clone()) {
+            trace.conditions.push(DecisionCondition {
+                code: "WARM_POOL".to_string(),
+                detail,
+            });
+        }
+        trace.decision_id = decisions::record_decision_tx(conn, &trace)?;
+        let applied = trace.applied;
+        // Held by hysteresis means the pool sizes didn't move either, so a
+        // step too small to change them yet is kept to build on.
+        let keep_pool_change = applied || trace.suppressed_by.as_deref() == Some("hysteresis");
+        let intent = trace.proposed.clone();
+        output.decisions.push(trace);
+        if let Some(change) = pool_change.filter(|_| keep_pool_change) {
+            warm_pool::save_state_tx(conn, &obs.tenant_id, &obs.workload_id, &change.state)?;
+        }
+        if policy.scaling_strategy != ScalingStrategy::Heuristic {
+            let state = if applied {
+                recommendation.state
+            } else {
+                scaling::held_state(&strategy_state, &recommendation.state)
+            };
+            scaling::save_state_tx(
+                conn,
+                &obs.tenant_id,
+                &obs.workload_id,
+                policy.scaling_strategy.kind(),
+                &state,
+                now,
+            )?;
+        }
+        if !applied {
+            continue;
+        }
+        let preferred_group = intent.preferred_node_group.clone();
+        let anti_affinity = intent.anti_affinity;
+        upsert_intent(conn, &intent)?;
+        // Nothing holds back a workload's first intent, so this is where a
+        // new row picks up the idle tracking started above.
+        if current.is_none() && idle_since.is_some() {
+            update_idle_since(conn, &obs.tenant_id, &obs.workload_id, idle_since)?;
+        }
+
+        output.enqueued.extend(enqueue_action(
+            conn,
+            &obs.tenant_id,
+            &obs.workload_id,
+            "SetPoolTarget",
+            json!({
+                "function_id": policy.runtime_function_id,
+                "min_instances": intent.pool_min_ready,
+                "max_instances": intent.pool_max_ready
+            }),
+            policy.burst_ttl_seconds,
+            window_start,
+            None,
+            None,
+        )?);
+
+        for container_id in &policy.target_container_ids {
+            output.enqueued.extend(enqueue_action(
+                conn,
+                &obs.tenant_id,
+                &obs.workload_id,
+                "SetBurstPolicy",
+                json!({
+                    "container_id": container_id,
+                    "memory_limit_mb": intent.burst_mem_mb,
+                    "cpu_limit_percent": (intent.burst_cpu_cap * 100.0).round() as u32
+                }),
+                policy.burst_ttl_seconds,
+                window_start,
+                None,
+                None,
+            )?);
+        }
+
+        output.enqueued.extend(enqueue_action(
+            conn,
+            &obs.tenant_id,
+            &obs.workload_id,
+            "SetPlacementPreference",
+            json!({
+                "tenant_id": obs.tenant_id,
+                "workload_id": obs.workload_id,
+                "node_group": preferred_group,
+                "anti_affinity": anti_affinity
+            }),
+            policy.burst_ttl_seconds,
+            window_start,
+            None,
+            None,
+        )?);
+    }
+    Ok(output)
+}
+
+fn run_slow_loop_tx(conn: &Connection, cfg: &OrchestratorConfig) -> Result<LoopOutput> {
+    let mut output = LoopOutput::default();
+    let now = now_unix_seconds();
+    let groups = observations::node_group_observations_tx(conn, &cfg.observations, now)?;
+    events::prune_events_tx(conn, now)?;
+    decisions::prune_decisions_tx(conn, now)?;
+    observations::prune_samples_tx(conn, &cfg.observations, now)?;
+    let window_start = decision_window_start(now, cfg.slow_loop_seconds as i64);
+    for group in groups {
+        let utilization = if group.capacity_units == 0 {
             0.0
         } else {
             group.used_units as f64 / group.capacity_units as f64
         };
-        if utilization > 0.80 {
-            enqueue_action(
+        if utilization > cfg.scale_up_utilization {
+            output.enqueued.extend(enqueue_action(
                 conn,
                 PLATFORM_TENANT_ID,
                 &group.node_group,
@@ -885,13 +2280,13 @@ fn run_slow_loop_tx(conn: &Connection) -> Result<()> {
                     "node_group": group.node_group,
                     "delta_units": 1
                 }),
-                SLOW_LOOP_SECONDS as u32,
+                cfg.slow_loop_seconds as u32,
                 window_start,
                 None,
                 None,
-            )?;
-        } else if utilization < 0.35 && group.warm_ready > 0 {
-            enqueue_action(
+            )?);
+        } else if utilization < cfg.scale_down_utilization && group.warm_ready > 0 {
+            output.enqueued.extend(enqueue_action(
                 conn,
                 PLATFORM_TENANT_ID,
                 &group.node_group,
@@ -900,71 +2295,211 @@ fn run_slow_loop_tx(conn: &Connection) -> Result<()> {
                     "node_group": group.node_group,
                     "delta_units": 1
                 }),
-                SLOW_LOOP_SECONDS as u32,
+                cfg.slow_loop_seconds as u32,
                 window_start,
                 None,
                 None,
-            )?;
+            )?);
         }
     }
-    Ok(())
+    Ok(output)
 }
 
-pub async fn run_fast_loop(db: &DbPool) -> Result<()> {
+pub async fn run_fast_loop(db: &DbPool, cfg: &OrchestratorConfig) -> Result<()> {
     let db = db.clone();
-    execute_async(&db, move |conn| {
-        let tx = conn.unchecked_transaction()?;
-        run_fast_loop_tx(&tx)?;
-        tx.commit()?;
-        Ok(())
-    })
+    let cfg = cfg.clone();
+    metrics::observe_loop(
+        metrics::LOOP_FAST,
+        execute_async(&db, move |conn| {
+            let tx = events::transaction(conn)?;
+            run_fast_loop_tx(&tx, &cfg)?;
+            tx.commit()?;
+            Ok(())
+        }),
+    )
     .await
 }
 
-pub async fn run_slow_loop(db: &DbPool) -> Result<()> {
+pub async fn run_slow_loop(db: &DbPool, cfg: &OrchestratorConfig) -> Result<()> {
     let db = db.clone();
-    execute_async(&db, move |conn| {
-        let tx = conn.unchecked_transaction()?;
-        run_slow_loop_tx(&tx)?;
-        tx.commit()?;
-        Ok(())
-    })
+    let cfg = cfg.clone();
+    metrics::observe_loop(
+        metrics::LOOP_SLOW,
+        execute_async(&db, move |conn| {
+            let tx = events::transaction(conn)?;
+            run_slow_loop_tx(&tx, &cfg)?;
+            tx.commit()?;
+            Ok(())
+        }),
+    )
     .await
 }
 
-fn load_dispatch_candidates(conn: &Connection, now: i64) -> Result<Vec<DispatchAction>> {
-    let mut stmt = conn.prepare(
-        "SELECT action_id, tenant_id, workload_id, action_type, payload_json, ttl_seconds, rollback_action_json, idempotency_key, status, effective_at, runtime_operation_id, attempt_count, created_at
-         FROM orchestrator_action
-         WHERE status IN ('pending','accepted','queued','running') AND next_retry_at <= ?1
-         ORDER BY created_at ASC
-         LIMIT ?2",
+/// Run the fast then slow loop with `req` layered over the stored state, in
+/// a transaction that is always rolled back. Action metrics are muted so a
+/// dry run doesn't show up as enqueues. Actions whose idempotency key was
+/// already used in the current decision window are left out, as they would
+/// be by the real loops. `tenant_id` restricts what is returned.
+pub async fn simulate(
+    db: &DbPool,
+    cfg: &OrchestratorConfig,
+    req: SimulationRequest,
+    tenant_id: Option<&str>,
+) -> Result<SimulationResponse> {
+    for p in &req.policies {
+        validate_workload_policy(&p.policy)?;
+    }
+    for s in &req.slos {
+        validate_workload_slo(&s.slo)?;
+    }
+    let cfg = cfg.clone();
+    let tenant_id = tenant_id.map(ToString::to_string);
+    let mut response = execute_async(db, move |conn| {
+        let tx = events::transaction(conn)?;
+        // Dropping `tx` without committing rolls everything back.
+        metrics::muted(|| simulate_tx(&tx, &cfg, &req))
+    })
+    .await?;
+    if let Some(tenant_id) = tenant_id {
+        response.intents.retain(|i| i.tenant_id == tenant_id);
+        response.actions.retain(|a| a.tenant_id == tenant_id);
+        response.decisions.retain(|d| d.tenant_id == tenant_id);
+    }
+    Ok(response)
+}
+
+fn simulate_tx(
+    conn: &Connection,
+    cfg: &OrchestratorConfig,
+    req: &SimulationRequest,
+) -> Result<SimulationResponse> {
+    let now = now_unix_seconds();
+    for p in &req.policies {
+        upsert_workload_policy_tx(conn, &p.tenant_id, &p.workload_id, &p.policy, now)?;
+    }
+    for s in &req.slos {
+        upsert_workload_slo_tx(conn, &s.tenant_id, &s.workload_id, &s.slo, now)?;
+    }
+    ingest_observations_tx(conn, &req.workloads, &req.node_groups, now)?;
+
+    let fast = run_fast_loop_tx(conn, cfg)?;
+    let slow = run_slow_loop_tx(conn, cfg)?;
+    let mut actions = Vec::new();
+    for action_id in fast.enqueued.iter().chain(&slow.enqueued) {
+        actions.push(get_action_tx(conn, action_id)?);
+    }
+    let decisions: Vec<DecisionTrace> = fast
+        .decisions
+        .into_iter()
+        .map(|d| DecisionTrace {
+            decision_id: 0,
+            ..d
+        })
+        .collect();
+    let intents = decisions
+        .iter()
+        .filter(|d| d.applied)
+        .map(|d| d.proposed.clone())
+        .collect();
+    Ok(SimulationResponse {
+        intents,
+        actions,
+        decisions,
+    })
+}
+
+fn action_status_counts_tx(conn: &Connection) -> Result<Vec<(String, i64)>> {
+    conn.query_map(
+        "SELECT status, COUNT(*) FROM orchestrator_action GROUP BY status ORDER BY status",
+        params![],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+}
+
+pub async fn action_status_counts(db: &DbPool) -> Result<Vec<(String, i64)>> {
+    execute_async(db, action_status_counts_tx).await
+}
+
+const DISPATCH_COLUMNS: &str = "action_id, tenant_id, workload_id, action_type, payload_json, ttl_seconds, rollback_action_json, idempotency_key, status, effective_at, runtime_operation_id, attempt_count, created_at";
+
+fn row_to_dispatch_action(row: &Row) -> Result<DispatchAction> {
+    let payload_raw: String = row.get(4)?;
+    let rollback_raw: Option<String> = row.get(6)?;
+    Ok(DispatchAction {
+        action_id: row.get(0)?,
+        tenant_id: row.get(1)?,
+        workload_id: row.get(2)?,
+        action_type: row.get(3)?,
+        payload_json: serde_json::from_str(&payload_raw).unwrap_or_else(|_| json!({})),
+        ttl_seconds: row.get(5)?,
+        rollback_action_json: rollback_raw.and_then(|v| serde_json::from_str::<Value>(&v).ok()),
+        idempotency_key: row.get(7)?,
+        status: row.get(8)?,
+        effective_at: row.get(9)?,
+        runtime_operation_id: row.get(10)?,
+        attempt_count: row.get(11)?,
+        created_at: row.get(12)?,
+    })
+}
+
+/// Due actions, oldest first within each tenant and interleaved round-robin
+/// across tenants, so one tenant's backlog can't fill the batch.
+fn load_dispatch_candidates(
+    conn: &Connection,
+    now: i64,
+    limit: usize,
+) -> Result<Vec<DispatchAction>> {
+    let rows = conn.query_map(
+        &format!(
+            "SELECT {DISPATCH_COLUMNS}
+         FROM (
+           SELECT *, ROW_NUMBER() OVER (PARTITION BY tenant_id ORDER BY created_at, action_id) AS tenant_rank
+           FROM orchestrator_action
+           WHERE status IN ('pending','accepted','queued','running') AND next_retry_at <= ?1
+         ) due
+         ORDER BY tenant_rank, created_at, action_id
+         LIMIT ?2"
+        ),
+        params![now, limit as i64],
+        row_to_dispatch_action,
     )?;
-    let rows = stmt
-        .query_map(params![now, MAX_DISPATCH_BATCH as i64], |row| {
-            let payload_raw: String = row.get(4)?;
-            let rollback_raw: Option<String> = row.get(6)?;
-            Ok(DispatchAction {
-                action_id: row.get(0)?,
-                tenant_id: row.get(1)?,
-                workload_id: row.get(2)?,
-                action_type: row.get(3)?,
-                payload_json: serde_json::from_str(&payload_raw).unwrap_or_else(|_| json!({})),
-                ttl_seconds: row.get(5)?,
-                rollback_action_json: rollback_raw
-                    .and_then(|v| serde_json::from_str::<Value>(&v).ok()),
-                idempotency_key: row.get(7)?,
-                status: row.get(8)?,
-                effective_at: row.get(9)?,
-                runtime_operation_id: row.get(10)?,
-                attempt_count: row.get(11)?,
-                created_at: row.get(12)?,
-            })
-        })?
-        .collect::<Result<Vec<_>, _>>()?;
     Ok(rows)
 }
 
+fn load_dispatch_action(conn: &Connection, action_id: &str) -> Result<Option<DispatchAction>> {
+    conn.query_row(
+        &format!("SELECT {DISPATCH_COLUMNS} FROM orchestrator_action WHERE action_id = ?1"),
+        params![action_id],
+        row_to_dispatch_action,
+    )
+    .optional()
+}
+
+fn record_action_event(
+    conn: &Connection,
+    action: &DispatchAction,
+    event_type: &str,
+    status: Option<&str>,
+    reason_code: Option<&str>,
+    data: Value,
+) -> Result<()> {
+    events::record_event_tx(
+        conn,
+        &NewEvent {
+            event_type,
+            tenant_id: &action.tenant_id,
+            workload_id: &action.workload_id,
+            action_id: Some(&action.action_id),
+            action_type: Some(&action.action_type),
+            status,
+            reason_code,
+            data,
+        },
+        now_unix_seconds(),
+    )?;
+    Ok(())
+}
+
 fn set_action_status(
     conn: &Connection,
     action_id: &str,
@@ -982,48 +2517,170 @@ fn set_action_status(
     Ok(())
 }
 
+/// Retry `action` after its failed attempt `attempt_count` per `policy`,
+/// waiting at least `min_delay_seconds`, or dead-letter it once the policy
+/// is exhausted. Every failed attempt lands in the attempt history.
+#[allow(clippy::too_many_arguments)]
 fn schedule_retry(
     conn: &Connection,
-    action_id: &str,
+    action: &DispatchAction,
+    policy: &RetryPolicy,
     attempt_count: u32,
+    min_delay_seconds: i64,
     reason_code: &str,
     reason_message: &str,
 ) -> Result<()> {
     let now = now_unix_seconds();
-    let next = now + (2_i64.pow(attempt_count.min(6)));
-    conn.execute(
+    let decision = policy.next(
+        attempt_count,
+        now - action.effective_at,
+        min_delay_seconds,
+        &action.action_id,
+    );
+    let delay_seconds = match decision {
+        RetryDecision::Retry { delay_seconds } => delay_seconds,
+        RetryDecision::DeadLetter { exhausted } => {
+            return dead_letter(
+                conn,
+                action,
+                attempt_count,
+                exhausted,
+                reason_code,
+                reason_message,
+            );
+        }
+    };
+    let updated = conn.execute(
         "UPDATE orchestrator_action
          SET attempt_count = ?1, next_retry_at = ?2, reason_code = ?3, reason_message = ?4, updated_at = ?5
-         WHERE action_id = ?6",
-        params![attempt_count, next, reason_code, reason_message, now, action_id],
+         WHERE action_id = ?6 AND status = ?7",
+        params![
+            attempt_count,
+            now + delay_seconds,
+            reason_code,
+            reason_message,
+            now,
+            action.action_id,
+            action.status
+        ],
+    )?;
+    if updated == 0 {
+        return Ok(());
+    }
+    retry::record_attempt_tx(
+        conn,
+        &ActionAttempt {
+            action_id: action.action_id.clone(),
+            attempt: attempt_count,
+            status: action.status.clone(),
+            reason_code: reason_code.to_string(),
+            reason_message: Some(reason_message.to_string()),
+            attempted_at: now,
+            next_retry_at: Some(now + delay_seconds),
+        },
+    )?;
+    metrics::action_retried(&action.action_type, reason_code);
+    record_action_event(
+        conn,
+        action,
+        events::EVENT_ACTION_RETRIED,
+        Some(&action.status),
+        Some(reason_code),
+        json!({
+            "attempt": attempt_count,
+            "next_retry_at": now + delay_seconds,
+            "reason_message": reason_message
+        }),
+    )
+}
+
+fn dead_letter(
+    conn: &Connection,
+    action: &DispatchAction,
+    attempt_count: u32,
+    exhausted: &str,
+    reason_code: &str,
+    reason_message: &str,
+) -> Result<()> {
+    let now = now_unix_seconds();
+    let updated = conn.execute(
+        "UPDATE orchestrator_action SET attempt_count = ?1 WHERE action_id = ?2 AND status = ?3",
+        params![attempt_count, action.action_id, action.status],
+    )?;
+    if updated == 0 {
+        return Ok(());
+    }
+    retry::record_attempt_tx(
+        conn,
+        &ActionAttempt {
+            action_id: action.action_id.clone(),
+            attempt: attempt_count,
+            status: action.status.clone(),
+            reason_code: reason_code.to_string(),
+            reason_message: Some(reason_message.to_string()),
+            attempted_at: now,
+            next_retry_at: None,
+        },
+    )?;
+    mark_terminal(
+        conn,
+        action,
+        DEAD_LETTER,
+        Some(RC_ORCH_RETRIES_EXHAUSTED),
+        Some(&format!(
+            "Gave up after {} attempts ({} exhausted); last failure {}: {}",
+            attempt_count, exhausted, reason_code, reason_message
+        )),
     )?;
     Ok(())
 }
 
+/// Returns `false` without writing if the action left the status it was
+/// loaded with, e.g. an operator cancelled it mid-dispatch.
 fn mark_terminal(
     conn: &Connection,
     action: &DispatchAction,
     status: &str,
     reason_code: Option<&str>,
     reason_message: Option<&str>,
-) -> Result<()> {
+) -> Result<bool> {
     let now = now_unix_seconds();
     let latency = (now - action.created_at) * 1000;
-    conn.execute(
+    let updated = conn.execute(
         "UPDATE orchestrator_action
          SET status = ?1, terminal_status = ?1, reason_code = ?2, reason_message = ?3, terminal_at = ?4, total_latency_ms = ?5, updated_at = ?4
-         WHERE action_id = ?6",
-        params![status, reason_code, reason_message, now, latency, action.action_id],
+         WHERE action_id = ?6 AND status = ?7",
+        params![
+            status,
+            reason_code,
+            reason_message,
+            now,
+            latency,
+            action.action_id,
+            action.status
+        ],
     )?;
-    Ok(())
+    if updated == 0 {
+        return Ok(false);
+    }
+    metrics::action_terminal(&action.action_type, status, reason_code, latency);
+    record_action_event(
+        conn,
+        action,
+        events::EVENT_ACTION_TERMINAL,
+        Some(status),
+        reason_code,
+        json!({ "total_latency_ms": latency, "reason_message": reason_message }),
+    )?;
+    Ok(true)
 }
 
-fn enqueue_rollback(conn: &Connection, action: &DispatchAction) -> Result<()> {
+fn enqueue_rollback(conn: &Connection, action: &DispatchAction, window_seconds: u64) -> Result<()> {
     let Some(rollback_payload) = &action.rollback_action_json else {
         return Ok(());
     };
     let now = now_unix_seconds();
-    let window_start = decision_window_start(now, FAST_LOOP_SECONDS as i64);
+    let window_start = decision_window_start(now, window_seconds as i64);
     enqueue_action(
         conn,
         &action.tenant_id,
@@ -1042,7 +2699,14 @@ fn enqueue_rollback(conn: &Connection, action: &DispatchAction) -> Result<()> {
         Some(RC_ORCH_ROLLBACK_TRIGGERED),
         Some("Rollback action enqueued"),
     )?;
-    Ok(())
+    record_action_event(
+        conn,
+        action,
+        events::EVENT_ACTION_ROLLED_BACK,
+        Some("failed"),
+        Some(RC_ORCH_ROLLBACK_TRIGGERED),
+        json!({ "rollback_payload": rollback_payload }),
+    )
 }
 
 fn workload_ownership_valid(conn: &Connection, tenant_id: &str, workload_id: &str) -> Result<bool> {
@@ -1056,123 +2720,41 @@ fn workload_ownership_valid(conn: &Connection, tenant_id: &str, workload_id: &st
     Ok(exists.is_some())
 }
 
-async fn dispatch_control_operation(
-    http: &Client,
-    cfg: &ExecutionConfig,
-    action: &DispatchAction,
-    path: String,
-    payload: Value,
-) -> Result<RuntimeOperation> {
-    let Some(base) = &cfg.control_base_url else {
-        anyhow::bail!("{}", RC_ORCH_DEPENDENCY_UNAVAILABLE);
-    };
-    let url = format!("{base}{path}");
-    let mut req = http
-        .post(url)
-        .header("Idempotency-Key", action.idempotency_key.clone())
-        .header("X-Tenant-Id", action.tenant_id.clone())
-        .header("X-Orch-Action-Id", action.action_id.clone())
-        .json(&payload);
-    if let Some(api_key) = &cfg.control_api_key {
-        req = req.header("X-Api-Key", api_key);
-    }
-    let resp = req.send().await.context("runtime request failed")?;
-    parse_runtime_response(resp.status(), resp.text().await.unwrap_or_default())
-}
-
-async fn poll_control_operation(
-    http: &Client,
-    cfg: &ExecutionConfig,
-    action: &DispatchAction,
-    operation_id: &str,
-) -> Result<RuntimeOperation> {
-    let Some(base) = &cfg.control_base_url else {
-        anyhow::bail!("{}", RC_ORCH_DEPENDENCY_UNAVAILABLE);
-    };
-    let url = format!("{base}/api/elasticity/control/operations/{operation_id}");
-    let mut req = http
-        .get(url)
-        .header("X-Tenant-Id", action.tenant_id.clone())
-        .header("X-Orch-Action-Id", action.action_id.clone());
-    if let Some(api_key) = &cfg.control_api_key {
-        req = req.header("X-Api-Key", api_key);
-    }
-    let resp = req.send().await.context("runtime poll failed")?;
-    parse_runtime_response(resp.status(), resp.text().await.unwrap_or_default())
-}
-
-fn parse_runtime_response(status: StatusCode, body: String) -> Result<RuntimeOperation> {
-    let value: Value = serde_json::from_str(&body).unwrap_or_else(|_| json!({}));
-    let reason_code = value
-        .get("reason_code")
-        .and_then(Value::as_str)
-        .map(ToString::to_string);
-    let reason_message = value
-        .get("reason_message")
-        .and_then(Value::as_str)
-        .map(ToString::to_string);
-    let retryable = value.get("retryable").and_then(Value::as_bool);
-
-    if status.is_server_error() {
-        anyhow::bail!("{}|retryable=true|{}", RC_ORCH_DEPENDENCY_UNAVAILABLE, body);
-    }
-
-    let op = RuntimeOperation {
-        operation_id: value
-            .get("operation_id")
-            .and_then(Value::as_str)
-            .unwrap_or_default()
-            .to_string(),
-        operation_type: value
-            .get("operation_type")
-            .and_then(Value::as_str)
-            .map(ToString::to_string),
-        status: value
-            .get("status")
-            .and_then(Value::as_str)
-            .unwrap_or("accepted")
-            .to_string(),
-        reason_code,
-        reason_message,
-    };
-    if status.is_client_error() {
-        if op.reason_code.as_deref() == Some(RC_IDEMPOTENCY_REPLAY) && !op.operation_id.is_empty() {
-            return Ok(op);
-        }
-        let code = normalize_reason_code(op.reason_code.as_deref());
-        anyhow::bail!(
-            "{}|retryable={}|{}",
-            code,
-            retryable
-                .map(|v| if v { "true" } else { "false" })
-                .unwrap_or("unknown"),
-            op.reason_message
-                .clone()
-                .unwrap_or_else(|| "client_error".to_string())
-        );
+/// Whether a failed executor call counts against the breaker. Rejections
+/// the dependency answered (bad argument, missing target, ...) show it is up.
+fn dependency_failed<T>(result: &Result<T>) -> bool {
+    match result {
+        Ok(_) => false,
+        Err(e) => parse_dispatch_error(&e.to_string()).0 == RC_ORCH_DEPENDENCY_UNAVAILABLE,
     }
-    Ok(op)
 }
 
 async fn process_action(
     db: &DbPool,
-    http: &Client,
-    cfg: &ExecutionConfig,
+    executors: &ExecutorRegistry,
+    breaker: &CircuitBreaker,
+    cfg: &OrchestratorConfig,
     action: DispatchAction,
 ) -> Result<()> {
     let now = now_unix_seconds();
-    let ttl_expired = now > action.effective_at + action.ttl_seconds as i64;
-    if ttl_expired {
+    if action.ttl_expired(now) {
+        // A rollback would go to the same unhealthy dependency; expire the
+        // action once it has recovered instead.
+        if !breaker.is_closed() {
+            return Ok(());
+        }
         let db = db.clone();
-        execute_async(&db, move |conn| {
-            mark_terminal(
+        let window_seconds = cfg.fast_loop_seconds;
+        events::execute_logged(&db, move |conn| {
+            if mark_terminal(
                 conn,
                 &action,
                 "failed",
                 Some(RC_ORCH_TTL_EXPIRED),
                 Some("TTL expired before completion"),
-            )?;
-            enqueue_rollback(conn, &action)?;
+            )? {
+                enqueue_rollback(conn, &action, window_seconds)?;
+            }
             Ok(())
         })
         .await?;
@@ -1183,24 +2765,44 @@ async fn process_action(
         let Some(op_id) = action.runtime_operation_id.clone() else {
             return Ok(());
         };
-        match poll_control_operation(http, cfg, &action, &op_id).await {
+        let polled = async {
+            let (executor, payload) =
+                executors.resolve(&action.action_type, &action.payload_json)?;
+            let polled = executor.poll(&action.request(&payload), &op_id).await;
+            breaker.record(&cfg.breaker, dependency_failed(&polled), now_unix_seconds());
+            polled
+        }
+        .await;
+        match polled {
             Ok(op) => {
                 let db = db.clone();
-                execute_async(&db, move |conn| {
+                events::execute_logged(&db, move |conn| {
                     let now = now_unix_seconds();
                     if is_runtime_non_terminal(&op.status) {
-                        conn.execute(
+                        let updated = conn.execute(
                             "UPDATE orchestrator_action
                              SET status = ?1, reason_code = ?2, reason_message = ?3, updated_at = ?4
-                             WHERE action_id = ?5",
+                             WHERE action_id = ?5 AND status = ?6",
                             params![
                                 op.status,
                                 op.reason_code,
                                 op.reason_message,
                                 now,
-                                action.action_id
+                                action.action_id,
+                                action.status
                             ],
                         )?;
+                        if updated == 0 {
+                            return Ok(());
+                        }
+                        record_action_event(
+                            conn,
+                            &action,
+                            events::EVENT_ACTION_POLLED,
+                            Some(&op.status),
+                            op.reason_code.as_deref(),
+                            json!({ "operation_id": op.operation_id }),
+                        )?;
                     } else if is_runtime_terminal_success(&op.status) {
                         mark_terminal(
                             conn,
@@ -1225,12 +2827,15 @@ async fn process_action(
             Err(err) => {
                 let msg = err.to_string();
                 let attempt = action.attempt_count + 1;
+                let policy = cfg.retry.policy(&action.action_type).clone();
                 let db = db.clone();
-                execute_async(&db, move |conn| {
+                events::execute_logged(&db, move |conn| {
                     schedule_retry(
                         conn,
-                        &action.action_id,
+                        &action,
+                        &policy,
                         attempt,
+                        0,
                         RC_ORCH_DEPENDENCY_UNAVAILABLE,
                         &msg,
                     )?;
@@ -1244,7 +2849,7 @@ async fn process_action(
 
     let db = db.clone();
     let action_for_validate = action.clone();
-    let ownership_ok = execute_async(&db, move |conn| {
+    let ownership_ok = events::execute_logged(&db, move |conn| {
         let valid = workload_ownership_valid(
             conn,
             &action_for_validate.tenant_id,
@@ -1266,152 +2871,29 @@ async fn process_action(
         return Ok(());
     }
 
-    let dispatch_result = match action.action_type.as_str() {
-        "SetPoolTarget" => dispatch_control_operation(
-            http,
-            cfg,
-            &action,
-            format!(
-                "/api/elasticity/control/functions/{}/pool-target",
-                action
-                    .payload_json
-                    .get("function_id")
-                    .and_then(Value::as_str)
-                    .map(str::trim)
-                    .filter(|v| !v.is_empty())
-                    .ok_or_else(|| anyhow::anyhow!(RC_INVALID_ARGUMENT))?
-            ),
-            json!({
-                "min_instances": action.payload_json.get("min_instances").and_then(Value::as_u64).ok_or_else(|| anyhow::anyhow!(RC_INVALID_ARGUMENT))?,
-                "max_instances": action.payload_json.get("max_instances").and_then(Value::as_u64).ok_or_else(|| anyhow::anyhow!(RC_INVALID_ARGUMENT))?
-            }),
-        )
-        .await
-        .map(Some),
-        "SetBurstPolicy" => {
-            let container_id = action
-                .payload_json
-                .get("container_id")
-                .and_then(Value::as_str)
-                .ok_or_else(|| anyhow::anyhow!(RC_INVALID_ARGUMENT))?;
-            let memory_limit_mb = action
-                .payload_json
-                .get("memory_limit_mb")
-                .and_then(Value::as_u64)
-                .ok_or_else(|| anyhow::anyhow!(RC_INVALID_ARGUMENT))?;
-            let cpu_limit_percent = action
-                .payload_json
-                .get("cpu_limit_percent")
-                .and_then(Value::as_u64)
-                .ok_or_else(|| anyhow::anyhow!(RC_INVALID_ARGUMENT))?;
-            dispatch_control_operation(
-                http,
-                cfg,
-                &action,
-                format!("/api/elasticity/control/containers/{container_id}/resize"),
-                json!({
-                    "memory_limit_mb": memory_limit_mb,
-                    "cpu_limit_percent": cpu_limit_percent
-                }),
-            )
-            .await
-            .map(Some)
-        }
-        "SetPlacementPreference" => dispatch_control_operation(
-            http,
-            cfg,
-            &action,
-            format!(
-                "/api/elasticity/control/workloads/{}/placement-preference",
-                action
-                    .payload_json
-                    .get("workload_id")
-                    .and_then(Value::as_str)
-                    .map(str::trim)
-                    .filter(|v| !v.is_empty())
-                    .ok_or_else(|| anyhow::anyhow!(RC_INVALID_ARGUMENT))?
-            ),
-            json!({
-                "node_group": action
-                    .payload_json
-                    .get("node_group")
-                    .and_then(Value::as_str)
-                    .map(str::trim)
-                    .filter(|v| !v.is_empty())
-                    .ok_or_else(|| anyhow::anyhow!(RC_INVALID_ARGUMENT))?,
-                "anti_affinity": action
-                    .payload_json
-                    .get("anti_affinity")
-                    .and_then(Value::as_bool)
-                    .unwrap_or(false)
-            }),
-        )
-        .await
-        .map(Some),
-        "ScaleNodeGroupUp" | "ScaleNodeGroupDown" => {
-            dispatch_control_operation(
-                http,
-                cfg,
-                &action,
-                format!(
-                    "/api/elasticity/control/node-groups/{}/scale",
-                    action
-                        .payload_json
-                        .get("node_group")
-                        .and_then(Value::as_str)
-                        .map(str::trim)
-                        .filter(|v| !v.is_empty())
-                        .ok_or_else(|| anyhow::anyhow!(RC_INVALID_ARGUMENT))?
-                ),
-                json!({
-                    "delta_units": action
-                        .payload_json
-                        .get("delta_units")
-                        .and_then(Value::as_i64)
-                        .ok_or_else(|| anyhow::anyhow!(RC_INVALID_ARGUMENT))?
-                }),
-            )
-            .await
-            .map(Some)
-        }
-        "RollbackAction" => dispatch_control_operation(
-            http,
-            cfg,
-            &action,
-            format!(
-                "/api/elasticity/control/actions/{}/rollback",
-                action
-                    .payload_json
-                    .get("target_action_id")
-                    .and_then(Value::as_str)
-                    .map(str::trim)
-                    .filter(|v| !v.is_empty())
-                    .unwrap_or(action.action_id.as_str())
-            ),
-            json!({
-                "target_action_id": action.payload_json.get("target_action_id").and_then(Value::as_str),
-                "target_operation_id": action.payload_json.get("target_operation_id").and_then(Value::as_str),
-                "reason_code": action.payload_json.get("reason_code").and_then(Value::as_str),
-                "reason_message": action.payload_json.get("reason_message").and_then(Value::as_str),
-                "payload": action.payload_json.get("payload").cloned()
-            }),
-        )
-        .await
-        .map(Some),
-        _ => Err(anyhow::anyhow!(RC_ORCH_ACTION_UNSUPPORTED)),
-    };
+    let dispatch_result = async {
+        let (executor, payload) = executors.resolve(&action.action_type, &action.payload_json)?;
+        let dispatched = executor.dispatch(&action.request(&payload)).await;
+        breaker.record(
+            &cfg.breaker,
+            dependency_failed(&dispatched),
+            now_unix_seconds(),
+        );
+        dispatched
+    }
+    .await;
 
     match dispatch_result {
         Ok(Some(op)) => {
             let db = db.clone();
-            execute_async(&db, move |conn| {
+            events::execute_logged(&db, move |conn| {
                 let now = now_unix_seconds();
                 if is_runtime_terminal_success(&op.status) || is_runtime_terminal_failure(&op.status) {
                     let latency = (now - action.created_at) * 1000;
-                    conn.execute(
+                    let updated = conn.execute(
                         "UPDATE orchestrator_action
                          SET outbound_requested_at = ?1, runtime_operation_id = ?2, runtime_operation_type = ?3, status = ?4, terminal_status = ?4, reason_code = ?5, reason_message = ?6, terminal_at = ?1, total_latency_ms = ?7, attempt_count = attempt_count + 1, next_retry_at = ?1, updated_at = ?1
-                         WHERE action_id = ?8",
+                         WHERE action_id = ?8 AND status = ?9",
                         params![
                             now,
                             op.operation_id,
@@ -1420,14 +2902,36 @@ async fn process_action(
                             op.reason_code,
                             op.reason_message,
                             latency,
-                            action.action_id
+                            action.action_id,
+                            action.status
                         ],
                     )?;
+                    if updated == 0 {
+                        return Ok(());
+                    }
+                    metrics::action_terminal(
+                        &action.action_type,
+                        &op.status,
+                        op.reason_code.as_deref(),
+                        latency,
+                    );
+                    record_action_event(
+                        conn,
+                        &action,
+                        events::EVENT_ACTION_TERMINAL,
+                        Some(&op.status),
+                        op.reason_code.as_deref(),
+                        json!({
+                            "operation_id": op.operation_id,
+                            "total_latency_ms": latency,
+                            "reason_message": op.reason_message
+                        }),
+                    )?;
                 } else {
-                    conn.execute(
+                    let updated = conn.execute(
                         "UPDATE orchestrator_action
                          SET outbound_requested_at = ?1, runtime_operation_id = ?2, runtime_operation_type = ?3, status = ?4, reason_code = ?5, reason_message = ?6, attempt_count = attempt_count + 1, next_retry_at = ?1, updated_at = ?1
-                         WHERE action_id = ?7",
+                         WHERE action_id = ?7 AND status = ?8",
                         params![
                             now,
                             op.operation_id,
@@ -1435,9 +2939,24 @@ async fn process_action(
                             op.status,
                             op.reason_code,
                             op.reason_message,
-                            action.action_id
+                            action.action_id,
+                            action.status
                         ],
                     )?;
+                    if updated == 0 {
+                        return Ok(());
+                    }
+                    record_action_event(
+                        conn,
+                        &action,
+                        events::EVENT_ACTION_DISPATCHED,
+                        Some(&op.status),
+                        op.reason_code.as_deref(),
+                        json!({
+                            "operation_id": op.operation_id,
+                            "operation_type": op.operation_type
+                        }),
+                    )?;
                 }
                 Ok(())
             })
@@ -1445,7 +2964,7 @@ async fn process_action(
         }
         Ok(None) => {
             let db = db.clone();
-            execute_async(&db, move |conn| {
+            events::execute_logged(&db, move |conn| {
                 mark_terminal(conn, &action, RUNTIME_SUCCESS, None, None)?;
                 Ok(())
             })
@@ -1456,7 +2975,9 @@ async fn process_action(
             let (reason_code_owned, retryable_hint, detail) = parse_dispatch_error(&message);
 
             let db = db.clone();
-            execute_async(&db, move |conn| {
+            let pressure_retry = cfg.resource_pressure_retry_seconds as i64;
+            let policy = cfg.retry.policy(&action.action_type).clone();
+            events::execute_logged(&db, move |conn| {
                 let reason_code = reason_code_owned.as_str();
                 if action.action_type == "SetPoolTarget" {
                     let non_retry_terminal = reason_code == RC_INVALID_ARGUMENT
@@ -1467,40 +2988,39 @@ async fn process_action(
                         mark_terminal(conn, &action, "failed", Some(reason_code), Some(&detail))?;
                     } else if reason_code == RC_RESOURCE_PRESSURE && retryable_hint == Some(true) {
                         let attempt = action.attempt_count + 1;
-                        let now = now_unix_seconds();
-                        conn.execute(
-                            "UPDATE orchestrator_action
-                             SET attempt_count = ?1, next_retry_at = ?2, reason_code = ?3, reason_message = ?4, updated_at = ?5
-                             WHERE action_id = ?6",
-                            params![
-                                attempt,
-                                now + 15,
-                                reason_code,
-                                detail,
-                                now,
-                                action.action_id
-                            ],
+                        schedule_retry(
+                            conn,
+                            &action,
+                            &policy,
+                            attempt,
+                            pressure_retry,
+                            reason_code,
+                            &detail,
                         )?;
                     } else if retryable_hint == Some(true) {
                         let attempt = action.attempt_count + 1;
-                        schedule_retry(conn, &action.action_id, attempt, reason_code, &detail)?;
+                        schedule_retry(conn, &action, &policy, attempt, 0, reason_code, &detail)?;
                     } else {
                         mark_terminal(conn, &action, "failed", Some(reason_code), Some(&detail))?;
                     }
-                } else if reason_code == RC_INVALID_ARGUMENT || reason_code == RC_ORCH_ACTION_UNSUPPORTED {
+                } else if reason_code == RC_INVALID_ARGUMENT
+                    || reason_code == RC_ORCH_ACTION_UNSUPPORTED
+                {
                     mark_terminal(conn, &action, "failed", Some(reason_code), Some(&detail))?;
                 } else if reason_code == RC_RESOURCE_PRESSURE {
                     let attempt = action.attempt_count + 1;
-                    let now = now_unix_seconds();
-                    conn.execute(
-                        "UPDATE orchestrator_action
-                         SET attempt_count = ?1, next_retry_at = ?2, reason_code = ?3, reason_message = ?4, updated_at = ?5
-                         WHERE action_id = ?6",
-                        params![attempt, now + 15, reason_code, detail, now, action.action_id],
+                    schedule_retry(
+                        conn,
+                        &action,
+                        &policy,
+                        attempt,
+                        pressure_retry,
+                        reason_code,
+                        &detail,
                     )?;
                 } else {
                     let attempt = action.attempt_count + 1;
-                    schedule_retry(conn, &action.action_id, attempt, reason_code, &detail)?;
+                    schedule_retry(conn, &action, &policy, attempt, 0, reason_code, &detail)?;
                 }
                 Ok(())
             })
@@ -1510,74 +3030,146 @@ async fn process_action(
     Ok(())
 }
 
-pub async fn run_dispatch_cycle(db: &DbPool, cfg: &ExecutionConfig) -> Result<()> {
+pub async fn run_dispatch_cycle(
+    db: &DbPool,
+    executors: &ExecutorRegistry,
+    dispatcher: &Dispatcher,
+    breaker: &CircuitBreaker,
+    cfg: &Arc<OrchestratorConfig>,
+) -> Result<()> {
+    metrics::observe_loop(
+        metrics::LOOP_DISPATCH,
+        dispatch_cycle(db, executors, dispatcher, breaker, cfg),
+    )
+    .await
+}
+
+/// Admit due actions and start each in its own task. The cycle doesn't wait
+/// for them; an action still running next cycle keeps its slot.
+///
+/// Nothing happens while the breaker is open. Half-open, a single action
+/// that hasn't expired goes out as the probe.
+async fn dispatch_cycle(
+    db: &DbPool,
+    executors: &ExecutorRegistry,
+    dispatcher: &Dispatcher,
+    breaker: &CircuitBreaker,
+    cfg: &Arc<OrchestratorConfig>,
+) -> Result<()> {
     let now = now_unix_seconds();
+    let mut probe = match breaker.gate(&cfg.breaker, now) {
+        Gate::Open => return Ok(()),
+        Gate::Closed => None,
+        Gate::Probe(probe) => Some(probe),
+    };
+    let limit = cfg.max_dispatch_batch;
     let candidates = {
         let db = db.clone();
-        execute_async(&db, move |conn| load_dispatch_candidates(conn, now)).await?
+        execute_async(&db, move |conn| load_dispatch_candidates(conn, now, limit)).await?
     };
-    if candidates.is_empty() {
-        return Ok(());
-    }
-    let http = Client::new();
-    for action in candidates {
-        process_action(db, &http, cfg, action).await?;
+    let limits = DispatchLimits {
+        max_in_flight: cfg.max_dispatch_in_flight,
+        per_tenant: cfg.max_dispatch_in_flight_per_tenant,
+        per_target: cfg.max_dispatch_in_flight_per_target,
+    };
+    let admitted = if probe.is_some() {
+        candidates
+            .into_iter()
+            .filter(|a| !a.ttl_expired(now))
+            .find_map(|a| {
+                dispatcher
+                    .admit(vec![a], &limits, DispatchAction::slot_key)
+                    .pop()
+            })
+            .into_iter()
+            .collect()
+    } else {
+        dispatcher.admit(candidates, &limits, DispatchAction::slot_key)
+    };
+    for (action, slot) in admitted {
+        let db = db.clone();
+        let executors = executors.clone();
+        let breaker = breaker.clone();
+        let cfg = cfg.clone();
+        let probe = probe.take();
+        tokio::spawn(async move {
+            let action_id = action.action_id.clone();
+            if let Err(e) = process_action(&db, &executors, &breaker, &cfg, action).await {
+                tracing::error!("Dispatch of action {} failed: {}", action_id, e);
+            }
+            drop((slot, probe));
+        });
     }
     Ok(())
 }
 
-pub async fn start_loops(db: DbPool, config: ExecutionConfig) -> Result<()> {
+/// Spawn the fast, slow and dispatch loops. Every instance runs the timers,
+/// but a tick only does work while `leader` holds the orchestrator lease.
+pub async fn start_loops(
+    db: DbPool,
+    executors: ExecutorRegistry,
+    leader: LeaderElection,
+    breaker: CircuitBreaker,
+    config: ConfigHandle,
+) -> Result<()> {
+    // Each loop re-reads the config every tick, so a reload also changes
+    // its interval.
     let fast_db = db.clone();
+    let fast_leader = leader.clone();
+    let fast_config = config.clone();
     tokio::spawn(async move {
-        let mut ticker = tokio::time::interval(std::time::Duration::from_secs(FAST_LOOP_SECONDS));
         loop {
-            ticker.tick().await;
-            if let Err(e) = run_fast_loop(&fast_db).await {
-                tracing::error!("Fast loop failed: {}", e);
+            let cfg = fast_config.current();
+            if fast_leader.is_leader() {
+                if let Err(e) = run_fast_loop(&fast_db, &cfg).await {
+                    tracing::error!("Fast loop failed: {}", e);
+                }
             }
+            tokio::time::sleep(Duration::from_secs(cfg.fast_loop_seconds)).await;
         }
     });
 
     let slow_db = db.clone();
+    let slow_leader = leader.clone();
+    let slow_config = config.clone();
     tokio::spawn(async move {
-        let mut ticker = tokio::time::interval(std::time::Duration::from_secs(SLOW_LOOP_SECONDS));
         loop {
-            ticker.tick().await;
-            if let Err(e) = run_slow_loop(&slow_db).await {
-                tracing::error!("Slow loop failed: {}", e);
+            let cfg = slow_config.current();
+            if slow_leader.is_leader() {
+                if let Err(e) = run_slow_loop(&slow_db, &cfg).await {
+                    tracing::error!("Slow loop failed: {}", e);
+                }
             }
+            tokio::time::sleep(Duration::from_secs(cfg.slow_loop_seconds)).await;
         }
     });
 
+    let cfg = config.current();
+    info!(
+        "Orchestrator loops started (fast={}s, slow={}s, dispatch={}s)",
+        cfg.fast_loop_seconds, cfg.slow_loop_seconds, cfg.dispatch_loop_seconds
+    );
+    let dispatcher = Dispatcher::new();
     tokio::spawn(async move {
-        let mut ticker =
-            tokio::time::interval(std::time::Duration::from_secs(DISPATCH_LOOP_SECONDS));
         loop {
-            ticker.tick().await;
-            if let Err(e) = run_dispatch_cycle(&db, &config).await {
-                tracing::error!("Dispatch loop failed: {}", e);
+            let cfg = config.current();
+            if leader.is_leader() {
+                if let Err(e) =
+                    run_dispatch_cycle(&db, &executors, &dispatcher, &breaker, &cfg).await
+                {
+                    tracing::error!("Dispatch loop failed: {}", e);
+                }
             }
+            tokio::time::sleep(Duration::from_secs(cfg.dispatch_loop_seconds)).await;
         }
     });
-
-    info!(
-        "Orchestrator loops started (fast={}s, slow={}s, dispatch={}s)",
-        FAST_LOOP_SECONDS, SLOW_LOOP_SECONDS, DISPATCH_LOOP_SECONDS
-    );
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rusqlite::Connection;
-
-    fn setup_conn() -> Connection {
-        let conn = Connection::open_in_memory().expect("in-memory sqlite");
-        conn.execute_batch(include_str!("../../migrations/003_orchestrator.sql"))
-            .expect("migrations");
-        conn
-    }
+    use crate::types::{PidStrategy, SimulatedPolicy};
 
     #[test]
     fn idempotency_key_is_deterministic() {
@@ -1622,7 +3214,7 @@ mod tests {
 
     #[test]
     fn duplicate_dispatch_key_collapses_to_single_action() {
-        let conn = setup_conn();
+        let conn = crate::db::test_conn();
         enqueue_action(
             &conn,
             "tenant-a",
@@ -1649,28 +3241,1129 @@ mod tests {
         .expect("second enqueue");
 
         let count: i64 = conn
-            .query_row("SELECT COUNT(*) FROM orchestrator_action", [], |row| {
-                row.get(0)
-            })
+            .query_row(
+                "SELECT COUNT(*) FROM orchestrator_action",
+                params![],
+                |row| row.get(0),
+            )
             .expect("count");
         assert_eq!(count, 1);
+        let enqueued = events::list_events_tx(&conn, &Default::default(), 0, 10).expect("events");
+        assert_eq!(enqueued.len(), 1);
+        assert_eq!(enqueued[0].event_type, events::EVENT_ACTION_ENQUEUED);
+    }
+
+    fn valid_policy() -> WorkloadPolicyRequest {
+        WorkloadPolicyRequest {
+            runtime_function_id: "fn-1".to_string(),
+            max_concurrency: 2,
+            hard_quota: 10,
+            soft_burst: 2,
+            absolute_limit: 20,
+            priority: 5,
+            cooldown_seconds: 30,
+            hysteresis_pct: 10.0,
+            burst_cpu_cap: 1.5,
+            burst_mem_mb: 512,
+            burst_ttl_seconds: 60,
+            target_container_ids: vec!["c1".to_string()],
+            scale_to_zero_idle_seconds: None,
+            scaling_strategy: ScalingStrategy::default(),
+            warm_pool: None,
+        }
     }
 
     #[test]
-    fn replay_reason_code_is_treated_as_success_path() {
-        let op = parse_runtime_response(
-            StatusCode::CONFLICT,
-            json!({
-                "operation_id": "op_123",
-                "operation_type": "elasticity.resize_container",
-                "status": "accepted",
-                "reason_code": RC_IDEMPOTENCY_REPLAY,
-                "reason_message": "replayed"
+    fn policy_validation_reports_every_bad_field() {
+        assert!(validate_workload_policy(&valid_policy()).is_ok());
+
+        let mut req = valid_policy();
+        req.runtime_function_id = " ".to_string();
+        req.hard_quota = 1;
+        req.hysteresis_pct = 150.0;
+        req.priority = MAX_POLICY_PRIORITY + 1;
+        let err = validate_workload_policy(&req).expect_err("invalid policy");
+        let Some(ServiceError::Validation(fields)) = err.downcast_ref::<ServiceError>() else {
+            panic!("expected validation error, got {err}");
+        };
+        let names: Vec<&str> = fields.iter().map(|f| f.field.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "runtime_function_id",
+                "hard_quota",
+                "priority",
+                "hysteresis_pct"
+            ]
+        );
+    }
+
+    fn policy_row() -> WorkloadPolicyRow {
+        WorkloadPolicyRow {
+            runtime_function_id: "fn-1".to_string(),
+            max_concurrency: 2,
+            hard_quota: 10,
+            soft_burst: 2,
+            absolute_limit: 20,
+            priority: 5,
+            cooldown_seconds: 30,
+            hysteresis_pct: 10.0,
+            burst_cpu_cap: 1.5,
+            burst_mem_mb: 512,
+            burst_ttl_seconds: 60,
+            target_container_ids: Vec::new(),
+            scale_to_zero_idle_seconds: None,
+            scaling_strategy: ScalingStrategy::default(),
+            warm_pool: None,
+        }
+    }
+
+    fn heuristic(obs: &WorkloadObservation) -> Recommendation {
+        scaling::recommend(
+            &ScalingStrategy::Heuristic,
+            obs,
+            None,
+            None,
+            StrategyState::default(),
+            0,
+        )
+    }
+
+    fn observation(active_compute_units: u32, queue_depth: u32) -> WorkloadObservation {
+        WorkloadObservation {
+            queue_depth,
+            invoke_p95_ms: 900,
+            active_compute_units,
+            ..WorkloadObservation::fixture()
+        }
+    }
+
+    #[test]
+    fn decision_trace_keeps_every_condition_and_clamp() {
+        let slo = WorkloadSloRow {
+            p95_latency_ms: 500,
+            max_cold_start_pct: 5.0,
+            max_reject_pct: 1.0,
+            max_cost_per_compute_unit: 2.0,
+        };
+        let trace = evaluate_workload(
+            &observation(10, 50),
+            &policy_row(),
+            Some(&slo),
+            &[],
+            None,
+            &Tuning::default(),
+            &heuristic(&observation(10, 50)),
+            None,
+            100,
+        );
+
+        let codes: Vec<&str> = trace.conditions.iter().map(|c| c.code.as_str()).collect();
+        assert_eq!(codes, ["SLO_GUARD", "QUEUE_PRESSURE"]);
+        assert_eq!(trace.proposed.reason_code, "QUEUE_PRESSURE");
+        assert_eq!(trace.base_target, 15);
+        let clamps: Vec<(&str, i64, i64)> = trace
+            .clamps
+            .iter()
+            .map(|c| (c.rule.as_str(), c.before, c.after))
+            .collect();
+        assert_eq!(
+            clamps,
+            [
+                ("hard_quota_plus_soft_burst", 15, 12),
+                ("slo_soft_burst", 12, 14)
+            ]
+        );
+        assert_eq!(trace.proposed.target_concurrency, 14);
+        assert!(trace.applied);
+    }
+
+    #[test]
+    fn decision_trace_records_suppression() {
+        let obs = observation(8, 0);
+        let cooling = evaluate_workload(
+            &obs,
+            &policy_row(),
+            None,
+            &[],
+            Some(CurrentIntent {
+                target_concurrency: 2,
+                updated_at: 90,
+                idle_since: None,
+                pool_ready: [0; 3],
+                warm_pool: WarmPoolState::default(),
+            }),
+            &Tuning::default(),
+            &heuristic(&obs),
+            None,
+            100,
+        );
+        assert_eq!(cooling.suppressed_by.as_deref(), Some("cooldown"));
+        assert!(!cooling.applied);
+
+        let steady = evaluate_workload(
+            &obs,
+            &policy_row(),
+            None,
+            &[],
+            Some(CurrentIntent {
+                target_concurrency: 8,
+                updated_at: 0,
+                idle_since: None,
+                pool_ready: [0; 3],
+                warm_pool: WarmPoolState::default(),
+            }),
+            &Tuning::default(),
+            &heuristic(&obs),
+            None,
+            100,
+        );
+        assert_eq!(steady.suppressed_by.as_deref(), Some("hysteresis"));
+        assert_eq!(steady.current_target, Some(8));
+    }
+
+    #[test]
+    fn idle_workload_scales_to_zero_and_wakes_past_cooldown() {
+        let policy = WorkloadPolicyRow {
+            scale_to_zero_idle_seconds: Some(300),
+            ..policy_row()
+        };
+        let current = |target_concurrency, idle_since| {
+            Some(CurrentIntent {
+                target_concurrency,
+                updated_at: 0,
+                idle_since,
+                pool_ready: [0; 3],
+                warm_pool: WarmPoolState::default(),
             })
-            .to_string(),
+        };
+        let evaluate = |obs: &WorkloadObservation, current| {
+            evaluate_workload(
+                obs,
+                &policy,
+                None,
+                &[],
+                current,
+                &Tuning::default(),
+                &heuristic(obs),
+                None,
+                1000,
+            )
+        };
+
+        let not_yet = evaluate(&observation(0, 0), current(1, Some(800)));
+        assert_eq!(not_yet.proposed.target_concurrency, 2);
+
+        // Within hysteresis of the current target, but still applied.
+        let idle = evaluate(&observation(0, 0), current(1, Some(700)));
+        assert!(idle.applied);
+        assert_eq!(idle.proposed.reason_code, "SCALE_TO_ZERO");
+        let p = &idle.proposed;
+        assert_eq!(
+            (
+                p.target_concurrency,
+                p.pool_min_ready,
+                p.pool_target_ready,
+                p.pool_max_ready
+            ),
+            (0, 0, 0, 0)
+        );
+
+        // Without opting in the max_concurrency floor holds.
+        let opted_out = evaluate_workload(
+            &observation(0, 0),
+            &policy_row(),
+            None,
+            &[],
+            current(1, Some(0)),
+            &Tuning::default(),
+            &heuristic(&observation(0, 0)),
+            None,
+            1000,
+        );
+        assert_eq!(opted_out.proposed.target_concurrency, 2);
+
+        let woken = evaluate(
+            &observation(0, 3),
+            Some(CurrentIntent {
+                target_concurrency: 0,
+                updated_at: 995,
+                idle_since: None,
+                pool_ready: [0; 3],
+                warm_pool: WarmPoolState::default(),
+            }),
+        );
+        assert!(woken.applied);
+        assert_eq!(woken.proposed.reason_code, "WAKE_FROM_ZERO");
+        assert_eq!(woken.proposed.target_concurrency, 2);
+    }
+
+    #[test]
+    fn warm_pool_adjustment_shifts_ratios_within_bounds() {
+        let current = Some(CurrentIntent {
+            target_concurrency: 10,
+            updated_at: 0,
+            idle_since: None,
+            pool_ready: [0; 3],
+            warm_pool: WarmPoolState {
+                adjustment: 0.25,
+                idle_ticks: 0,
+            },
+        });
+        let evaluate = |policy: &WorkloadPolicyRow| {
+            let trace = evaluate_workload(
+                &observation(10, 0),
+                policy,
+                None,
+                &[],
+                current,
+                &Tuning::default(),
+                &heuristic(&observation(10, 0)),
+                None,
+                1000,
+            );
+            let p = &trace.proposed;
+            let pools = (p.pool_min_ready, p.pool_target_ready, p.pool_max_ready);
+            (pools, trace)
+        };
+
+        // Without opting in the stored adjustment is ignored.
+        let (pools, _) = evaluate(&policy_row());
+        assert_eq!(pools, (1, 2, 3));
+
+        let adaptive = WorkloadPolicyRow {
+            warm_pool: Some(AdaptiveWarmPool {
+                min_ready: 5,
+                max_ready: 5,
+                ..Default::default()
+            }),
+            ..policy_row()
+        };
+        let (pools, trace) = evaluate(&adaptive);
+        assert_eq!(pools, (5, 5, 5));
+        let clamps: Vec<(&str, i64, i64)> = trace
+            .clamps
+            .iter()
+            .map(|c| (c.rule.as_str(), c.before, c.after))
+            .collect();
+        assert_eq!(
+            clamps,
+            [
+                ("pool_min_ready_bounds", 4, 5),
+                ("pool_max_ready_bounds", 6, 5)
+            ]
+        );
+    }
+
+    #[test]
+    fn pool_only_resize_is_not_held_by_hysteresis() {
+        let current = |pool_ready| {
+            Some(CurrentIntent {
+                target_concurrency: 10,
+                updated_at: 0,
+                idle_since: None,
+                pool_ready,
+                warm_pool: WarmPoolState {
+                    adjustment: 0.25,
+                    idle_ticks: 0,
+                },
+            })
+        };
+        let adaptive = WorkloadPolicyRow {
+            warm_pool: Some(AdaptiveWarmPool::default()),
+            ..policy_row()
+        };
+        let evaluate = |policy: &WorkloadPolicyRow, current| {
+            evaluate_workload(
+                &observation(10, 0),
+                policy,
+                None,
+                &[],
+                current,
+                &Tuning::default(),
+                &heuristic(&observation(10, 0)),
+                None,
+                1000,
+            )
+        };
+
+        let resized = evaluate(&adaptive, current([1, 2, 3]));
+        assert!(resized.applied, "{:?}", resized.suppressed_by);
+        let p = &resized.proposed;
+        assert_eq!(
+            (
+                p.target_concurrency,
+                p.pool_min_ready,
+                p.pool_target_ready,
+                p.pool_max_ready
+            ),
+            (10, 4, 5, 6)
+        );
+
+        let settled = evaluate(&adaptive, current([4, 5, 6]));
+        assert_eq!(settled.suppressed_by.as_deref(), Some("hysteresis"));
+
+        // Without opting in, pool sizes follow the target and hysteresis
+        // holds as before.
+        let opted_out = evaluate(&policy_row(), current([0; 3]));
+        assert_eq!(opted_out.suppressed_by.as_deref(), Some("hysteresis"));
+    }
+
+    #[test]
+    fn saturated_node_group_is_shared_by_priority() {
+        let group = |used_units| NodeGroupObservation {
+            node_group: "g".to_string(),
+            cpu_pressure: 0.5,
+            mem_pressure: 0.5,
+            io_pressure: 0.1,
+            warm_ready: 0,
+            warm_hit_rate: 0.0,
+            capacity_units: 20,
+            used_units,
+        };
+        let demand = |priority, active_compute_units, desired| CapacityDemand {
+            node_group: "g",
+            priority,
+            active_compute_units,
+            desired,
+        };
+        // 2 units used outside these workloads leaves 18 for 8 + 12 + 4.
+        let demands = [demand(1, 6, 8), demand(9, 8, 12), demand(1, 2, 4)];
+        let ceilings = priority_ceilings(&demands, &[group(18)], 0.9);
+        let granted: Vec<Option<u32>> = ceilings
+            .iter()
+            .map(|c| c.as_ref().map(|p| p.ceiling))
+            .collect();
+        assert_eq!(granted, [Some(6), None, Some(1)]);
+        // Below the saturation threshold nobody is held back.
+        assert!(priority_ceilings(&demands, &[group(17)], 0.9)
+            .iter()
+            .all(Option::is_none));
+
+        let slo = WorkloadSloRow {
+            p95_latency_ms: 500,
+            max_cold_start_pct: 5.0,
+            max_reject_pct: 1.0,
+            max_cost_per_compute_unit: 2.0,
+        };
+        let trace = evaluate_workload(
+            &observation(8, 0),
+            &policy_row(),
+            Some(&slo),
+            &[],
+            None,
+            &Tuning::default(),
+            &heuristic(&observation(8, 0)),
+            ceilings[0].as_ref(),
+            100,
+        );
+        assert_eq!(trace.proposed.reason_code, "PRIORITY_PREEMPTION");
+        assert_eq!(trace.proposed.target_concurrency, 6);
+        assert_eq!(trace.proposed.burst_cpu_cap, 1.5);
+        assert_eq!(
+            trace.clamps.last().map(|c| (c.rule.as_str(), c.after)),
+            Some(("priority_preemption", 6))
+        );
+    }
+
+    #[test]
+    fn simulation_reports_actions_and_rolls_back() {
+        let conn = crate::db::test_conn();
+        let req = SimulationRequest {
+            policies: vec![SimulatedPolicy {
+                tenant_id: "t".to_string(),
+                workload_id: "w".to_string(),
+                policy: valid_policy(),
+            }],
+            slos: Vec::new(),
+            workloads: vec![observation(2, 0)],
+            node_groups: vec![NodeGroupObservation {
+                node_group: "g".to_string(),
+                cpu_pressure: 0.5,
+                mem_pressure: 0.5,
+                io_pressure: 0.1,
+                warm_ready: 1,
+                warm_hit_rate: 0.9,
+                capacity_units: 10,
+                used_units: 9,
+            }],
+        };
+        conn.execute(
+            "INSERT INTO orchestrator_tenant_tuning VALUES ('t', '{\"warm_pool_max_ratio\":1.0}', 100)",
+            params![],
+        )
+        .expect("tenant tuning");
+        let tx = conn.transaction().expect("tx");
+        let response = simulate_tx(&tx, &OrchestratorConfig::default(), &req).expect("simulate");
+        drop(tx);
+
+        assert_eq!(response.intents.len(), 1);
+        assert_eq!(
+            response.intents[0].pool_max_ready,
+            response.intents[0].target_concurrency
+        );
+        assert_eq!(response.decisions.len(), 1);
+        assert_eq!(response.decisions[0].decision_id, 0);
+        let types: Vec<&str> = response
+            .actions
+            .iter()
+            .map(|a| a.action_type.as_str())
+            .collect();
+        assert_eq!(
+            types,
+            [
+                "SetPoolTarget",
+                "SetBurstPolicy",
+                "SetPlacementPreference",
+                "ScaleNodeGroupUp"
+            ]
+        );
+
+        for table in [
+            "orchestrator_workload_policy",
+            "orchestrator_workload_observation",
+            "orchestrator_node_group_observation",
+            "orchestrator_intent",
+            "orchestrator_action",
+            "orchestrator_decision",
+            "orchestrator_event",
+            "orchestrator_workload_observation_sample",
+            "orchestrator_node_group_observation_sample",
+        ] {
+            let rows: i64 = conn
+                .query_row(&format!("SELECT COUNT(*) FROM {table}"), params![], |row| {
+                    row.get(0)
+                })
+                .expect("count");
+            assert_eq!(rows, 0, "{table}");
+        }
+    }
+
+    #[test]
+    fn warm_pool_adjustment_is_kept_once_applied() {
+        let conn = crate::db::test_conn();
+        let now = now_unix_seconds();
+        let policy = WorkloadPolicyRequest {
+            warm_pool: Some(AdaptiveWarmPool::default()),
+            ..valid_policy()
+        };
+        upsert_workload_policy_tx(&conn, "t", "w", &policy, now).expect("policy");
+        conn.execute(
+            "INSERT INTO orchestrator_intent
+               (tenant_id, workload_id, target_concurrency, burst_cpu_cap, burst_mem_mb, burst_ttl_seconds, pool_min_ready, pool_target_ready, pool_max_ready, preferred_node_group, anti_affinity, reason_code, effective_at, ttl_seconds, updated_at)
+             VALUES ('t', 'w', 10, 1.5, 512, 60, 1, 2, 3, 'g', 0, 'STEADY_STATE', ?1, 60, ?1)",
+            params![now],
+        )
+        .expect("intent");
+        let missing = NodeGroupObservation {
+            node_group: "g".to_string(),
+            cpu_pressure: 0.5,
+            mem_pressure: 0.5,
+            io_pressure: 0.1,
+            warm_ready: 0,
+            warm_hit_rate: 0.5,
+            capacity_units: 40,
+            used_units: 10,
+        };
+        ingest_observations_tx(&conn, &[observation(10, 0)], &[missing], now).expect("ingest");
+        let stored = || {
+            load_current_intent(&conn, "t", "w")
+                .expect("intent")
+                .expect("row")
+        };
+
+        // Cooldown holds the resize, so the grown adjustment is not kept.
+        let cfg = OrchestratorConfig::default();
+        let held = run_fast_loop_tx(&conn, &cfg).expect("fast loop");
+        assert_eq!(held.decisions[0].suppressed_by.as_deref(), Some("cooldown"));
+        assert_eq!(stored().warm_pool.adjustment, 0.0);
+
+        conn.execute(
+            "UPDATE orchestrator_intent SET updated_at = ?1",
+            params![now - 100],
+        )
+        .expect("age intent");
+        let resized = run_fast_loop_tx(&conn, &cfg).expect("fast loop");
+        assert!(resized.decisions[0].applied);
+        let current = stored();
+        assert!((current.warm_pool.adjustment - 0.05).abs() < 1e-9);
+        assert_eq!(current.target_concurrency, 10);
+        assert_eq!(current.pool_ready, [2, 3, 4]);
+    }
+
+    #[test]
+    fn idle_tracking_starts_before_the_first_intent() {
+        let conn = crate::db::test_conn();
+        let now = now_unix_seconds();
+        let policy = WorkloadPolicyRequest {
+            scale_to_zero_idle_seconds: Some(60),
+            cooldown_seconds: 0,
+            ..valid_policy()
+        };
+        upsert_workload_policy_tx(&conn, "t", "w", &policy, now).expect("policy");
+        ingest_observations_tx(&conn, &[observation(0, 0)], &[], now).expect("ingest");
+
+        let cfg = OrchestratorConfig::default();
+        let first = run_fast_loop_tx(&conn, &cfg).expect("fast loop");
+        assert_eq!(first.decisions[0].current_target, None);
+        let created = load_current_intent(&conn, "t", "w")
+            .expect("intent")
+            .expect("row");
+        let since = created.idle_since.expect("idle from the first tick");
+        assert!(since >= now);
+
+        // Idle long enough counted from that first tick, not the second.
+        conn.execute(
+            "UPDATE orchestrator_intent SET idle_since = idle_since - 60",
+            params![],
+        )
+        .expect("age idle");
+        let idle = run_fast_loop_tx(&conn, &cfg).expect("fast loop");
+        assert_eq!(idle.decisions[0].proposed.reason_code, "SCALE_TO_ZERO");
+        assert_eq!(idle.decisions[0].proposed.target_concurrency, 0);
+    }
+
+    #[test]
+    fn pid_integral_does_not_wind_up_while_held() {
+        let conn = crate::db::test_conn();
+        let now = now_unix_seconds();
+        let policy = WorkloadPolicyRequest {
+            scaling_strategy: ScalingStrategy::Pid(PidStrategy::default()),
+            ..valid_policy()
+        };
+        upsert_workload_policy_tx(&conn, "t", "w", &policy, now).expect("policy");
+        upsert_workload_slo_tx(
+            &conn,
+            "t",
+            "w",
+            &WorkloadSloRequest {
+                p95_latency_ms: 500,
+                max_cold_start_pct: 100.0,
+                max_reject_pct: 100.0,
+                rto_seconds: 60,
+                max_cost_per_compute_unit: 100.0,
+            },
+            now,
+        )
+        .expect("slo");
+        conn.execute(
+            "INSERT INTO orchestrator_intent
+               (tenant_id, workload_id, target_concurrency, burst_cpu_cap, burst_mem_mb, burst_ttl_seconds, pool_min_ready, pool_target_ready, pool_max_ready, preferred_node_group, anti_affinity, reason_code, effective_at, ttl_seconds, updated_at)
+             VALUES ('t', 'w', 10, 1.5, 512, 60, 1, 1, 2, 'g', 0, 'STEADY_STATE', ?1, 60, ?1)",
+            params![now],
         )
-        .expect("replay should not fail");
-        assert_eq!(op.operation_id, "op_123");
-        assert_eq!(op.reason_code.as_deref(), Some(RC_IDEMPOTENCY_REPLAY));
+        .expect("intent");
+        // A minute since the last tick, at p95 900ms against 500ms.
+        scaling::save_state_tx(
+            &conn,
+            "t",
+            "w",
+            policy.scaling_strategy.kind(),
+            &StrategyState {
+                last_error: Some(0.8),
+                last_at: Some(now - 60),
+                ..Default::default()
+            },
+            now - 60,
+        )
+        .expect("state");
+        ingest_observations_tx(&conn, &[observation(10, 0)], &[], now).expect("ingest");
+        let integral = || {
+            scaling::load_state_tx(&conn, "t", "w", policy.scaling_strategy.kind())
+                .expect("state")
+                .integral
+        };
+
+        let cfg = OrchestratorConfig::default();
+        for _ in 0..3 {
+            let held = run_fast_loop_tx(&conn, &cfg).expect("fast loop");
+            assert_eq!(held.decisions[0].suppressed_by.as_deref(), Some("cooldown"));
+            assert_eq!(integral(), 0.0);
+        }
+
+        conn.execute(
+            "UPDATE orchestrator_intent SET updated_at = ?1",
+            params![now - 100],
+        )
+        .expect("age intent");
+        let applied = run_fast_loop_tx(&conn, &cfg).expect("fast loop");
+        assert!(applied.decisions[0].applied);
+        // 10 * (1 + 0.3 * 0.8) plus at most a second of integral, not the
+        // minute the hold lasted.
+        assert_eq!(applied.decisions[0].base_target, 13);
+        assert!(integral() < 1.0);
+    }
+
+    #[test]
+    fn deleting_policy_cascades_to_derived_state() {
+        let conn = crate::db::test_conn();
+        conn.execute_batch(
+            "INSERT INTO orchestrator_workload_policy VALUES
+               ('t', 'w', 'fn-1', 2, 10, 2, 20, 5, 30, 10.0, 1.5, 512, 60, '[]', 100, NULL, '{\"kind\":\"heuristic\"}', NULL);
+             INSERT INTO orchestrator_intent
+               (tenant_id, workload_id, target_concurrency, burst_cpu_cap, burst_mem_mb, burst_ttl_seconds, pool_min_ready, pool_target_ready, pool_max_ready, preferred_node_group, anti_affinity, reason_code, effective_at, ttl_seconds, updated_at)
+             VALUES ('t', 'w', 4, 1.5, 512, 60, 1, 1, 2, 'g', 0, 'STEADY_STATE', 100, 60, 100);
+             INSERT INTO orchestrator_workload_observation VALUES
+               ('t', 'w', 'g', 0, 0.1, 0.1, 0.1, 0.0, 10, 0.0, 2, 1.0, 100);",
+        )
+        .expect("seed");
+        for (payload, status) in [(1, "pending"), (2, "running")] {
+            enqueue_action(
+                &conn,
+                "t",
+                "w",
+                "SetPlacementPreference",
+                json!({ "max_instances": payload }),
+                30,
+                100,
+                None,
+                None,
+            )
+            .expect("enqueue");
+            conn.execute(
+                "UPDATE orchestrator_action SET status = ?1 WHERE payload_json = ?2",
+                params![status, json!({ "max_instances": payload }).to_string()],
+            )
+            .expect("status");
+        }
+
+        let cascade = delete_workload_policy_tx(&conn, "t", "w", 200).expect("delete");
+        assert_eq!(
+            cascade,
+            PolicyCascade {
+                intents: 1,
+                actions_cancelled: 1,
+                observations: 1,
+            }
+        );
+        let statuses = conn
+            .query_map(
+                "SELECT status FROM orchestrator_action ORDER BY status",
+                params![],
+                |row| row.get::<String>(0),
+            )
+            .expect("statuses");
+        assert_eq!(statuses, ["cancelled", "running"]);
+        let logged: Vec<(String, Option<String>)> =
+            events::list_events_tx(&conn, &Default::default(), 0, 10)
+                .expect("events")
+                .into_iter()
+                .map(|e| (e.event_type, e.status))
+                .collect();
+        assert_eq!(
+            logged,
+            [
+                (
+                    events::EVENT_ACTION_ENQUEUED.to_string(),
+                    Some("pending".to_string())
+                ),
+                (
+                    events::EVENT_ACTION_ENQUEUED.to_string(),
+                    Some("pending".to_string())
+                ),
+                (
+                    events::EVENT_ACTION_TERMINAL.to_string(),
+                    Some("cancelled".to_string())
+                ),
+                (events::EVENT_INTENT_CLEARED.to_string(), None),
+            ]
+        );
+
+        let err = delete_workload_policy_tx(&conn, "t", "w", 300).expect_err("already gone");
+        assert!(matches!(
+            err.downcast_ref::<ServiceError>(),
+            Some(ServiceError::NotFound(_))
+        ));
+    }
+
+    fn enqueue(conn: &Connection, action_type: &str, payload: Value, window: i64) -> String {
+        enqueue_action(conn, "t", "w", action_type, payload, 30, window, None, None)
+            .expect("enqueue")
+            .expect("not a duplicate")
+    }
+
+    fn status_of(conn: &Connection, action_id: &str) -> (String, Option<String>) {
+        let action = get_action_tx(conn, action_id).expect("action");
+        (action.status, action.reason_code)
+    }
+
+    #[test]
+    fn newer_targets_supersede_pending_actions() {
+        let conn = crate::db::test_conn();
+        let pool = enqueue(&conn, "SetPoolTarget", json!({ "max_instances": 1 }), 100);
+        let dispatched = enqueue(
+            &conn,
+            "SetBurstPolicy",
+            json!({ "container_id": "c1" }),
+            100,
+        );
+        conn.execute(
+            "UPDATE orchestrator_action SET status = 'running' WHERE action_id = ?1",
+            params![dispatched],
+        )
+        .expect("dispatch");
+        let c2 = enqueue(
+            &conn,
+            "SetBurstPolicy",
+            json!({ "container_id": "c2" }),
+            100,
+        );
+
+        let newer_pool = enqueue(&conn, "SetPoolTarget", json!({ "max_instances": 2 }), 105);
+        let newer_c1 = enqueue(
+            &conn,
+            "SetBurstPolicy",
+            json!({ "container_id": "c1" }),
+            105,
+        );
+
+        let superseded = (
+            "cancelled".to_string(),
+            Some(RC_ORCH_SUPERSEDED.to_string()),
+        );
+        assert_eq!(status_of(&conn, &pool), superseded);
+        assert_eq!(status_of(&conn, &dispatched).0, "running");
+        assert_eq!(status_of(&conn, &c2).0, "pending");
+        assert_eq!(status_of(&conn, &newer_pool).0, "pending");
+        assert_eq!(status_of(&conn, &newer_c1).0, "pending");
+
+        let err = requeue_action_tx(&conn, &pool, 200).expect_err("stale target");
+        assert!(matches!(
+            err.downcast_ref::<ServiceError>(),
+            Some(ServiceError::Conflict(_))
+        ));
+    }
+
+    #[test]
+    fn cancel_and_requeue_check_transitions() {
+        let conn = crate::db::test_conn();
+        let action_id = enqueue(
+            &conn,
+            "SetPlacementPreference",
+            json!({ "node_group": "g" }),
+            100,
+        );
+
+        let cancelled = cancel_action_tx(&conn, &action_id, 150).expect("cancel");
+        assert_eq!(cancelled.status, "cancelled");
+        assert_eq!(cancelled.reason_code.as_deref(), Some(RC_ORCH_CANCELLED));
+        let err = cancel_action_tx(&conn, &action_id, 160).expect_err("already cancelled");
+        assert!(matches!(
+            err.downcast_ref::<ServiceError>(),
+            Some(ServiceError::Conflict(_))
+        ));
+
+        let requeued = requeue_action_tx(&conn, &action_id, 200).expect("requeue");
+        assert_eq!(requeued.status, "pending");
+        assert_eq!(requeued.terminal_status, None);
+        // A new key, so the runtime doesn't replay the operation that failed.
+        assert_ne!(requeued.idempotency_key, cancelled.idempotency_key);
+        assert_eq!((requeued.effective_at, requeued.next_retry_at), (200, 200));
+        let err = requeue_action_tx(&conn, &action_id, 210).expect_err("still pending");
+        assert!(matches!(
+            err.downcast_ref::<ServiceError>(),
+            Some(ServiceError::Conflict(_))
+        ));
+        let err = cancel_action_tx(&conn, "missing", 210).expect_err("missing");
+        assert!(matches!(
+            err.downcast_ref::<ServiceError>(),
+            Some(ServiceError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn executor_results_follow_the_transition_table() {
+        let conn = crate::db::test_conn();
+        let conflict = |err: anyhow::Error| {
+            matches!(
+                err.downcast_ref::<ServiceError>(),
+                Some(ServiceError::Conflict(_))
+            )
+        };
+        let report = |action_id: &str, status: &str| {
+            update_action_result_tx(&conn, action_id, status, None, None, 150)
+        };
+        let with_status = |status: &str| {
+            let action_id = enqueue(
+                &conn,
+                "SetPlacementPreference",
+                json!({ "node_group": status }),
+                100,
+            );
+            conn.execute(
+                "UPDATE orchestrator_action SET status = ?1 WHERE action_id = ?2",
+                params![status, action_id],
+            )
+            .expect("status");
+            action_id
+        };
+
+        for status in ["pending", DEAD_LETTER, "cancelled", "succeeded"] {
+            let action_id = with_status(status);
+            for next in ["running", "succeeded"] {
+                assert!(conflict(report(&action_id, next).expect_err(next)));
+            }
+            assert_eq!(status_of(&conn, &action_id).0, status);
+        }
+
+        let running = with_status("running");
+        assert!(conflict(report(&running, "queued").expect_err("backwards")));
+        report(&running, "running").expect("still running");
+        report(&running, "succeeded").expect("finish");
+        let finished = get_action_tx(&conn, &running).expect("action");
+        assert_eq!(finished.status, "succeeded");
+        assert_eq!(finished.terminal_status.as_deref(), Some("succeeded"));
+        assert!(finished.terminal_at.is_some());
+        assert!(finished.total_latency_ms.is_some());
+        let terminal_events: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM orchestrator_event WHERE action_id = ?1 AND event_type = ?2",
+                params![running, events::EVENT_ACTION_TERMINAL],
+                |row| row.get(0),
+            )
+            .expect("events");
+        assert_eq!(terminal_events, 1);
+
+        let accepted = with_status("accepted");
+        report(&accepted, "queued").expect("queued");
+        report(&accepted, "timed_out").expect("timed out");
+        assert_eq!(status_of(&conn, &accepted).0, "timed_out");
+    }
+
+    fn next_candidate(conn: &Connection) -> DispatchAction {
+        load_dispatch_candidates(conn, i64::MAX, 10)
+            .expect("candidates")
+            .remove(0)
+    }
+
+    #[test]
+    fn exhausted_retries_dead_letter_until_replayed() {
+        let conn = crate::db::test_conn();
+        let action_id = enqueue(
+            &conn,
+            "SetPlacementPreference",
+            json!({ "node_group": "g" }),
+            100,
+        );
+        let policy = RetryPolicy {
+            max_attempts: 2,
+            ..Default::default()
+        };
+
+        schedule_retry(
+            &conn,
+            &next_candidate(&conn),
+            &policy,
+            1,
+            15,
+            RC_RESOURCE_PRESSURE,
+            "busy",
+        )
+        .expect("retry");
+        let retried = get_action_tx(&conn, &action_id).expect("action");
+        assert_eq!(retried.status, "pending");
+        assert!(retried.next_retry_at >= retried.effective_at + 15);
+
+        schedule_retry(
+            &conn,
+            &next_candidate(&conn),
+            &policy,
+            2,
+            0,
+            RC_OPERATION_FAILED,
+            "boom",
+        )
+        .expect("dead letter");
+        assert_eq!(
+            status_of(&conn, &action_id),
+            (
+                DEAD_LETTER.to_string(),
+                Some(RC_ORCH_RETRIES_EXHAUSTED.to_string())
+            )
+        );
+        assert!(load_dispatch_candidates(&conn, i64::MAX, 10)
+            .expect("candidates")
+            .is_empty());
+
+        let dead = list_dead_letters_tx(&conn, Some("t"), 10).expect("dead letters");
+        assert_eq!(dead.len(), 1);
+        let codes: Vec<(u32, &str, Option<i64>)> = dead[0]
+            .attempts
+            .iter()
+            .map(|a| (a.attempt, a.reason_code.as_str(), a.next_retry_at))
+            .collect();
+        assert_eq!(
+            codes,
+            [
+                (1, RC_RESOURCE_PRESSURE, Some(retried.next_retry_at)),
+                (2, RC_OPERATION_FAILED, None)
+            ]
+        );
+        assert!(list_dead_letters_tx(&conn, Some("other"), 10)
+            .expect("dead letters")
+            .is_empty());
+
+        let err = requeue_action_tx(&conn, &action_id, 200).expect_err("not a failure");
+        assert!(matches!(
+            err.downcast_ref::<ServiceError>(),
+            Some(ServiceError::Conflict(_))
+        ));
+        let replayed = replay_action_tx(&conn, &action_id, 200).expect("replay");
+        assert_eq!(replayed.status, "pending");
+        assert_eq!(replayed.attempt_count, 0);
+        assert_eq!(replayed.reason_code.as_deref(), Some(RC_ORCH_REPLAYED));
+        assert!(list_dead_letters_tx(&conn, None, 10)
+            .expect("dead letters")
+            .is_empty());
+        assert_eq!(
+            retry::list_attempts_tx(&conn, &action_id)
+                .expect("attempts")
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn dispatch_candidates_interleave_tenants() {
+        let conn = crate::db::test_conn();
+        for (tenant, node_group, window) in [
+            ("busy", "g1", 100),
+            ("busy", "g2", 101),
+            ("busy", "g3", 102),
+            ("quiet", "g1", 103),
+        ] {
+            enqueue_action(
+                &conn,
+                tenant,
+                "w",
+                "ScaleNodeGroupUp",
+                json!({ "node_group": node_group, "delta_units": 1 }),
+                30,
+                window,
+                None,
+                None,
+            )
+            .expect("enqueue");
+        }
+        conn.execute(
+            "UPDATE orchestrator_action SET created_at = decision_window_start",
+            params![],
+        )
+        .expect("backdate");
+        let candidates = load_dispatch_candidates(&conn, i64::MAX, 3).expect("candidates");
+        let picked: Vec<(&str, String)> = candidates
+            .iter()
+            .map(|a| (a.tenant_id.as_str(), a.target()))
+            .collect();
+        assert_eq!(
+            picked,
+            [
+                ("busy", "node_group/g1".to_string()),
+                ("quiet", "node_group/g1".to_string()),
+                ("busy", "node_group/g2".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[ignore = "needs PostgreSQL: set QUILT_TEST_POSTGRES_URL and run with --ignored"]
+    async fn postgres_policy_to_actions_round_trip() {
+        let db = tokio::task::spawn_blocking(|| crate::db::postgres_test_pool("orchestrator"))
+            .await
+            .expect("join");
+        let policy = WorkloadPolicyRequest {
+            cooldown_seconds: 0,
+            ..valid_policy()
+        };
+        upsert_workload_policy(&db, "t", "w", policy)
+            .await
+            .expect("policy");
+        ingest_observations(
+            &db,
+            ObservationIngestRequest {
+                workloads: vec![observation(4, 50)],
+                node_groups: vec![NodeGroupObservation {
+                    node_group: "g".to_string(),
+                    cpu_pressure: 0.5,
+                    mem_pressure: 0.5,
+                    io_pressure: 0.1,
+                    warm_ready: 2,
+                    warm_hit_rate: 0.8,
+                    capacity_units: 100,
+                    used_units: 40,
+                }],
+            },
+        )
+        .await
+        .expect("ingest");
+        let cfg = OrchestratorConfig::default();
+        run_fast_loop(&db, &cfg).await.expect("fast loop");
+        run_slow_loop(&db, &cfg).await.expect("slow loop");
+
+        let intents = list_intents(&db, None).await.expect("intents");
+        assert_eq!(intents.len(), 1);
+        assert!(intents[0].target_concurrency > 0);
+        let actions = list_actions(&db, Some("t"), None, 100)
+            .await
+            .expect("actions");
+        assert!(!actions.is_empty());
+        let traces = decisions::list_decisions(&db, "t", "w", None, 10)
+            .await
+            .expect("decisions");
+        assert_eq!(traces.len(), 1);
+        assert!(traces[0].applied);
+        let older = decisions::list_decisions(&db, "t", "w", Some(traces[0].decision_id), 10)
+            .await
+            .expect("older decisions");
+        assert!(older.is_empty());
+
+        // Simulation rolls back everything it evaluated.
+        let simulated = simulate(&db, &cfg, Default::default(), Some("t"))
+            .await
+            .expect("simulate");
+        assert_eq!(simulated.decisions.len(), 1);
+        assert_eq!(
+            decisions::list_decisions(&db, "t", "w", None, 10)
+                .await
+                .expect("decisions")
+                .len(),
+            1
+        );
+        assert_eq!(
+            list_actions(&db, Some("t"), None, 100)
+                .await
+                .expect("actions")
+                .len(),
+            actions.len()
+        );
+
+        let placement = actions
+            .iter()
+            .find(|a| a.action_type == "SetPlacementPreference")
+            .expect("placement action");
+        let cancelled = cancel_action(&db, &placement.action_id)
+            .await
+            .expect("cancel");
+        assert_eq!(cancelled.status, "cancelled");
+        let requeued = requeue_action(&db, &placement.action_id)
+            .await
+            .expect("requeue");
+        assert_eq!(requeued.status, "pending");
+
+        let policies = list_workload_policies(&db, Some("t"))
+            .await
+            .expect("policies");
+        assert_eq!(policies[0].policy.target_container_ids, ["c1"]);
+        delete_workload_policy(&db, "t", "w")
+            .await
+            .expect("delete policy");
+        assert!(list_intents(&db, Some("t"))
+            .await
+            .expect("intents")
+            .is_empty());
+        let pending = list_actions(&db, Some("t"), Some("pending"), 100)
+            .await
+            .expect("actions");
+        assert!(pending.is_empty());
     }
 }
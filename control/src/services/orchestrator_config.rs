@@ -0,0 +1,491 @@
+//! Orchestrator tuning: a TOML file read at startup and on reload (SIGHUP or
+//! `POST /v1/orchestrator/config:reload`), plus per-tenant overrides of the
+//! decision knobs stored in the database.
+//!
+//! Overrides are read inside each loop transaction rather than cached, so
+//! every instance sees a change without a reload.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use crate::db::{execute_async, params, Connection, DbPool};
+use crate::services::circuit_breaker::BreakerConfig;
+use crate::services::error::{ServiceError, Validator};
+use crate::services::observations::{Aggregation, ObservationConfig};
+use crate::services::orchestrator::now_unix_seconds;
+use crate::services::retry::RetryConfig;
+
+/// Knobs that shape a single workload's decision. Each can be overridden
+/// per tenant.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Tuning {
+    /// Pressure at or above which a workload or its node group counts as hot.
+    pub hot_group_pressure: f64,
+    /// Burst CPU and memory multipliers applied while an SLO is violated.
+    pub slo_burst_cpu_multiplier: f64,
+    pub slo_burst_mem_multiplier: f64,
+    /// Warm pool sizes as fractions of the target concurrency.
+    pub warm_pool_min_ratio: f64,
+    pub warm_pool_target_ratio: f64,
+    pub warm_pool_max_ratio: f64,
+}
+
+impl Default for Tuning {
+    fn default() -> Self {
+        Self {
+            hot_group_pressure: 0.85,
+            slo_burst_cpu_multiplier: 1.2,
+            slo_burst_mem_multiplier: 1.15,
+            warm_pool_min_ratio: 0.10,
+            warm_pool_target_ratio: 0.20,
+            warm_pool_max_ratio: 0.30,
+        }
+    }
+}
+
+/// A tenant's partial `Tuning`; unset fields fall back to the file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TuningOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hot_group_pressure: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slo_burst_cpu_multiplier: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slo_burst_mem_multiplier: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warm_pool_min_ratio: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warm_pool_target_ratio: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warm_pool_max_ratio: Option<f64>,
+}
+
+fn is_ratio(v: f64) -> bool {
+    (0.0..=1.0).contains(&v)
+}
+
+impl Tuning {
+    pub fn with_overrides(&self, o: &TuningOverrides) -> Tuning {
+        Tuning {
+            hot_group_pressure: o.hot_group_pressure.unwrap_or(self.hot_group_pressure),
+            slo_burst_cpu_multiplier: o
+                .slo_burst_cpu_multiplier
+                .unwrap_or(self.slo_burst_cpu_multiplier),
+            slo_burst_mem_multiplier: o
+                .slo_burst_mem_multiplier
+                .unwrap_or(self.slo_burst_mem_multiplier),
+            warm_pool_min_ratio: o.warm_pool_min_ratio.unwrap_or(self.warm_pool_min_ratio),
+            warm_pool_target_ratio: o
+                .warm_pool_target_ratio
+                .unwrap_or(self.warm_pool_target_ratio),
+            warm_pool_max_ratio: o.warm_pool_max_ratio.unwrap_or(self.warm_pool_max_ratio),
+        }
+    }
+
+    fn check(&self, v: &mut Validator, prefix: &str) {
+        let field = |name: &str| format!("{prefix}{name}");
+        v.check(
+            self.hot_group_pressure > 0.0 && self.hot_group_pressure <= 1.0,
+            &field("hot_group_pressure"),
+            "must be in (0, 1]",
+        )
+        .check(
+            self.slo_burst_cpu_multiplier >= 1.0,
+            &field("slo_burst_cpu_multiplier"),
+            "must be >= 1",
+        )
+        .check(
+            self.slo_burst_mem_multiplier >= 1.0,
+            &field("slo_burst_mem_multiplier"),
+            "must be >= 1",
+        )
+        .check(
+            is_ratio(self.warm_pool_min_ratio)
+                && is_ratio(self.warm_pool_target_ratio)
+                && is_ratio(self.warm_pool_max_ratio)
+                && self.warm_pool_min_ratio <= self.warm_pool_target_ratio
+                && self.warm_pool_target_ratio <= self.warm_pool_max_ratio,
+            &field("warm_pool_*_ratio"),
+            "must satisfy 0 <= min <= target <= max <= 1",
+        );
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OrchestratorConfig {
+    pub fast_loop_seconds: u64,
+    pub slow_loop_seconds: u64,
+    pub dispatch_loop_seconds: u64,
+    /// Actions loaded per dispatch cycle.
+    pub max_dispatch_batch: usize,
+    /// Actions dispatched or polled at once, across all tenants.
+    pub max_dispatch_in_flight: usize,
+    /// Per-tenant share of `max_dispatch_in_flight`.
+    pub max_dispatch_in_flight_per_tenant: usize,
+    /// Concurrent actions against one container, node group or workload.
+    pub max_dispatch_in_flight_per_target: usize,
+    /// Minimum delay before retrying an action the elasticity API rejected
+    /// for resource pressure; the retry policy's backoff applies on top.
+    pub resource_pressure_retry_seconds: u64,
+    /// Node-group utilization above which the slow loop scales up.
+    pub scale_up_utilization: f64,
+    /// Utilization below which a group with warm capacity scales down.
+    pub scale_down_utilization: f64,
+    /// Node-group utilization at or above which the fast loop shares the
+    /// group's capacity out by workload priority.
+    pub preemption_utilization: f64,
+    pub tuning: Tuning,
+    pub observations: ObservationConfig,
+    pub retry: RetryConfig,
+    pub breaker: BreakerConfig,
+}
+
+impl Default for OrchestratorConfig {
+    fn default() -> Self {
+        Self {
+            fast_loop_seconds: 5,
+            slow_loop_seconds: 120,
+            dispatch_loop_seconds: 2,
+            max_dispatch_batch: 100,
+            max_dispatch_in_flight: 16,
+            max_dispatch_in_flight_per_tenant: 4,
+            max_dispatch_in_flight_per_target: 1,
+            resource_pressure_retry_seconds: 15,
+            scale_up_utilization: 0.80,
+            scale_down_utilization: 0.35,
+            preemption_utilization: 0.90,
+            tuning: Tuning::default(),
+            observations: ObservationConfig::default(),
+            retry: RetryConfig::default(),
+            breaker: BreakerConfig::default(),
+        }
+    }
+}
+
+impl OrchestratorConfig {
+    pub fn from_toml(raw: &str) -> Result<Self> {
+        let cfg: Self = toml::from_str(raw).context("invalid orchestrator config")?;
+        cfg.validate()?;
+        Ok(cfg)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let mut v = Validator::new();
+        v.check(
+            self.fast_loop_seconds >= 1,
+            "fast_loop_seconds",
+            "must be >= 1",
+        )
+        .check(
+            self.slow_loop_seconds >= 1,
+            "slow_loop_seconds",
+            "must be >= 1",
+        )
+        .check(
+            self.dispatch_loop_seconds >= 1,
+            "dispatch_loop_seconds",
+            "must be >= 1",
+        )
+        .check(
+            self.max_dispatch_batch >= 1,
+            "max_dispatch_batch",
+            "must be >= 1",
+        )
+        .check(
+            self.max_dispatch_in_flight >= 1,
+            "max_dispatch_in_flight",
+            "must be >= 1",
+        )
+        .check(
+            self.max_dispatch_in_flight_per_tenant >= 1,
+            "max_dispatch_in_flight_per_tenant",
+            "must be >= 1",
+        )
+        .check(
+            self.max_dispatch_in_flight_per_target >= 1,
+            "max_dispatch_in_flight_per_target",
+            "must be >= 1",
+        )
+        .check(
+            self.scale_down_utilization >= 0.0
+                && self.scale_down_utilization < self.scale_up_utilization,
+            "scale_down_utilization",
+            "must be >= 0 and below scale_up_utilization",
+        )
+        .check(
+            self.preemption_utilization > 0.0 && self.preemption_utilization <= 1.0,
+            "preemption_utilization",
+            "must be in (0, 1]",
+        );
+        self.tuning.check(&mut v, "tuning.");
+        self.retry.check(&mut v, "retry.");
+        let obs = &self.observations;
+        if let Aggregation::Ewma { alpha } = obs.aggregation {
+            v.check(
+                alpha > 0.0 && alpha <= 1.0,
+                "observations.aggregation.alpha",
+                "must be in (0, 1]",
+            );
+        }
+        v.check(
+            self.breaker.failure_threshold >= 1,
+            "breaker.failure_threshold",
+            "must be >= 1",
+        )
+        .check(
+            self.breaker.open_seconds >= 1,
+            "breaker.open_seconds",
+            "must be >= 1",
+        )
+        .check(
+            obs.stale_after_seconds >= 1 && obs.stale_after_seconds <= obs.window_seconds,
+            "observations.stale_after_seconds",
+            "must be >= 1 and within window_seconds",
+        )
+        .check(
+            obs.retention_seconds >= obs.window_seconds,
+            "observations.retention_seconds",
+            "must cover window_seconds",
+        )
+        .finish()
+    }
+}
+
+#[derive(Debug)]
+struct Loaded {
+    config: Arc<OrchestratorConfig>,
+    loaded_at: i64,
+}
+
+/// Shared, swappable orchestrator config. Loops take a snapshot per tick.
+#[derive(Debug, Clone)]
+pub struct ConfigHandle {
+    path: Option<PathBuf>,
+    current: Arc<RwLock<Loaded>>,
+}
+
+fn read_config(path: Option<&Path>) -> Result<OrchestratorConfig> {
+    let Some(path) = path else {
+        return Ok(OrchestratorConfig::default());
+    };
+    let raw = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    OrchestratorConfig::from_toml(&raw)
+}
+
+impl ConfigHandle {
+    /// Built-in defaults when `path` is `None`.
+    pub fn load(path: Option<PathBuf>) -> Result<Self> {
+        let config = read_config(path.as_deref())?;
+        Ok(Self {
+            path,
+            current: Arc::new(RwLock::new(Loaded {
+                config: Arc::new(config),
+                loaded_at: now_unix_seconds(),
+            })),
+        })
+    }
+
+    pub fn current(&self) -> Arc<OrchestratorConfig> {
+        self.current.read().expect("config lock").config.clone()
+    }
+
+    /// Re-read the file. On error the running config is kept.
+    pub fn reload(&self) -> Result<Arc<OrchestratorConfig>> {
+        let config = Arc::new(read_config(self.path.as_deref())?);
+        *self.current.write().expect("config lock") = Loaded {
+            config: config.clone(),
+            loaded_at: now_unix_seconds(),
+        };
+        Ok(config)
+    }
+
+    pub fn source(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub fn loaded_at(&self) -> i64 {
+        self.current.read().expect("config lock").loaded_at
+    }
+}
+
+/// Effective config as served by `GET /v1/orchestrator/config`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrchestratorConfigResponse {
+    pub config: OrchestratorConfig,
+    /// Config file path, or `None` when running on built-in defaults.
+    pub source: Option<String>,
+    pub loaded_at: i64,
+    pub tenant_overrides: BTreeMap<String, TuningOverrides>,
+}
+
+pub(crate) fn tenant_overrides_tx(
+    conn: &Connection,
+    tenant_id: Option<&str>,
+) -> Result<BTreeMap<String, TuningOverrides>> {
+    let rows = conn.query_map(
+        "SELECT tenant_id, overrides_json FROM orchestrator_tenant_tuning
+         WHERE CAST(?1 AS TEXT) IS NULL OR tenant_id = ?1",
+        params![tenant_id],
+        |row| Ok((row.get::<String>(0)?, row.get::<String>(1)?)),
+    )?;
+    rows.into_iter()
+        .map(|(tenant_id, raw)| Ok((tenant_id, serde_json::from_str(&raw)?)))
+        .collect()
+}
+
+pub async fn list_tenant_overrides(
+    db: &DbPool,
+    tenant_id: Option<&str>,
+) -> Result<BTreeMap<String, TuningOverrides>> {
+    let tenant_id = tenant_id.map(ToString::to_string);
+    execute_async(db, move |conn| {
+        tenant_overrides_tx(conn, tenant_id.as_deref())
+    })
+    .await
+}
+
+/// Store a tenant's overrides. They must be valid on top of `base`.
+pub async fn upsert_tenant_overrides(
+    db: &DbPool,
+    base: &Tuning,
+    tenant_id: &str,
+    overrides: TuningOverrides,
+) -> Result<()> {
+    let mut v = Validator::new();
+    base.with_overrides(&overrides).check(&mut v, "");
+    v.finish()?;
+    let now = now_unix_seconds();
+    let tenant_id = tenant_id.to_string();
+    execute_async(db, move |conn| {
+        conn.execute(
+            "INSERT INTO orchestrator_tenant_tuning (tenant_id, overrides_json, updated_at)
+             VALUES (?1, ?2, ?3)
+             ON CONFLICT(tenant_id) DO UPDATE SET
+               overrides_json=excluded.overrides_json,
+               updated_at=excluded.updated_at",
+            params![tenant_id, serde_json::to_string(&overrides)?, now],
+        )?;
+        Ok(())
+    })
+    .await
+}
+
+pub async fn delete_tenant_overrides(db: &DbPool, tenant_id: &str) -> Result<()> {
+    let tenant_id = tenant_id.to_string();
+    execute_async(db, move |conn| {
+        let deleted = conn.execute(
+            "DELETE FROM orchestrator_tenant_tuning WHERE tenant_id = ?1",
+            params![tenant_id],
+        )?;
+        if deleted == 0 {
+            return Err(ServiceError::NotFound(format!(
+                "No tuning overrides for tenant {}",
+                tenant_id
+            ))
+            .into());
+        }
+        Ok(())
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partial_toml_keeps_defaults_and_is_validated() {
+        let cfg = OrchestratorConfig::from_toml(
+            r#"
+            fast_loop_seconds = 10
+
+            [tuning]
+            hot_group_pressure = 0.9
+
+            [observations]
+            aggregation = { method = "p95" }
+
+            [retry.action_types.SetPoolTarget]
+            max_attempts = 3
+            "#,
+        )
+        .expect("config");
+        assert_eq!(cfg.fast_loop_seconds, 10);
+        assert_eq!(cfg.slow_loop_seconds, 120);
+        assert_eq!(cfg.tuning.hot_group_pressure, 0.9);
+        assert_eq!(cfg.tuning.warm_pool_max_ratio, 0.30);
+        assert_eq!(cfg.observations.aggregation, Aggregation::P95);
+        assert_eq!(cfg.retry.policy("SetPoolTarget").max_attempts, 3);
+        assert_eq!(cfg.retry.policy("SetBurstPolicy").max_attempts, 8);
+
+        let err = OrchestratorConfig::from_toml(
+            "scale_up_utilization = 0.3\n[tuning]\nwarm_pool_min_ratio = 0.5\n",
+        )
+        .expect_err("invalid");
+        let Some(ServiceError::Validation(fields)) = err.downcast_ref::<ServiceError>() else {
+            panic!("expected validation error, got {err}");
+        };
+        let names: Vec<&str> = fields.iter().map(|f| f.field.as_str()).collect();
+        assert_eq!(
+            names,
+            ["scale_down_utilization", "tuning.warm_pool_*_ratio"]
+        );
+        assert!(OrchestratorConfig::from_toml("fast_loop_secs = 1").is_err());
+    }
+
+    #[test]
+    fn overrides_replace_only_set_fields() {
+        let overrides: TuningOverrides =
+            serde_json::from_str(r#"{"slo_burst_cpu_multiplier": 1.5}"#).expect("overrides");
+        let tuning = Tuning::default().with_overrides(&overrides);
+        assert_eq!(tuning.slo_burst_cpu_multiplier, 1.5);
+        assert_eq!(tuning.hot_group_pressure, 0.85);
+        assert_eq!(
+            serde_json::to_value(&overrides).expect("json"),
+            serde_json::json!({"slo_burst_cpu_multiplier": 1.5})
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[ignore = "needs PostgreSQL: set QUILT_TEST_POSTGRES_URL and run with --ignored"]
+    async fn postgres_tenant_overrides_round_trip() {
+        let db = tokio::task::spawn_blocking(|| crate::db::postgres_test_pool("tenant_tuning"))
+            .await
+            .expect("join");
+        let overrides = TuningOverrides {
+            hot_group_pressure: Some(0.95),
+            ..Default::default()
+        };
+        for _ in 0..2 {
+            upsert_tenant_overrides(&db, &Tuning::default(), "tenant-a", overrides.clone())
+                .await
+                .expect("upsert");
+        }
+        let stored = list_tenant_overrides(&db, Some("tenant-a"))
+            .await
+            .expect("list");
+        assert_eq!(stored["tenant-a"].hot_group_pressure, Some(0.95));
+        assert!(list_tenant_overrides(&db, Some("tenant-b"))
+            .await
+            .expect("list")
+            .is_empty());
+
+        delete_tenant_overrides(&db, "tenant-a")
+            .await
+            .expect("delete");
+        let err = delete_tenant_overrides(&db, "tenant-a")
+            .await
+            .expect_err("already deleted");
+        assert!(matches!(
+            err.downcast_ref::<ServiceError>(),
+            Some(ServiceError::NotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,274 @@
+//! Retry policy for failed dispatches, and the attempt history kept for
+//! every action that failed at least once.
+//!
+//! Delays grow exponentially per attempt with jitter derived from the action
+//! id, so a burst of actions failing together doesn't retry in lockstep and
+//! a given attempt always gets the same delay. An action that runs out of
+//! attempts or elapsed time moves to `dead_letter` instead of retrying.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+use crate::db::{params, Connection};
+use crate::services::error::Validator;
+use crate::types::ActionAttempt;
+
+pub const DEAD_LETTER: &str = "dead_letter";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetryPolicy {
+    /// Delay before the first retry.
+    pub initial_delay_seconds: u64,
+    /// Cap on any single delay, before jitter.
+    pub max_delay_seconds: u64,
+    /// Growth of the delay per attempt.
+    pub multiplier: f64,
+    /// Fraction of each delay, in `[0, 1]`, that is randomly shaved off.
+    pub jitter: f64,
+    /// Failed attempts after which the action is dead-lettered.
+    pub max_attempts: u32,
+    /// Time since the action became effective after which it is
+    /// dead-lettered rather than retried again.
+    pub max_elapsed_seconds: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            initial_delay_seconds: 2,
+            max_delay_seconds: 64,
+            multiplier: 2.0,
+            jitter: 0.2,
+            max_attempts: 8,
+            max_elapsed_seconds: 900,
+        }
+    }
+}
+
+/// What to do with an action after a failed attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryDecision {
+    Retry {
+        delay_seconds: i64,
+    },
+    /// Names the exhausted limit.
+    DeadLetter {
+        exhausted: &'static str,
+    },
+}
+
+/// Deterministic value in `[0, 1)` for `seed` and `attempt`.
+fn jitter_fraction(seed: &str, attempt: u32) -> f64 {
+    let mut hasher = Sha256::new();
+    hasher.update(format!("{}|{}", seed, attempt).as_bytes());
+    let digest = hasher.finalize();
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&digest[..8]);
+    (u64::from_be_bytes(bytes) >> 11) as f64 / (1u64 << 53) as f64
+}
+
+impl RetryPolicy {
+    /// Delay before retrying after failed attempt number `attempt` (1-based).
+    pub fn delay_seconds(&self, attempt: u32, seed: &str) -> i64 {
+        let exponent = attempt.saturating_sub(1).min(63) as i32;
+        let base = (self.initial_delay_seconds as f64 * self.multiplier.powi(exponent))
+            .min(self.max_delay_seconds as f64);
+        let jittered = base * (1.0 - self.jitter * jitter_fraction(seed, attempt));
+        (jittered.round() as i64).max(1)
+    }
+
+    /// `attempt` failed attempts so far, `elapsed_seconds` since the action
+    /// became effective. `min_delay_seconds` floors the delay, e.g. for
+    /// resource pressure the runtime wants us to wait out.
+    pub fn next(
+        &self,
+        attempt: u32,
+        elapsed_seconds: i64,
+        min_delay_seconds: i64,
+        seed: &str,
+    ) -> RetryDecision {
+        if attempt >= self.max_attempts {
+            return RetryDecision::DeadLetter {
+                exhausted: "max_attempts",
+            };
+        }
+        let delay_seconds = self.delay_seconds(attempt, seed).max(min_delay_seconds);
+        if elapsed_seconds + delay_seconds > self.max_elapsed_seconds as i64 {
+            return RetryDecision::DeadLetter {
+                exhausted: "max_elapsed_seconds",
+            };
+        }
+        RetryDecision::Retry { delay_seconds }
+    }
+
+    fn check(&self, v: &mut Validator, prefix: &str) {
+        let field = |name: &str| format!("{prefix}{name}");
+        v.check(
+            self.initial_delay_seconds >= 1,
+            &field("initial_delay_seconds"),
+            "must be >= 1",
+        )
+        .check(
+            self.max_delay_seconds >= self.initial_delay_seconds,
+            &field("max_delay_seconds"),
+            "must be >= initial_delay_seconds",
+        )
+        .check(
+            self.multiplier.is_finite() && self.multiplier >= 1.0,
+            &field("multiplier"),
+            "must be >= 1",
+        )
+        .check(
+            (0.0..=1.0).contains(&self.jitter),
+            &field("jitter"),
+            "must be between 0 and 1",
+        )
+        .check(
+            self.max_attempts >= 1,
+            &field("max_attempts"),
+            "must be >= 1",
+        )
+        .check(
+            self.max_elapsed_seconds >= 1,
+            &field("max_elapsed_seconds"),
+            "must be >= 1",
+        );
+    }
+}
+
+/// Retry policies by action type. A type without its own entry uses
+/// `default`; fields left out of an entry take the built-in defaults, not
+/// those of `default`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetryConfig {
+    pub default: RetryPolicy,
+    pub action_types: BTreeMap<String, RetryPolicy>,
+}
+
+impl RetryConfig {
+    pub fn policy(&self, action_type: &str) -> &RetryPolicy {
+        self.action_types.get(action_type).unwrap_or(&self.default)
+    }
+
+    pub fn check(&self, v: &mut Validator, prefix: &str) {
+        self.default.check(v, &format!("{prefix}default."));
+        for (action_type, policy) in &self.action_types {
+            policy.check(v, &format!("{prefix}action_types.{action_type}."));
+        }
+    }
+}
+
+/// Append a failed attempt. `next_retry_at` is `None` when it was the last.
+pub fn record_attempt_tx(conn: &Connection, attempt: &ActionAttempt) -> Result<()> {
+    conn.execute(
+        "INSERT INTO orchestrator_action_attempt
+         (action_id, attempt, status, reason_code, reason_message, attempted_at, next_retry_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            attempt.action_id,
+            attempt.attempt,
+            attempt.status,
+            attempt.reason_code,
+            attempt.reason_message,
+            attempt.attempted_at,
+            attempt.next_retry_at
+        ],
+    )?;
+    Ok(())
+}
+
+/// Failed attempts of `action_id`, oldest first. Survives a replay, so the
+/// history spans every run of the action.
+pub fn list_attempts_tx(conn: &Connection, action_id: &str) -> Result<Vec<ActionAttempt>> {
+    conn.query_map(
+        "SELECT action_id, attempt, status, reason_code, reason_message, attempted_at, next_retry_at
+         FROM orchestrator_action_attempt
+         WHERE action_id = ?1
+         ORDER BY attempt_id ASC",
+        params![action_id],
+        |row| {
+            Ok(ActionAttempt {
+                action_id: row.get(0)?,
+                attempt: row.get(1)?,
+                status: row.get(2)?,
+                reason_code: row.get(3)?,
+                reason_message: row.get(4)?,
+                attempted_at: row.get(5)?,
+                next_retry_at: row.get(6)?,
+            })
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn no_jitter() -> RetryPolicy {
+        RetryPolicy {
+            jitter: 0.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn delays_grow_exponentially_up_to_the_cap() {
+        let policy = no_jitter();
+        let delays: Vec<i64> = (1..=7).map(|a| policy.delay_seconds(a, "a")).collect();
+        assert_eq!(delays, [2, 4, 8, 16, 32, 64, 64]);
+    }
+
+    #[test]
+    fn jitter_is_bounded_and_stable_per_action() {
+        let policy = RetryPolicy {
+            jitter: 0.5,
+            ..Default::default()
+        };
+        for attempt in 1..=6 {
+            let full = no_jitter().delay_seconds(attempt, "a");
+            let d = policy.delay_seconds(attempt, "a");
+            assert!(
+                d <= full && d as f64 >= full as f64 * 0.5 - 1.0,
+                "{d} vs {full}"
+            );
+            assert_eq!(d, policy.delay_seconds(attempt, "a"));
+        }
+        let spread: std::collections::HashSet<i64> = (0..20)
+            .map(|i| policy.delay_seconds(6, &format!("action-{i}")))
+            .collect();
+        assert!(spread.len() > 1);
+    }
+
+    #[test]
+    fn gives_up_on_attempts_or_elapsed_time() {
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            max_elapsed_seconds: 30,
+            ..no_jitter()
+        };
+        assert_eq!(
+            policy.next(1, 0, 0, "a"),
+            RetryDecision::Retry { delay_seconds: 2 }
+        );
+        assert_eq!(
+            policy.next(2, 0, 15, "a"),
+            RetryDecision::Retry { delay_seconds: 15 }
+        );
+        assert_eq!(
+            policy.next(3, 0, 0, "a"),
+            RetryDecision::DeadLetter {
+                exhausted: "max_attempts"
+            }
+        );
+        assert_eq!(
+            policy.next(2, 27, 0, "a"),
+            RetryDecision::DeadLetter {
+                exhausted: "max_elapsed_seconds"
+            }
+        );
+    }
+}
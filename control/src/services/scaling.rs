@@ -0,0 +1,343 @@
+//! Scaling strategies: how the fast loop turns a workload observation into
+//! the base target that the policy's clamps then shape.
+//!
+//! The strategy is chosen per workload policy. Stateful strategies keep
+//! their state in `orchestrator_scaling_state`, one row per workload; the
+//! row is reset when the policy switches to another strategy.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::db::{params, Connection, OptionalExtension};
+use crate::services::error::Validator;
+use crate::types::{PidStrategy, ScalingStrategy, TargetTrackingStrategy, WorkloadObservation};
+
+/// What a strategy carries from one tick to the next. Each strategy uses
+/// its own fields and leaves the rest at their defaults.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StrategyState {
+    /// Target tracking: recent recommendations as `(at, units)`, newest last.
+    pub recommendations: Vec<(i64, u32)>,
+    /// PID: accumulated normalized latency error, in error-seconds.
+    pub integral: f64,
+    pub last_error: Option<f64>,
+    pub last_at: Option<i64>,
+}
+
+/// A strategy's output for one tick.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recommendation {
+    pub base_target: i64,
+    /// Multiplier applied for queue depth; 1 for strategies that count
+    /// queued requests as load instead.
+    pub queue_boost: f64,
+    /// How the target was reached, for the decision trace. `None` for the
+    /// heuristic, which the trace already explains.
+    pub detail: Option<String>,
+    pub state: StrategyState,
+}
+
+fn heuristic(obs: &WorkloadObservation, state: StrategyState) -> Recommendation {
+    let queue_boost = if obs.queue_depth > 0 {
+        1.0 + (obs.queue_depth as f64 / 100.0).min(1.0)
+    } else {
+        1.0
+    };
+    Recommendation {
+        base_target: (obs.active_compute_units as f64 * queue_boost).ceil() as i64,
+        queue_boost,
+        detail: None,
+        state,
+    }
+}
+
+fn target_tracking(
+    t: &TargetTrackingStrategy,
+    obs: &WorkloadObservation,
+    mut state: StrategyState,
+    now: i64,
+) -> Recommendation {
+    let load = (obs.queue_depth + obs.active_compute_units) as f64;
+    let units = (load / t.target_per_instance).ceil() as u32;
+    state
+        .recommendations
+        .retain(|(at, _)| now - at < t.scale_in_stabilization_seconds as i64);
+    state.recommendations.push((now, units));
+    let stabilized = state
+        .recommendations
+        .iter()
+        .map(|(_, u)| *u)
+        .max()
+        .unwrap_or(units);
+    Recommendation {
+        base_target: stabilized as i64,
+        queue_boost: 1.0,
+        detail: Some(format!(
+            "target_tracking: load {} / {} per instance = {}, stabilized to {}",
+            load, t.target_per_instance, units, stabilized
+        )),
+        state,
+    }
+}
+
+fn pid(
+    p: &PidStrategy,
+    obs: &WorkloadObservation,
+    p95_latency_ms: Option<u32>,
+    current_target: Option<u32>,
+    mut state: StrategyState,
+    now: i64,
+) -> Recommendation {
+    let Some(setpoint) = p95_latency_ms else {
+        return Recommendation {
+            detail: Some("pid: no p95_latency_ms SLO, using the heuristic".to_string()),
+            ..heuristic(obs, state)
+        };
+    };
+    let error = (obs.invoke_p95_ms as f64 - setpoint as f64) / setpoint as f64;
+    let dt = state
+        .last_at
+        .map(|at| (now - at) as f64)
+        .filter(|dt| *dt > 0.0);
+    let mut derivative = 0.0;
+    if let Some(dt) = dt {
+        state.integral = (state.integral + error * dt).clamp(-p.integral_limit, p.integral_limit);
+        if let Some(last) = state.last_error {
+            derivative = (error - last) / dt;
+        }
+    }
+    state.last_error = Some(error);
+    state.last_at = Some(now);
+    let output = p.kp * error + p.ki * state.integral + p.kd * derivative;
+    let from = current_target.unwrap_or(obs.active_compute_units).max(1);
+    let base_target = (from as f64 * (1.0 + output)).ceil().max(0.0) as i64;
+    Recommendation {
+        base_target,
+        queue_boost: 1.0,
+        detail: Some(format!(
+            "pid: invoke_p95_ms {} vs {}, error {:.2}, output {:+.2}, {} -> {}",
+            obs.invoke_p95_ms, setpoint, error, output, from, base_target
+        )),
+        state,
+    }
+}
+
+/// Base target for `obs` under `strategy`, and the state to keep for the
+/// next tick. `current_target` is the workload's stored intent, if any.
+pub fn recommend(
+    strategy: &ScalingStrategy,
+    obs: &WorkloadObservation,
+    p95_latency_ms: Option<u32>,
+    current_target: Option<u32>,
+    state: StrategyState,
+    now: i64,
+) -> Recommendation {
+    match strategy {
+        ScalingStrategy::Heuristic => heuristic(obs, state),
+        ScalingStrategy::TargetTracking(t) => target_tracking(t, obs, state, now),
+        ScalingStrategy::Pid(p) => pid(p, obs, p95_latency_ms, current_target, state, now),
+    }
+}
+
+/// State to keep when the decision built on `recommended` was held back by
+/// cooldown or hysteresis: the clock moves on but the PID integral stays put,
+/// so it can't wind up while the target is held and jump once it is not.
+pub fn held_state(previous: &StrategyState, recommended: &StrategyState) -> StrategyState {
+    StrategyState {
+        integral: previous.integral,
+        ..recommended.clone()
+    }
+}
+
+pub fn check(strategy: &ScalingStrategy, v: &mut Validator, prefix: &str) {
+    let field = |name: &str| format!("{prefix}{name}");
+    match strategy {
+        ScalingStrategy::Heuristic => {}
+        ScalingStrategy::TargetTracking(t) => {
+            v.check(
+                t.target_per_instance.is_finite() && t.target_per_instance > 0.0,
+                &field("target_per_instance"),
+                "must be > 0",
+            );
+        }
+        ScalingStrategy::Pid(p) => {
+            v.check(
+                [p.kp, p.ki, p.kd]
+                    .iter()
+                    .all(|g| g.is_finite() && *g >= 0.0),
+                &field("kp"),
+                "gains must be >= 0",
+            )
+            .check(
+                p.integral_limit.is_finite() && p.integral_limit > 0.0,
+                &field("integral_limit"),
+                "must be > 0",
+            );
+        }
+    }
+}
+
+/// State kept for the workload's `strategy`; default when there is none or
+/// it belongs to a different strategy.
+pub fn load_state_tx(
+    conn: &Connection,
+    tenant_id: &str,
+    workload_id: &str,
+    strategy: &str,
+) -> Result<StrategyState> {
+    let row: Option<(String, String)> = conn
+        .query_row(
+            "SELECT strategy, state_json FROM orchestrator_scaling_state
+             WHERE tenant_id = ?1 AND workload_id = ?2",
+            params![tenant_id, workload_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+    Ok(row
+        .filter(|(kind, _)| kind == strategy)
+        .and_then(|(_, raw)| serde_json::from_str(&raw).ok())
+        .unwrap_or_default())
+}
+
+pub fn save_state_tx(
+    conn: &Connection,
+    tenant_id: &str,
+    workload_id: &str,
+    strategy: &str,
+    state: &StrategyState,
+    now: i64,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO orchestrator_scaling_state (tenant_id, workload_id, strategy, state_json, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(tenant_id, workload_id) DO UPDATE SET
+           strategy=excluded.strategy,
+           state_json=excluded.state_json,
+           updated_at=excluded.updated_at",
+        params![
+            tenant_id,
+            workload_id,
+            strategy,
+            serde_json::to_string(state)?,
+            now
+        ],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn observation(
+        active_compute_units: u32,
+        queue_depth: u32,
+        invoke_p95_ms: u32,
+    ) -> WorkloadObservation {
+        WorkloadObservation {
+            queue_depth,
+            invoke_p95_ms,
+            active_compute_units,
+            ..WorkloadObservation::fixture()
+        }
+    }
+
+    #[test]
+    fn target_tracking_divides_load_and_stabilizes_scale_in() {
+        let strategy = ScalingStrategy::TargetTracking(TargetTrackingStrategy {
+            target_per_instance: 4.0,
+            scale_in_stabilization_seconds: 60,
+        });
+        let first = recommend(
+            &strategy,
+            &observation(10, 10, 0),
+            None,
+            None,
+            StrategyState::default(),
+            100,
+        );
+        assert_eq!(first.base_target, 5);
+        let lower = recommend(
+            &strategy,
+            &observation(4, 0, 0),
+            None,
+            None,
+            first.state,
+            130,
+        );
+        assert_eq!(lower.base_target, 5);
+        let later = recommend(
+            &strategy,
+            &observation(4, 0, 0),
+            None,
+            None,
+            lower.state,
+            161,
+        );
+        assert_eq!(later.base_target, 1);
+        assert_eq!(later.state.recommendations, [(130, 1), (161, 1)]);
+    }
+
+    #[test]
+    fn pid_scales_with_latency_error_and_bounds_windup() {
+        let strategy = ScalingStrategy::Pid(PidStrategy {
+            kp: 0.5,
+            ki: 0.1,
+            kd: 0.0,
+            integral_limit: 2.0,
+        });
+        let slow = observation(4, 0, 1000);
+        let first = recommend(
+            &strategy,
+            &slow,
+            Some(500),
+            Some(4),
+            StrategyState::default(),
+            100,
+        );
+        // No elapsed time yet: proportional term only, 4 * 1.5.
+        assert_eq!(first.base_target, 6);
+        let second = recommend(&strategy, &slow, Some(500), Some(6), first.state, 110);
+        assert_eq!(second.state.integral, 2.0);
+        // 6 * (1 + 0.5 + 0.1 * 2).
+        assert_eq!(second.base_target, 11);
+
+        let fast = recommend(
+            &strategy,
+            &observation(4, 0, 250),
+            Some(500),
+            Some(8),
+            second.state,
+            115,
+        );
+        assert!(fast.base_target < 8, "{}", fast.base_target);
+
+        let no_slo = recommend(
+            &strategy,
+            &slow,
+            None,
+            Some(4),
+            StrategyState::default(),
+            100,
+        );
+        assert_eq!(no_slo.base_target, 4);
+        assert_eq!(no_slo.state, StrategyState::default());
+    }
+
+    #[test]
+    fn state_is_reset_when_the_strategy_changes() {
+        let conn = crate::db::test_conn();
+        let state = StrategyState {
+            integral: 1.5,
+            last_at: Some(100),
+            ..Default::default()
+        };
+        save_state_tx(&conn, "t", "w", "pid", &state, 100).expect("save");
+        assert_eq!(load_state_tx(&conn, "t", "w", "pid").expect("load"), state);
+        assert_eq!(
+            load_state_tx(&conn, "t", "w", "target_tracking").expect("load"),
+            StrategyState::default()
+        );
+    }
+}
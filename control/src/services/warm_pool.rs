@@ -0,0 +1,271 @@
+//! Adaptive warm pool sizing for policies that opt in.
+//!
+//! The controller keeps one number per workload: an adjustment added to
+//! each of the tuning's warm pool ratios. It steps up on every tick with a
+//! grow signal (cold starts over the SLO, or a low warm hit rate on the
+//! workload's node group) and steps down once the pool has sat unused for
+//! `shrink_after_ticks` ticks: as many instances warm on the node group as
+//! the workload's pool target, and no cold starts. The adjustment never
+//! leaves the range that keeps the pool target within the policy's bounds.
+//! Its state lives on the workload's intent row next to `idle_since`.
+
+use anyhow::Result;
+
+use crate::db::{params, Connection};
+use crate::services::error::Validator;
+use crate::types::{AdaptiveWarmPool, NodeGroupObservation, WorkloadObservation};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WarmPoolState {
+    /// Added to each warm pool ratio, within [`adjustment_range`].
+    pub adjustment: f64,
+    /// Consecutive ticks the pool was idle.
+    pub idle_ticks: u32,
+}
+
+/// Adjustments that keep the pool target of a workload running
+/// `target_concurrency` units within the policy's bounds, given the
+/// tuning's `target_ratio`.
+pub fn adjustment_range(
+    cfg: &AdaptiveWarmPool,
+    target_ratio: f64,
+    target_concurrency: u32,
+) -> (f64, f64) {
+    let (mut lo, mut hi) = (-target_ratio, 1.0 - target_ratio);
+    if target_concurrency > 0 {
+        let units = target_concurrency as f64;
+        lo = lo.max(cfg.min_ready as f64 / units - target_ratio);
+        hi = hi.min(cfg.max_ready as f64 / units - target_ratio);
+    }
+    (lo.min(hi), hi)
+}
+
+/// Advance the controller one tick. `pool_target_ready` is the workload's
+/// current pool target and `range` comes from [`adjustment_range`]. Returns
+/// the new state and, when the adjustment moved, why.
+pub fn step(
+    cfg: &AdaptiveWarmPool,
+    state: WarmPoolState,
+    obs: &WorkloadObservation,
+    group: Option<&NodeGroupObservation>,
+    max_cold_start_pct: Option<f64>,
+    pool_target_ready: u32,
+    (lo, hi): (f64, f64),
+) -> (WarmPoolState, Option<String>) {
+    let moved = |adjustment: f64, why: String| {
+        let next = WarmPoolState {
+            adjustment,
+            idle_ticks: 0,
+        };
+        (next, (adjustment != state.adjustment).then_some(why))
+    };
+    let mut grow = Vec::new();
+    if let Some(max) = max_cold_start_pct.filter(|max| obs.cold_start_pct > *max) {
+        grow.push(format!("cold_start_pct {} > {}", obs.cold_start_pct, max));
+    }
+    if let Some(rate) = group
+        .map(|g| g.warm_hit_rate)
+        .filter(|rate| *rate < cfg.min_warm_hit_rate)
+    {
+        grow.push(format!(
+            "warm_hit_rate {:.2} < {:.2}",
+            rate, cfg.min_warm_hit_rate
+        ));
+    }
+    if !grow.is_empty() {
+        let adjustment = (state.adjustment + cfg.step).clamp(lo, hi);
+        return moved(
+            adjustment,
+            format!("grow to {:+.2}: {}", adjustment, grow.join("; ")),
+        );
+    }
+    let warm_ready = group.map_or(0, |g| g.warm_ready);
+    let unused =
+        pool_target_ready > 0 && warm_ready >= pool_target_ready && obs.cold_start_pct == 0.0;
+    if !unused {
+        return (
+            WarmPoolState {
+                adjustment: state.adjustment.clamp(lo, hi),
+                idle_ticks: 0,
+            },
+            None,
+        );
+    }
+    let idle_ticks = state.idle_ticks + 1;
+    if idle_ticks < cfg.shrink_after_ticks {
+        return (
+            WarmPoolState {
+                adjustment: state.adjustment.clamp(lo, hi),
+                idle_ticks,
+            },
+            None,
+        );
+    }
+    let adjustment = (state.adjustment - cfg.step).clamp(lo, hi);
+    moved(
+        adjustment,
+        format!(
+            "shrink to {:+.2}: {} warm for a pool target of {} over {} ticks",
+            adjustment, warm_ready, pool_target_ready, idle_ticks
+        ),
+    )
+}
+
+pub fn check(cfg: &AdaptiveWarmPool, v: &mut Validator, prefix: &str) {
+    let field = |name: &str| format!("{prefix}{name}");
+    v.check(
+        cfg.max_ready >= cfg.min_ready,
+        &field("max_ready"),
+        format!("must be >= min_ready ({})", cfg.min_ready),
+    )
+    .check(
+        (0.0..=1.0).contains(&cfg.min_warm_hit_rate),
+        &field("min_warm_hit_rate"),
+        "must be between 0 and 1",
+    )
+    .check(
+        cfg.step > 0.0 && cfg.step <= 1.0,
+        &field("step"),
+        "must be in (0, 1]",
+    )
+    .check(
+        cfg.shrink_after_ticks >= 1,
+        &field("shrink_after_ticks"),
+        "must be >= 1",
+    );
+}
+
+pub fn save_state_tx(
+    conn: &Connection,
+    tenant_id: &str,
+    workload_id: &str,
+    state: &WarmPoolState,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE orchestrator_intent SET warm_pool_adjustment = ?3, warm_pool_idle_ticks = ?4
+         WHERE tenant_id = ?1 AND workload_id = ?2",
+        params![tenant_id, workload_id, state.adjustment, state.idle_ticks],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn observation(queue_depth: u32, cold_start_pct: f64) -> WorkloadObservation {
+        WorkloadObservation {
+            queue_depth,
+            cold_start_pct,
+            active_compute_units: 4,
+            ..WorkloadObservation::fixture()
+        }
+    }
+
+    fn group(warm_ready: u32, warm_hit_rate: f64) -> NodeGroupObservation {
+        NodeGroupObservation {
+            node_group: "g".to_string(),
+            cpu_pressure: 0.5,
+            mem_pressure: 0.5,
+            io_pressure: 0.1,
+            warm_ready,
+            warm_hit_rate,
+            capacity_units: 20,
+            used_units: 10,
+        }
+    }
+
+    #[test]
+    fn grows_on_cold_starts_or_misses_and_shrinks_when_unused() {
+        let cfg = AdaptiveWarmPool {
+            step: 0.1,
+            shrink_after_ticks: 2,
+            ..Default::default()
+        };
+        let range = (-1.0, 1.0);
+        let (state, why) = step(
+            &cfg,
+            WarmPoolState::default(),
+            &observation(0, 8.0),
+            Some(&group(0, 0.95)),
+            Some(5.0),
+            2,
+            range,
+        );
+        assert_eq!(state.adjustment, 0.1);
+        assert!(why.expect("grew").contains("cold_start_pct 8 > 5"));
+        let (state, _) = step(
+            &cfg,
+            state,
+            &observation(3, 1.0),
+            Some(&group(0, 0.5)),
+            Some(5.0),
+            2,
+            range,
+        );
+        assert!((state.adjustment - 0.2).abs() < 1e-9);
+
+        // An idle workload whose pool is drawn down is not shrunk.
+        let (state, why) = step(
+            &cfg,
+            state,
+            &observation(0, 0.0),
+            Some(&group(1, 0.95)),
+            Some(5.0),
+            2,
+            range,
+        );
+        assert_eq!((state.idle_ticks, why), (0, None));
+
+        let unused = group(4, 0.95);
+        let (state, why) = step(
+            &cfg,
+            state,
+            &observation(3, 0.0),
+            Some(&unused),
+            None,
+            2,
+            range,
+        );
+        assert_eq!((state.idle_ticks, why), (1, None));
+        let (state, why) = step(
+            &cfg,
+            state,
+            &observation(3, 0.0),
+            Some(&unused),
+            None,
+            2,
+            range,
+        );
+        assert!((state.adjustment - 0.1).abs() < 1e-9);
+        assert_eq!(state.idle_ticks, 0);
+        assert!(why.expect("shrank").starts_with("shrink"));
+    }
+
+    #[test]
+    fn adjustment_stays_within_policy_bounds() {
+        let cfg = AdaptiveWarmPool {
+            min_ready: 1,
+            max_ready: 4,
+            step: 0.5,
+            ..Default::default()
+        };
+        // Pool target 0.2 * 10 = 2 may move between 1 and 4 instances.
+        let range = adjustment_range(&cfg, 0.2, 10);
+        assert!((range.0 + 0.1).abs() < 1e-9 && (range.1 - 0.2).abs() < 1e-9);
+        let (state, why) = step(
+            &cfg,
+            WarmPoolState::default(),
+            &observation(0, 8.0),
+            None,
+            Some(5.0),
+            2,
+            range,
+        );
+        assert!((state.adjustment - 0.2).abs() < 1e-9);
+        assert!(why.is_some());
+        // Already at the bound: no further wind-up, and nothing to report.
+        let (again, why) = step(&cfg, state, &observation(0, 8.0), None, Some(5.0), 4, range);
+        assert_eq!((again, why), (state, None));
+    }
+}
@@ -3,6 +3,35 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HealthResponse {
     pub status: String,
+    pub elasticity_control: BreakerStatus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakerState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+impl BreakerState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BreakerState::Closed => "closed",
+            BreakerState::Open => "open",
+            BreakerState::HalfOpen => "half_open",
+        }
+    }
+}
+
+/// Circuit breaker around the elasticity control API, as seen by this
+/// instance.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BreakerStatus {
+    pub state: BreakerState,
+    pub consecutive_failures: u32,
+    /// When the breaker last opened; `None` while closed.
+    pub opened_at: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,6 +48,115 @@ pub struct WorkloadPolicyRequest {
     pub burst_mem_mb: u32,
     pub burst_ttl_seconds: u32,
     pub target_container_ids: Vec<String>,
+    /// Opt-in scale to zero: seconds without queue depth or active units
+    /// after which the workload's target and warm pool drop to zero. Queue
+    /// depth reported while at zero wakes it on the next fast loop tick, so
+    /// the first requests wait up to `fast_loop_seconds` for capacity.
+    #[serde(default)]
+    pub scale_to_zero_idle_seconds: Option<u32>,
+    #[serde(default)]
+    pub scaling_strategy: ScalingStrategy,
+    /// Opt-in adaptive warm pool; without it pools are fixed fractions of
+    /// the target.
+    #[serde(default)]
+    pub warm_pool: Option<AdaptiveWarmPool>,
+}
+
+/// Feedback control of the warm pool. The pool ratios of the orchestrator
+/// tuning are shifted up while cold starts exceed the SLO or the node
+/// group's warm hit rate is low, and back down after the pool has sat
+/// unused for a while.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AdaptiveWarmPool {
+    /// Bounds on every pool size, whatever the controller asks for.
+    pub min_ready: u32,
+    pub max_ready: u32,
+    /// Warm hit rate below which the pool grows.
+    pub min_warm_hit_rate: f64,
+    /// Ratio added or removed per adjustment.
+    pub step: f64,
+    /// Consecutive fast-loop ticks with the pool unused before it shrinks.
+    pub shrink_after_ticks: u32,
+}
+
+impl Default for AdaptiveWarmPool {
+    fn default() -> Self {
+        Self {
+            min_ready: 0,
+            max_ready: 100,
+            min_warm_hit_rate: 0.9,
+            step: 0.05,
+            shrink_after_ticks: 12,
+        }
+    }
+}
+
+/// How the fast loop turns a workload's observation into a base target,
+/// before the policy's quota and limit clamps.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ScalingStrategy {
+    /// Active units scaled up by queue depth.
+    #[default]
+    Heuristic,
+    TargetTracking(TargetTrackingStrategy),
+    Pid(PidStrategy),
+}
+
+impl ScalingStrategy {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ScalingStrategy::Heuristic => "heuristic",
+            ScalingStrategy::TargetTracking(_) => "target_tracking",
+            ScalingStrategy::Pid(_) => "pid",
+        }
+    }
+}
+
+/// Enough units that each carries `target_per_instance` of the observed
+/// load, counted as queued requests plus active units.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TargetTrackingStrategy {
+    pub target_per_instance: f64,
+    /// Scale in only to the highest recommendation of this many seconds.
+    pub scale_in_stabilization_seconds: u32,
+}
+
+impl Default for TargetTrackingStrategy {
+    fn default() -> Self {
+        Self {
+            target_per_instance: 1.0,
+            scale_in_stabilization_seconds: 60,
+        }
+    }
+}
+
+/// PID controller holding invoke p95 latency at the SLO's `p95_latency_ms`.
+/// The error is normalized by the setpoint; the output scales the current
+/// target.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PidStrategy {
+    pub kp: f64,
+    /// Per second of accumulated error.
+    pub ki: f64,
+    /// Per unit of error change per second.
+    pub kd: f64,
+    /// Bound on the accumulated error, in error-seconds, against windup.
+    pub integral_limit: f64,
+}
+
+impl Default for PidStrategy {
+    fn default() -> Self {
+        Self {
+            kp: 0.3,
+            ki: 0.02,
+            kd: 0.0,
+            integral_limit: 10.0,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,6 +168,34 @@ pub struct WorkloadSloRequest {
     pub max_cost_per_compute_unit: f64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkloadPolicy {
+    pub tenant_id: String,
+    pub workload_id: String,
+    #[serde(flatten)]
+    pub policy: WorkloadPolicyRequest,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkloadPolicyListResponse {
+    pub policies: Vec<WorkloadPolicy>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkloadSlo {
+    pub tenant_id: String,
+    pub workload_id: String,
+    #[serde(flatten)]
+    pub slo: WorkloadSloRequest,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkloadSloListResponse {
+    pub slos: Vec<WorkloadSlo>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkloadObservation {
     pub tenant_id: String,
@@ -46,6 +212,28 @@ pub struct WorkloadObservation {
     pub cost_per_compute_unit: f64,
 }
 
+#[cfg(test)]
+impl WorkloadObservation {
+    /// A quiet sample for workload `t`/`w` in group `g`. Tests override the
+    /// fields they care about with struct update syntax.
+    pub(crate) fn fixture() -> Self {
+        WorkloadObservation {
+            tenant_id: "t".to_string(),
+            workload_id: "w".to_string(),
+            node_group: "g".to_string(),
+            queue_depth: 0,
+            cpu_pressure: 0.5,
+            mem_pressure: 0.5,
+            io_pressure: 0.1,
+            cold_start_pct: 0.0,
+            invoke_p95_ms: 100,
+            reject_pct: 0.0,
+            active_compute_units: 1,
+            cost_per_compute_unit: 1.0,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NodeGroupObservation {
     pub node_group: String,
@@ -111,6 +299,109 @@ pub struct OrchestratorAction {
     pub updated_at: i64,
 }
 
+/// Entry in the persisted orchestrator event log, streamed over SSE.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrchestratorEvent {
+    pub seq: i64,
+    pub event_type: String,
+    pub tenant_id: String,
+    pub workload_id: String,
+    pub action_id: Option<String>,
+    pub action_type: Option<String>,
+    pub status: Option<String>,
+    pub reason_code: Option<String>,
+    pub data: serde_json::Value,
+    pub created_at: i64,
+}
+
+/// A condition the fast loop found true while evaluating a workload.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecisionCondition {
+    pub code: String,
+    pub detail: String,
+}
+
+/// A bound that changed the target concurrency.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecisionClamp {
+    pub rule: String,
+    pub before: i64,
+    pub after: i64,
+}
+
+/// Why the fast loop proposed (and applied or held back) an intent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecisionTrace {
+    pub decision_id: i64,
+    pub tenant_id: String,
+    pub workload_id: String,
+    /// Aggregated over the observation window, not the last sample.
+    pub observation: WorkloadObservation,
+    pub pressure: f64,
+    pub queue_boost: f64,
+    /// Target before any clamp, as recommended by the scaling strategy.
+    pub base_target: i64,
+    pub current_target: Option<u32>,
+    pub conditions: Vec<DecisionCondition>,
+    pub clamps: Vec<DecisionClamp>,
+    pub proposed: OrchestratorIntent,
+    /// `cooldown` or `hysteresis` when the proposal was not applied.
+    pub suppressed_by: Option<String>,
+    pub applied: bool,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecisionListResponse {
+    pub decisions: Vec<DecisionTrace>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulatedPolicy {
+    pub tenant_id: String,
+    pub workload_id: String,
+    #[serde(flatten)]
+    pub policy: WorkloadPolicyRequest,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulatedSlo {
+    pub tenant_id: String,
+    pub workload_id: String,
+    #[serde(flatten)]
+    pub slo: WorkloadSloRequest,
+}
+
+/// Hypothetical state layered over the stored policies, SLOs and
+/// observations for a dry run of the fast and slow loops.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SimulationRequest {
+    #[serde(default)]
+    pub policies: Vec<SimulatedPolicy>,
+    #[serde(default)]
+    pub slos: Vec<SimulatedSlo>,
+    #[serde(default)]
+    pub workloads: Vec<WorkloadObservation>,
+    #[serde(default)]
+    pub node_groups: Vec<NodeGroupObservation>,
+}
+
+/// What the loops would have written. Nothing here is persisted, so action
+/// ids are throwaway and decision ids are 0.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulationResponse {
+    pub intents: Vec<OrchestratorIntent>,
+    pub actions: Vec<OrchestratorAction>,
+    pub decisions: Vec<DecisionTrace>,
+}
+
+/// One field-level validation failure, reported in API error `details`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActionResultRequest {
     pub status: String,
@@ -123,7 +414,125 @@ pub struct ActionListResponse {
     pub actions: Vec<OrchestratorAction>,
 }
 
+/// A failed dispatch or poll of an action.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionAttempt {
+    pub action_id: String,
+    pub attempt: u32,
+    /// Action status when the attempt failed.
+    pub status: String,
+    pub reason_code: String,
+    pub reason_message: Option<String>,
+    pub attempted_at: i64,
+    /// `None` for the attempt after which the action was dead-lettered.
+    pub next_retry_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetterAction {
+    pub action: OrchestratorAction,
+    pub attempts: Vec<ActionAttempt>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetterListResponse {
+    pub dead_letters: Vec<DeadLetterAction>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IntentListResponse {
     pub intents: Vec<OrchestratorIntent>,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegisterNodeRequest {
+    pub hostname: String,
+    pub host_ip: String,
+    pub cpu_cores: Option<u32>,
+    pub ram_mb: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegisterNodeResponse {
+    pub node_id: String,
+    pub subnet: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Node {
+    pub node_id: String,
+    pub hostname: String,
+    pub host_ip: String,
+    pub subnet: String,
+    pub cpu_cores: Option<u32>,
+    pub ram_mb: Option<u64>,
+    pub status: String,
+    pub registered_at: i64,
+    pub last_heartbeat: i64,
+    pub status_changed_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListNodesResponse {
+    pub nodes: Vec<Node>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeTransition {
+    pub seq: i64,
+    pub node_id: String,
+    pub from_status: Option<String>,
+    pub to_status: String,
+    pub reason: String,
+    pub transitioned_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeTransitionListResponse {
+    pub transitions: Vec<NodeTransition>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaderStatusResponse {
+    pub instance_id: String,
+    pub is_leader: bool,
+    pub leader_id: Option<String>,
+    pub lease_acquired_at: Option<i64>,
+    pub lease_renewed_at: Option<i64>,
+    pub lease_expires_at: Option<i64>,
+    /// Seconds since the current leader acquired the lease.
+    pub lease_age_seconds: Option<i64>,
+    pub seconds_since_renewal: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateApiKeyRequest {
+    /// `admin` or `tenant`.
+    pub role: String,
+    /// Required for `tenant` keys, absent for `admin` keys.
+    pub tenant_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateApiKeyResponse {
+    pub key_id: String,
+    /// Plaintext key. Only returned here; the control plane stores a hash.
+    pub api_key: String,
+    pub role: String,
+    pub tenant_id: Option<String>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKey {
+    pub key_id: String,
+    pub role: String,
+    pub tenant_id: Option<String>,
+    pub created_at: i64,
+    pub revoked_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeyListResponse {
+    pub api_keys: Vec<ApiKey>,
+}
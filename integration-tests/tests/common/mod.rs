@@ -66,11 +66,19 @@ pub struct TestCluster {
     agents: Vec<Child>,
     _temp_dir: TempDir,
     db_path: PathBuf,
+    control_args: Vec<String>,
 }
 
 impl TestCluster {
     /// Start a control plane on an ephemeral port
     pub async fn new() -> Self {
+        Self::with_control_args(&[]).await
+    }
+
+    /// Start a control plane with extra command-line arguments, which are
+    /// also passed on restart
+    pub async fn with_control_args(extra: &[&str]) -> Self {
+        let control_args: Vec<String> = extra.iter().map(ToString::to_string).collect();
         let port = find_free_port();
         let temp_dir = TempDir::new().expect("Failed to create temp dir");
         let db_path = temp_dir.path().join("control.db");
@@ -79,6 +87,7 @@ impl TestCluster {
             .args(["--bind", &format!("127.0.0.1:{}", port)])
             .args(["--db-path", db_path.to_str().unwrap()])
             .args(["--log-level", "debug"])
+            .args(&control_args)
             .stdout(std::process::Stdio::null())
             .stderr(std::process::Stdio::null())
             .spawn()
@@ -93,6 +102,7 @@ impl TestCluster {
             agents: Vec::new(),
             db_path,
             _temp_dir: temp_dir,
+            control_args,
         }
     }
 
@@ -158,6 +168,7 @@ impl TestCluster {
             .args(["--bind", &format!("127.0.0.1:{}", port)])
             .args(["--db-path", self.db_path.to_str().unwrap()])
             .args(["--log-level", "debug"])
+            .args(&self.control_args)
             .stdout(std::process::Stdio::null())
             .stderr(std::process::Stdio::null())
             .spawn()
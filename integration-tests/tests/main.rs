@@ -4,4 +4,5 @@ mod concurrent_registration;
 mod control_restart;
 mod graceful_shutdown;
 mod node_lifecycle;
+mod orchestrator_dispatch;
 mod peer_discovery;
@@ -0,0 +1,160 @@
+use crate::common::TestCluster;
+use quilt_mock_elasticity::{Endpoint, MockElasticity, Outcome, Rule, RC_INVALID_ARGUMENT};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::time::Duration;
+
+const ADMIN_KEY: &str = "integration-admin";
+
+#[tokio::test]
+async fn test_orchestrator_dispatches_to_mock_elasticity() {
+    let mock = MockElasticity::new(Outcome::Succeed);
+    mock.push_rule(Rule {
+        endpoint: Some(Endpoint::Resize),
+        target: None,
+        outcome: Outcome::Hang,
+        times: None,
+    });
+    mock.push_rule(Rule {
+        endpoint: Some(Endpoint::PlacementPreference),
+        target: None,
+        outcome: Outcome::Fail {
+            reason_code: RC_INVALID_ARGUMENT.to_string(),
+            retryable: false,
+            reason_message: None,
+        },
+        times: None,
+    });
+    let (mock_addr, _server) = mock
+        .clone()
+        .spawn("127.0.0.1:0".parse().unwrap())
+        .await
+        .expect("Failed to start mock elasticity");
+
+    let cluster = TestCluster::with_control_args(&[
+        "--admin-api-key",
+        ADMIN_KEY,
+        "--control-base-url",
+        &format!("http://{}", mock_addr),
+    ])
+    .await;
+    let client = reqwest::Client::new();
+    let url = cluster.control_url();
+
+    let resp = client
+        .put(format!(
+            "{}/v1/orchestrator/tenants/t1/workloads/w1/policy",
+            url
+        ))
+        .header("X-Api-Key", ADMIN_KEY)
+        .json(&json!({
+            "runtime_function_id": "fn-1",
+            "max_concurrency": 2,
+            "hard_quota": 10,
+            "soft_burst": 2,
+            "absolute_limit": 20,
+            "priority": 5,
+            "cooldown_seconds": 30,
+            "hysteresis_pct": 10.0,
+            "burst_cpu_cap": 1.5,
+            "burst_mem_mb": 512,
+            "burst_ttl_seconds": 60,
+            "target_container_ids": ["c1"]
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert!(resp.status().is_success(), "policy: {}", resp.status());
+
+    let resp = client
+        .post(format!("{}/v1/orchestrator/observations", url))
+        .header("X-Api-Key", ADMIN_KEY)
+        .json(&json!({
+            "workloads": [{
+                "tenant_id": "t1",
+                "workload_id": "w1",
+                "node_group": "g1",
+                "queue_depth": 50,
+                "cpu_pressure": 0.9,
+                "mem_pressure": 0.5,
+                "io_pressure": 0.1,
+                "cold_start_pct": 0.0,
+                "invoke_p95_ms": 900,
+                "reject_pct": 0.0,
+                "active_compute_units": 1,
+                "cost_per_compute_unit": 1.0
+            }],
+            "node_groups": []
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert!(
+        resp.status().is_success(),
+        "observations: {}",
+        resp.status()
+    );
+
+    let resp = client
+        .post(format!("{}/v1/orchestrator/loops/fast:run", url))
+        .header("X-Api-Key", ADMIN_KEY)
+        .send()
+        .await
+        .unwrap();
+    assert!(resp.status().is_success(), "fast loop: {}", resp.status());
+
+    // The dispatch loop runs every couple of seconds on the leader.
+    let expected = HashMap::from([
+        ("SetPoolTarget", "succeeded"),
+        ("SetBurstPolicy", "running"),
+        ("SetPlacementPreference", "failed"),
+    ]);
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(20);
+    let statuses = loop {
+        let body: Value = client
+            .get(format!("{}/v1/orchestrator/actions", url))
+            .header("X-Api-Key", ADMIN_KEY)
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let statuses: HashMap<String, String> = body["actions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|a| {
+                (
+                    a["action_type"].as_str().unwrap().to_string(),
+                    a["status"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect();
+        let done = expected
+            .iter()
+            .all(|(t, s)| statuses.get(*t).map(String::as_str) == Some(*s));
+        if done || tokio::time::Instant::now() > deadline {
+            break statuses;
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    };
+    for (action_type, status) in &expected {
+        assert_eq!(
+            statuses.get(*action_type).map(String::as_str),
+            Some(*status),
+            "{} in {:?}",
+            action_type,
+            statuses
+        );
+    }
+
+    let calls = mock.calls();
+    let pool = calls
+        .iter()
+        .find(|c| c.endpoint == Endpoint::PoolTarget)
+        .expect("pool-target call");
+    assert_eq!(pool.target, "fn-1");
+    assert_eq!(pool.tenant_id.as_deref(), Some("t1"));
+    assert!(pool.idempotency_key.is_some());
+}
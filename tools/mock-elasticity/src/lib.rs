@@ -0,0 +1,533 @@
+//! In-memory implementation of the elasticity control API the orchestrator
+//! dispatches to, for running the control plane end to end without a real
+//! runtime.
+//!
+//! Every mutating call creates an operation whose fate is decided by the
+//! first matching [`Rule`], falling back to the default [`Outcome`]. Rules
+//! can be installed from code, from a JSON file at startup, or at runtime
+//! through the `/mock/*` admin endpoints.
+
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post, put},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use tracing::info;
+use uuid::Uuid;
+
+pub const RC_TARGET_NOT_FOUND: &str = "ELASTICITY_TARGET_NOT_FOUND";
+pub const RC_INVALID_ARGUMENT: &str = "ELASTICITY_INVALID_ARGUMENT";
+pub const RC_RESOURCE_PRESSURE: &str = "ELASTICITY_RESOURCE_PRESSURE";
+pub const RC_IDEMPOTENCY_REPLAY: &str = "ELASTICITY_IDEMPOTENCY_REPLAY";
+
+/// Mutating endpoints of the control contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Endpoint {
+    PoolTarget,
+    Resize,
+    PlacementPreference,
+    Scale,
+    Rollback,
+}
+
+impl Endpoint {
+    fn operation_type(self) -> &'static str {
+        match self {
+            Endpoint::PoolTarget => "elasticity.set_pool_target",
+            Endpoint::Resize => "elasticity.resize_container",
+            Endpoint::PlacementPreference => "elasticity.set_placement_preference",
+            Endpoint::Scale => "elasticity.scale_node_group",
+            Endpoint::Rollback => "elasticity.rollback",
+        }
+    }
+}
+
+/// What happens to a call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum Outcome {
+    /// Accept the call; the operation reports `succeeded` when polled.
+    Succeed,
+    /// Reject the call with a reason code and retryable hint.
+    Fail {
+        #[serde(default = "default_fail_code")]
+        reason_code: String,
+        #[serde(default = "default_retryable")]
+        retryable: bool,
+        #[serde(default)]
+        reason_message: Option<String>,
+    },
+    /// Accept the call; the operation stays `running` until completed
+    /// through `PUT /mock/operations/:id`.
+    Hang,
+    /// Answer `409 ELASTICITY_IDEMPOTENCY_REPLAY` with a succeeded operation,
+    /// as if the call had been applied before.
+    Replay,
+}
+
+fn default_fail_code() -> String {
+    RC_RESOURCE_PRESSURE.to_string()
+}
+
+fn default_retryable() -> bool {
+    true
+}
+
+/// Applies `outcome` to calls matching `endpoint` and `target` (either may
+/// be left out to match anything), for the next `times` calls or forever.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rule {
+    #[serde(default)]
+    pub endpoint: Option<Endpoint>,
+    #[serde(default)]
+    pub target: Option<String>,
+    #[serde(flatten)]
+    pub outcome: Outcome,
+    #[serde(default)]
+    pub times: Option<u32>,
+}
+
+impl Rule {
+    fn matches(&self, endpoint: Endpoint, target: &str) -> bool {
+        self.endpoint.is_none_or(|e| e == endpoint)
+            && self.target.as_deref().is_none_or(|t| t == target)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Operation {
+    pub operation_id: String,
+    pub operation_type: String,
+    pub status: String,
+    pub reason_code: Option<String>,
+    pub reason_message: Option<String>,
+}
+
+/// A call as received, for assertions in tests.
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordedCall {
+    pub endpoint: Endpoint,
+    pub target: String,
+    pub idempotency_key: Option<String>,
+    pub tenant_id: Option<String>,
+    pub action_id: Option<String>,
+    pub body: Value,
+    pub status: u16,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CompleteOperation {
+    pub status: String,
+    #[serde(default)]
+    pub reason_code: Option<String>,
+    #[serde(default)]
+    pub reason_message: Option<String>,
+}
+
+struct Inner {
+    default: Outcome,
+    rules: Vec<Rule>,
+    operations: HashMap<String, Operation>,
+    by_idempotency_key: HashMap<String, String>,
+    calls: Vec<RecordedCall>,
+}
+
+#[derive(Clone)]
+pub struct MockElasticity {
+    inner: Arc<Mutex<Inner>>,
+    api_key: Option<String>,
+}
+
+impl MockElasticity {
+    pub fn new(default: Outcome) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                default,
+                rules: Vec::new(),
+                operations: HashMap::new(),
+                by_idempotency_key: HashMap::new(),
+                calls: Vec::new(),
+            })),
+            api_key: None,
+        }
+    }
+
+    /// Require `X-Api-Key` on every contract call.
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().expect("mock state poisoned")
+    }
+
+    /// Append a rule. Rules are tried in insertion order.
+    pub fn push_rule(&self, rule: Rule) {
+        self.lock().rules.push(rule);
+    }
+
+    pub fn rules(&self) -> Vec<Rule> {
+        self.lock().rules.clone()
+    }
+
+    pub fn clear_rules(&self) {
+        self.lock().rules.clear();
+    }
+
+    pub fn calls(&self) -> Vec<RecordedCall> {
+        self.lock().calls.clone()
+    }
+
+    pub fn operation(&self, operation_id: &str) -> Option<Operation> {
+        self.lock().operations.get(operation_id).cloned()
+    }
+
+    /// Move an operation (typically a hung one) to a new status.
+    pub fn complete(&self, operation_id: &str, update: CompleteOperation) -> Option<Operation> {
+        let mut inner = self.lock();
+        let op = inner.operations.get_mut(operation_id)?;
+        op.status = update.status;
+        op.reason_code = update.reason_code;
+        op.reason_message = update.reason_message;
+        Some(op.clone())
+    }
+
+    fn next_outcome(inner: &mut Inner, endpoint: Endpoint, target: &str) -> Outcome {
+        let Some(idx) = inner.rules.iter().position(|r| r.matches(endpoint, target)) else {
+            return inner.default.clone();
+        };
+        let rule = &mut inner.rules[idx];
+        let outcome = rule.outcome.clone();
+        if let Some(times) = &mut rule.times {
+            *times = times.saturating_sub(1);
+            if *times == 0 {
+                inner.rules.remove(idx);
+            }
+        }
+        outcome
+    }
+
+    fn new_operation(inner: &mut Inner, endpoint: Endpoint, status: &str) -> Operation {
+        let op = Operation {
+            operation_id: format!("op_{}", Uuid::new_v4().simple()),
+            operation_type: endpoint.operation_type().to_string(),
+            status: status.to_string(),
+            reason_code: None,
+            reason_message: None,
+        };
+        inner.operations.insert(op.operation_id.clone(), op.clone());
+        op
+    }
+
+    /// Handle one contract call and return the status and body to send.
+    /// A repeated `Idempotency-Key` replays the original operation without
+    /// consulting the rules, like the real service.
+    pub fn handle(
+        &self,
+        endpoint: Endpoint,
+        target: &str,
+        headers: &HeaderMap,
+        body: Value,
+    ) -> (StatusCode, Value) {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(ToString::to_string)
+        };
+        let idempotency_key = header("idempotency-key");
+        let mut inner = self.lock();
+
+        let replayed = idempotency_key
+            .as_ref()
+            .and_then(|key| inner.by_idempotency_key.get(key))
+            .and_then(|id| inner.operations.get(id))
+            .cloned();
+        let (status, response) = if let Some(op) = replayed {
+            replay_response(&op)
+        } else {
+            match Self::next_outcome(&mut inner, endpoint, target) {
+                Outcome::Fail {
+                    reason_code,
+                    retryable,
+                    reason_message,
+                } => (
+                    fail_status(&reason_code),
+                    json!({
+                        "reason_code": reason_code,
+                        "reason_message": reason_message
+                            .unwrap_or_else(|| format!("mock {} failure", endpoint.operation_type())),
+                        "retryable": retryable
+                    }),
+                ),
+                outcome => {
+                    let final_status = match outcome {
+                        Outcome::Hang => "running",
+                        _ => "succeeded",
+                    };
+                    let op = Self::new_operation(&mut inner, endpoint, final_status);
+                    if let Some(key) = &idempotency_key {
+                        inner
+                            .by_idempotency_key
+                            .insert(key.clone(), op.operation_id.clone());
+                    }
+                    if outcome == Outcome::Replay {
+                        replay_response(&op)
+                    } else {
+                        (
+                            StatusCode::ACCEPTED,
+                            json!({
+                                "operation_id": op.operation_id,
+                                "operation_type": op.operation_type,
+                                "status": "accepted"
+                            }),
+                        )
+                    }
+                }
+            }
+        };
+
+        info!(
+            "{:?} {} -> {} {}",
+            endpoint,
+            target,
+            status.as_u16(),
+            response
+        );
+        inner.calls.push(RecordedCall {
+            endpoint,
+            target: target.to_string(),
+            idempotency_key,
+            tenant_id: header("x-tenant-id"),
+            action_id: header("x-orch-action-id"),
+            body,
+            status: status.as_u16(),
+        });
+        (status, response)
+    }
+
+    /// Routes for the control contract plus the `/mock/*` admin API.
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route(
+                "/api/elasticity/control/functions/:id/pool-target",
+                post(|s, h, p, b| contract(Endpoint::PoolTarget, s, h, p, b)),
+            )
+            .route(
+                "/api/elasticity/control/containers/:id/resize",
+                post(|s, h, p, b| contract(Endpoint::Resize, s, h, p, b)),
+            )
+            .route(
+                "/api/elasticity/control/workloads/:id/placement-preference",
+                post(|s, h, p, b| contract(Endpoint::PlacementPreference, s, h, p, b)),
+            )
+            .route(
+                "/api/elasticity/control/node-groups/:id/scale",
+                post(|s, h, p, b| contract(Endpoint::Scale, s, h, p, b)),
+            )
+            .route(
+                "/api/elasticity/control/actions/:id/rollback",
+                post(|s, h, p, b| contract(Endpoint::Rollback, s, h, p, b)),
+            )
+            .route("/api/elasticity/control/operations/:id", get(get_operation))
+            .route(
+                "/mock/rules",
+                get(list_rules).post(add_rule).delete(clear_rules),
+            )
+            .route("/mock/calls", get(list_calls))
+            .route("/mock/operations/:id", put(complete_operation))
+            .with_state(self.clone())
+    }
+
+    pub async fn serve(self, listener: TcpListener) -> std::io::Result<()> {
+        axum::serve(listener, self.router()).await
+    }
+
+    /// Serve on `addr` in the background. Bind to port 0 for an ephemeral
+    /// port; the bound address is returned.
+    pub async fn spawn(
+        self,
+        addr: SocketAddr,
+    ) -> std::io::Result<(SocketAddr, JoinHandle<std::io::Result<()>>)> {
+        let listener = TcpListener::bind(addr).await?;
+        let local = listener.local_addr()?;
+        Ok((local, tokio::spawn(self.serve(listener))))
+    }
+
+    fn authorized(&self, headers: &HeaderMap) -> bool {
+        match &self.api_key {
+            Some(expected) => headers
+                .get("x-api-key")
+                .and_then(|v| v.to_str().ok())
+                .is_some_and(|v| v == expected),
+            None => true,
+        }
+    }
+}
+
+fn replay_response(op: &Operation) -> (StatusCode, Value) {
+    (
+        StatusCode::CONFLICT,
+        json!({
+            "operation_id": op.operation_id,
+            "operation_type": op.operation_type,
+            "status": "accepted",
+            "reason_code": RC_IDEMPOTENCY_REPLAY,
+            "reason_message": "request already applied",
+            "retryable": false
+        }),
+    )
+}
+
+fn fail_status(reason_code: &str) -> StatusCode {
+    match reason_code {
+        RC_INVALID_ARGUMENT => StatusCode::BAD_REQUEST,
+        RC_TARGET_NOT_FOUND => StatusCode::NOT_FOUND,
+        RC_RESOURCE_PRESSURE => StatusCode::TOO_MANY_REQUESTS,
+        _ => StatusCode::CONFLICT,
+    }
+}
+
+fn unauthorized() -> Response {
+    (
+        StatusCode::UNAUTHORIZED,
+        Json(json!({ "reason_code": "UNAUTHORIZED", "reason_message": "invalid api key" })),
+    )
+        .into_response()
+}
+
+async fn contract(
+    endpoint: Endpoint,
+    State(mock): State<MockElasticity>,
+    headers: HeaderMap,
+    Path(target): Path<String>,
+    Json(body): Json<Value>,
+) -> Response {
+    if !mock.authorized(&headers) {
+        return unauthorized();
+    }
+    let (status, body) = mock.handle(endpoint, &target, &headers, body);
+    (status, Json(body)).into_response()
+}
+
+async fn get_operation(
+    State(mock): State<MockElasticity>,
+    headers: HeaderMap,
+    Path(operation_id): Path<String>,
+) -> Response {
+    if !mock.authorized(&headers) {
+        return unauthorized();
+    }
+    match mock.operation(&operation_id) {
+        Some(op) => Json(op).into_response(),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(json!({
+                "reason_code": RC_TARGET_NOT_FOUND,
+                "reason_message": format!("unknown operation {operation_id}"),
+                "retryable": false
+            })),
+        )
+            .into_response(),
+    }
+}
+
+async fn list_rules(State(mock): State<MockElasticity>) -> Json<Vec<Rule>> {
+    Json(mock.rules())
+}
+
+async fn add_rule(State(mock): State<MockElasticity>, Json(rule): Json<Rule>) -> StatusCode {
+    mock.push_rule(rule);
+    StatusCode::CREATED
+}
+
+async fn clear_rules(State(mock): State<MockElasticity>) -> StatusCode {
+    mock.clear_rules();
+    StatusCode::NO_CONTENT
+}
+
+async fn list_calls(State(mock): State<MockElasticity>) -> Json<Vec<RecordedCall>> {
+    Json(mock.calls())
+}
+
+async fn complete_operation(
+    State(mock): State<MockElasticity>,
+    Path(operation_id): Path<String>,
+    Json(update): Json<CompleteOperation>,
+) -> Response {
+    match mock.complete(&operation_id, update) {
+        Some(op) => Json(op).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(mock: &MockElasticity, endpoint: Endpoint, key: &str) -> (StatusCode, Value) {
+        let mut headers = HeaderMap::new();
+        headers.insert("idempotency-key", key.parse().unwrap());
+        mock.handle(endpoint, "fn-1", &headers, json!({}))
+    }
+
+    #[test]
+    fn rules_apply_in_order_and_expire() {
+        let mock = MockElasticity::new(Outcome::Succeed);
+        mock.push_rule(
+            serde_json::from_value(json!({
+                "endpoint": "pool-target",
+                "outcome": "fail",
+                "times": 1
+            }))
+            .expect("rule"),
+        );
+
+        let (status, body) = call(&mock, Endpoint::PoolTarget, "k1");
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(body["reason_code"], RC_RESOURCE_PRESSURE);
+        assert_eq!(body["retryable"], true);
+        assert!(mock.rules().is_empty());
+
+        let (status, body) = call(&mock, Endpoint::PoolTarget, "k1");
+        assert_eq!(status, StatusCode::ACCEPTED);
+        let op = mock
+            .operation(body["operation_id"].as_str().unwrap())
+            .expect("operation");
+        assert_eq!(op.status, "succeeded");
+        assert_eq!(mock.calls().len(), 2);
+    }
+
+    #[test]
+    fn repeated_idempotency_key_replays_original_operation() {
+        let mock = MockElasticity::new(Outcome::Hang);
+        let (_, first) = call(&mock, Endpoint::Resize, "k1");
+        let (status, replay) = call(&mock, Endpoint::Resize, "k1");
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(replay["reason_code"], RC_IDEMPOTENCY_REPLAY);
+        assert_eq!(replay["operation_id"], first["operation_id"]);
+
+        let id = first["operation_id"].as_str().unwrap();
+        assert_eq!(mock.operation(id).unwrap().status, "running");
+        mock.complete(
+            id,
+            CompleteOperation {
+                status: "failed".to_string(),
+                reason_code: Some("ELASTICITY_OPERATION_FAILED".to_string()),
+                reason_message: None,
+            },
+        );
+        assert_eq!(mock.operation(id).unwrap().status, "failed");
+    }
+}
@@ -0,0 +1,63 @@
+use anyhow::{Context, Result};
+use clap::Parser;
+use quilt_mock_elasticity::{MockElasticity, Outcome, Rule};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use tracing::{info, Level};
+use tracing_subscriber::FmtSubscriber;
+
+#[derive(Parser, Debug)]
+#[command(name = "quilt-mock-elasticity")]
+#[command(about = "In-memory elasticity control API for local orchestrator testing")]
+struct Args {
+    /// Bind address
+    #[arg(long, default_value = "127.0.0.1:8090")]
+    bind: SocketAddr,
+
+    /// Outcome for calls no rule matches
+    #[arg(long, default_value = "succeed", value_parser = ["succeed", "fail", "hang", "replay"])]
+    default_outcome: String,
+
+    /// JSON file with an array of rules, e.g.
+    /// `[{"endpoint": "resize", "outcome": "hang"}]`
+    #[arg(long)]
+    rules: Option<PathBuf>,
+
+    /// Require this value in X-Api-Key
+    #[arg(long, env = "CONTROL_API_KEY", hide_env_values = true)]
+    api_key: Option<String>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    tracing::subscriber::set_global_default(
+        FmtSubscriber::builder()
+            .with_max_level(Level::INFO)
+            .finish(),
+    )?;
+
+    let default: Outcome =
+        serde_json::from_value(serde_json::json!({ "outcome": args.default_outcome }))?;
+    let mut mock = MockElasticity::new(default);
+    if let Some(api_key) = args.api_key {
+        mock = mock.with_api_key(api_key);
+    }
+    if let Some(path) = &args.rules {
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let rules: Vec<Rule> = serde_json::from_str(&raw)
+            .with_context(|| format!("invalid rules in {}", path.display()))?;
+        for rule in rules {
+            mock.push_rule(rule);
+        }
+    }
+
+    let listener = tokio::net::TcpListener::bind(args.bind).await?;
+    info!(
+        "Mock elasticity control listening on http://{}",
+        listener.local_addr()?
+    );
+    mock.serve(listener).await?;
+    Ok(())
+}